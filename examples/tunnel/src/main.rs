@@ -0,0 +1,91 @@
+//! TCP port forwarding over a Remoc connection.
+//!
+//! Start the server, allowing access to a database listening on port 5432:
+//!
+//!     cargo run --manifest-path examples/tunnel/Cargo.toml -- server 0.0.0.0:9875 127.0.0.1:5432
+//!
+//! Then start the client, making the database available on local port 15432:
+//!
+//!     cargo run --manifest-path examples/tunnel/Cargo.toml -- client server-host:9875 127.0.0.1:15432 127.0.0.1:5432
+//!
+//! The client prints the byte counts of each tunnel when it is closed.
+//! All commands assume that you are in the top-level repository directory.
+
+use remoc::{
+    prelude::*,
+    tunnel::{self, Allowlist, Connector, LocalForward},
+};
+use std::{env, process::exit};
+use tokio::net::{TcpListener, TcpStream};
+
+fn usage() -> ! {
+    eprintln!("Usage:");
+    eprintln!("  tunnel server <listen-addr> <allowed-target>...");
+    eprintln!("  tunnel client <server-addr> <local-addr> <target>");
+    exit(2);
+}
+
+/// Accepts Remoc connections and provides tunnels to the allowed targets.
+async fn server(listen_addr: &str, allowlist: Allowlist) {
+    let listener = TcpListener::bind(listen_addr).await.unwrap();
+    println!("Listening on {listen_addr}");
+
+    loop {
+        let (socket, addr) = listener.accept().await.unwrap();
+        println!("Accepted connection from {addr}");
+        let allowlist = allowlist.clone();
+
+        tokio::spawn(async move {
+            let (socket_rx, socket_tx) = socket.into_split();
+            let (conn, mut tx, _rx): (_, rch::base::Sender<Connector>, rch::base::Receiver<()>) =
+                match remoc::Connect::io(remoc::Cfg::default(), socket_rx, socket_tx).await {
+                    Ok(connected) => connected,
+                    Err(err) => {
+                        eprintln!("Connection from {addr} failed: {err}");
+                        return;
+                    }
+                };
+            tokio::spawn(conn);
+
+            let (dialer, connector) = tunnel::dialer(allowlist);
+            if tx.send(connector).await.is_err() {
+                return;
+            }
+            dialer.serve().await;
+            println!("Connection from {addr} closed");
+        });
+    }
+}
+
+/// Connects to the server and forwards connections on the local address to the target.
+async fn client(server_addr: &str, local_addr: &str, target: &str) {
+    let socket = TcpStream::connect(server_addr).await.unwrap();
+    let (socket_rx, socket_tx) = socket.into_split();
+    let (conn, _tx, mut rx): (_, rch::base::Sender<()>, rch::base::Receiver<Connector>) =
+        remoc::Connect::io(remoc::Cfg::default(), socket_rx, socket_tx).await.unwrap();
+    tokio::spawn(conn);
+    let connector = rx.recv().await.unwrap().expect("server provided no connector");
+
+    let listener = TcpListener::bind(local_addr).await.unwrap();
+    println!("Forwarding {local_addr} to {target} via {server_addr}");
+
+    let forward = LocalForward::new(listener, target, connector).on_close(|info| {
+        println!(
+            "Tunnel {} from {} closed: {} bytes sent, {} bytes received",
+            info.id, info.peer_addr, info.bytes_sent, info.bytes_received
+        )
+    });
+    forward.run().await;
+}
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["server", listen_addr, targets @ ..] if !targets.is_empty() => {
+            server(listen_addr, targets.iter().copied().collect()).await
+        }
+        ["client", server_addr, local_addr, target] => client(server_addr, local_addr, target).await,
+        _ => usage(),
+    }
+}
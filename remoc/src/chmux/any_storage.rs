@@ -22,6 +22,8 @@ type AnyMap = HashMap<Uuid, AnyEntry>;
 #[derive(Clone)]
 pub struct AnyStorage {
     entries: Arc<std::sync::Mutex<AnyMap>>,
+    #[cfg(feature = "rch")]
+    id: Arc<Uuid>,
 }
 
 impl fmt::Debug for AnyStorage {
@@ -34,7 +36,23 @@ impl fmt::Debug for AnyStorage {
 impl AnyStorage {
     /// Creates a new storage.
     pub(crate) fn new() -> Self {
-        Self { entries: Arc::new(std::sync::Mutex::new(AnyMap::new())) }
+        Self {
+            entries: Arc::new(std::sync::Mutex::new(AnyMap::new())),
+            #[cfg(feature = "rch")]
+            id: Arc::new(Uuid::new_v4()),
+        }
+    }
+
+    /// Unique id of the storage and thus of the channel multiplexer owning it.
+    #[cfg(feature = "rch")]
+    pub(crate) fn id(&self) -> Uuid {
+        *self.id
+    }
+
+    /// Returns a weak reference that is alive as long as the storage is.
+    #[cfg(feature = "rch")]
+    pub(crate) fn alive(&self) -> std::sync::Weak<Uuid> {
+        Arc::downgrade(&self.id)
     }
 
     /// Insert a new entry into the storage and return its key.
@@ -2,7 +2,10 @@
 
 use std::time::Duration;
 
-use super::msg::MAX_MSG_LENGTH;
+use super::{
+    metadata::{AcceptMetadata, Metadata},
+    msg::MAX_MSG_LENGTH,
+};
 
 /// Behavior when ports are exhausted and a connect is requested.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -30,6 +33,25 @@ pub struct Cfg {
     /// Pings are send automatically when this is enabled and no data is transmitted.
     /// By default this is 60 seconds.
     pub connection_timeout: Option<Duration>,
+    /// Time limit for the handshake with the remote endpoint when establishing a connection.
+    ///
+    /// If the remote endpoint does not complete the handshake within this time,
+    /// establishing the connection fails with [ChMuxError::Timeout](super::ChMuxError::Timeout)
+    /// or [ConnectError::Timeout](crate::ConnectError::Timeout) when using the
+    /// [connect functions](crate::Connect).
+    /// By default this is 60 seconds.
+    ///
+    /// Previously the handshake was limited by the [connection timeout](Self::connection_timeout).
+    /// If you have customized it, set this to the same value to keep the previous behavior.
+    #[cfg_attr(feature = "serde", serde(default = "default_timeout"))]
+    pub handshake_timeout: Option<Duration>,
+    /// Time limit for opening the initial [base channel](crate::rch::base) when establishing
+    /// a connection using the [connect functions](crate::Connect).
+    ///
+    /// If exceeded, establishing the connection fails with [ConnectError::Timeout](crate::ConnectError::Timeout).
+    /// By default this is 60 seconds.
+    #[cfg_attr(feature = "serde", serde(default = "default_timeout"))]
+    pub base_channel_timeout: Option<Duration>,
     /// Maximum number of open ports.
     ///
     /// This must not exceed 2^31 = 2147483648.
@@ -112,16 +134,51 @@ pub struct Cfg {
     /// By default this is 128.
     /// This must not be zero.
     pub connect_queue: u16,
+    /// Length of the [event](super::Event) queue of each subscriber.
+    ///
+    /// If a subscriber does not keep up, it will miss the oldest events.
+    /// By default this is 128.
+    /// This must not be zero.
+    #[cfg_attr(feature = "serde", serde(default = "default_event_queue"))]
+    pub event_queue: usize,
+    /// Metadata sent to the remote endpoint during the connection handshake.
+    ///
+    /// The encoded metadata must fit into a single chunk of both endpoints,
+    /// i.e. it must not exceed the [chunk size](Self::chunk_size) minus 16 bytes.
+    /// By default this is empty.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub metadata: Metadata,
+    /// Check whether to accept a connection based on the metadata of the remote endpoint.
+    ///
+    /// If the check fails, establishing the connection fails with [ChMuxError::Refused](super::ChMuxError::Refused)
+    /// and the remote endpoint is notified of the reason.
+    /// By default all remote endpoints are accepted.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub accept_metadata: Option<AcceptMetadata>,
     #[doc(hidden)]
     pub _non_exhaustive: (),
 }
 
+/// Default value of timeouts missing from a deserialized configuration.
+#[cfg(feature = "serde")]
+fn default_timeout() -> Option<Duration> {
+    Cfg::default().handshake_timeout
+}
+
+/// Default event queue length of a deserialized configuration that does not specify it.
+#[cfg(feature = "serde")]
+fn default_event_queue() -> usize {
+    Cfg::default().event_queue
+}
+
 impl Default for Cfg {
     /// The default configuration provides a balance between throughput,
     /// memory usage and latency.
     fn default() -> Self {
         Self {
             connection_timeout: Some(Duration::from_secs(60)),
+            handshake_timeout: Some(Duration::from_secs(60)),
+            base_channel_timeout: Some(Duration::from_secs(60)),
             max_ports: 16_384,
             ports_exhausted: PortsExhausted::Wait(Some(Duration::from_secs(60))),
             max_data_size: 524_288,
@@ -132,6 +189,9 @@ impl Default for Cfg {
             transport_send_queue: 128,
             transport_receive_queue: 128,
             connect_queue: 128,
+            event_queue: 128,
+            metadata: Metadata::default(),
+            accept_metadata: None,
             _non_exhaustive: (),
         }
     }
@@ -170,6 +230,15 @@ impl Cfg {
         if self.connect_queue == 0 {
             panic!("connect queue length must not be zero");
         }
+
+        if self.event_queue == 0 {
+            panic!("event queue length must not be zero");
+        }
+
+        if !self.metadata.is_empty() && self.metadata.encoded_len() > self.chunk_size.saturating_sub(16) as usize
+        {
+            panic!("encoded metadata must not exceed chunk size minus 16 bytes");
+        }
     }
 
     /// Returns the maximum size of a frame that can be received by a
@@ -5,10 +5,14 @@ use std::{
 };
 use tokio::sync::{
     mpsc::{self, error::TrySendError},
-    oneshot,
+    oneshot, watch,
 };
 
-use super::{ChMuxError, SendError, mux::PortEvt};
+use super::{
+    ChMuxError, SendError,
+    event::{Event, EventTx},
+    mux::PortEvt,
+};
 
 // ===========================================================================
 // Credit accounting for sending data
@@ -68,6 +72,8 @@ struct ChannelCreditsInner {
     credits: u32,
     closed: Option<bool>,
     notify: Vec<oneshot::Sender<()>>,
+    /// Total number of credits returned by the remote endpoint.
+    returned: watch::Sender<u64>,
 }
 
 /// Provides credits for sending over a channel.
@@ -86,6 +92,7 @@ impl CreditProvider {
                 Some(new_credits) => inner.credits = new_credits,
                 None => return Err(ChMuxError::Protocol("credits overflow".to_string())),
             };
+            inner.returned.send_modify(|returned| *returned += u64::from(credits));
 
             mem::take(&mut inner.notify)
         };
@@ -118,9 +125,22 @@ pub(crate) struct CreditUser {
     channel: Weak<Mutex<ChannelCreditsInner>>,
     /// Whether data is sent anyway, when remote endpoint closed channel gracefully.
     pub(crate) override_graceful_close: bool,
+    /// Event sender for notifying about credit starvation.
+    events: EventTx,
+    /// Local port.
+    local_port: u32,
+    /// Remote port.
+    remote_port: u32,
+    /// Total number of credits returned by the remote endpoint.
+    returned: watch::Receiver<u64>,
 }
 
 impl CreditUser {
+    /// Watches the total number of credits returned by the remote endpoint.
+    pub fn returned(&self) -> watch::Receiver<u64> {
+        self.returned.clone()
+    }
+
     /// Requests credits for sending.
     /// Blocks until at least `min_req` credits become available.
     pub async fn request(&self, req: u32, min_req: u32) -> Result<AssignedCredits, SendError> {
@@ -153,6 +173,8 @@ impl CreditUser {
             };
 
             tracing::trace!("waiting for at least {min_req} credits, but want {req} credits");
+            self.events
+                .send(Event::CreditStarvation { local_port: self.local_port, remote_port: self.remote_port });
             let _ = rx_channel.await;
         }
     }
@@ -184,11 +206,27 @@ impl CreditUser {
 
 /// Creates a pair of credit provider and credit user, initially filled
 /// with the specified number of credits.
-pub(crate) fn credit_send_pair(initial_credits: u32) -> (CreditProvider, CreditUser) {
-    let inner =
-        Arc::new(Mutex::new(ChannelCreditsInner { credits: initial_credits, closed: None, notify: Vec::new() }));
-
-    let user = CreditUser { channel: Arc::downgrade(&inner), override_graceful_close: false };
+///
+/// Credit starvation of the user is reported as an event for the specified ports.
+pub(crate) fn credit_send_pair(
+    initial_credits: u32, events: EventTx, local_port: u32, remote_port: u32,
+) -> (CreditProvider, CreditUser) {
+    let (returned_tx, returned) = watch::channel(0);
+    let inner = Arc::new(Mutex::new(ChannelCreditsInner {
+        credits: initial_credits,
+        closed: None,
+        notify: Vec::new(),
+        returned: returned_tx,
+    }));
+
+    let user = CreditUser {
+        channel: Arc::downgrade(&inner),
+        override_graceful_close: false,
+        events,
+        local_port,
+        remote_port,
+        returned,
+    };
     let provider = CreditProvider(inner);
     (provider, user)
 }
@@ -267,6 +305,17 @@ impl ChannelCreditReturner {
         }
     }
 
+    /// Returns all remaining queued credits without waiting.
+    ///
+    /// This is used when the receiver is dropped, so that the sending side
+    /// learns that all received data has been consumed.
+    pub fn return_remaining(&mut self, remote_port: u32, tx: &mpsc::Sender<PortEvt>) {
+        if self.to_return > 0 {
+            let _ = tx.try_send(PortEvt::ReturnCredits { remote_port, credits: self.to_return });
+            self.to_return = 0;
+        }
+    }
+
     /// Completes returning of credits.
     pub async fn return_flush(&mut self) {
         if let Some(return_fut) = &mut self.return_fut {
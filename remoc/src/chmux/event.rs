@@ -0,0 +1,105 @@
+//! Multiplexer lifecycle events.
+
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// An endpoint of a multiplexer connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// This endpoint.
+    Local,
+    /// The remote endpoint.
+    Remote,
+}
+
+/// Reason for closing a port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PortCloseReason {
+    /// The specified endpoint closed or dropped its sender or receiver first.
+    Closed(Endpoint),
+    /// The multiplexer terminated while the port was still open.
+    Terminated,
+}
+
+/// Lifecycle event of a channel multiplexer.
+///
+/// Events can be received by [subscribing](super::ChMux::subscribe_events) to them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Event {
+    /// A port has been opened.
+    PortOpened {
+        /// Local port number.
+        local_port: u32,
+        /// Remote port number.
+        remote_port: u32,
+        /// Endpoint that requested opening the port.
+        initiator: Endpoint,
+    },
+    /// A port has been closed and released.
+    PortClosed {
+        /// Local port number.
+        local_port: u32,
+        /// Remote port number.
+        remote_port: u32,
+        /// Reason for closing.
+        reason: PortCloseReason,
+    },
+    /// Sending data over a port is delayed because the remote endpoint has not
+    /// yet returned enough flow control credits.
+    ///
+    /// This indicates that the remote endpoint is not processing received data fast enough.
+    CreditStarvation {
+        /// Local port number.
+        local_port: u32,
+        /// Remote port number.
+        remote_port: u32,
+    },
+    /// No message has been received from the remote endpoint for three quarters of the
+    /// [connection timeout](super::Cfg::connection_timeout).
+    ///
+    /// Since an idle remote endpoint sends pings at half of the timeout, this indicates
+    /// that at least one ping is overdue.
+    ///
+    /// The connection will be terminated if no message is received within the remaining time.
+    PingTimeoutApproaching {
+        /// Time until the connection times out.
+        remaining: Duration,
+    },
+    /// All clients of the remote endpoint have been dropped.
+    ///
+    /// No more ports will be opened by the remote endpoint.
+    RemoteClientFinished,
+    /// The listener of the remote endpoint has been dropped.
+    ///
+    /// No more ports can be opened to the remote endpoint.
+    RemoteListenerFinished,
+    /// A goodbye message terminating the connection has been sent or received.
+    Goodbye {
+        /// Endpoint that terminated the connection.
+        initiator: Endpoint,
+    },
+}
+
+/// Sender of multiplexer events.
+#[derive(Debug, Clone)]
+pub(crate) struct EventTx(broadcast::Sender<Event>);
+
+impl EventTx {
+    /// Creates a new event sender with the specified queue length per subscriber.
+    pub fn new(queue: usize) -> Self {
+        Self(broadcast::channel(queue).0)
+    }
+
+    /// Sends an event to all subscribers.
+    pub fn send(&self, event: Event) {
+        tracing::trace!(?event, "chmux event");
+        let _ = self.0.send(event);
+    }
+
+    /// Subscribes to events.
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.0.subscribe()
+    }
+}
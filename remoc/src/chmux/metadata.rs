@@ -0,0 +1,214 @@
+//! Metadata exchanged during connection handshake.
+
+use byteorder::{LE, ReadBytesExt, WriteBytesExt};
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    fmt,
+    hash::{Hash, Hasher},
+    io::{self, Read},
+    sync::Arc,
+};
+
+/// Metadata describing an endpoint.
+///
+/// It is sent to the remote endpoint while the connection is being established,
+/// before any channels are opened.
+/// The metadata of the remote endpoint is available from [ChMux::remote_metadata](super::ChMux::remote_metadata)
+/// and [Connect::remote_metadata](crate::Connect::remote_metadata).
+///
+/// The remote endpoint only receives metadata if it supports protocol version 4 or later.
+/// Otherwise, the metadata of the remote endpoint is empty.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metadata {
+    /// Application name.
+    pub app_name: Option<String>,
+    /// Application version.
+    pub app_version: Option<String>,
+    /// Application build identifier.
+    pub build_id: Option<String>,
+    /// Locale of the user, for example `en-US`.
+    pub locale: Option<String>,
+    /// Custom key-value headers.
+    pub headers: BTreeMap<String, String>,
+    #[doc(hidden)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub _non_exhaustive: (),
+}
+
+impl Metadata {
+    /// Creates empty metadata.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether no metadata is set.
+    pub fn is_empty(&self) -> bool {
+        self.app_name.is_none()
+            && self.app_version.is_none()
+            && self.build_id.is_none()
+            && self.locale.is_none()
+            && self.headers.is_empty()
+    }
+
+    /// Sets the application name and version.
+    pub fn with_app(mut self, name: impl Into<String>, version: impl Into<String>) -> Self {
+        self.app_name = Some(name.into());
+        self.app_version = Some(version.into());
+        self
+    }
+
+    /// Sets the build identifier.
+    pub fn with_build_id(mut self, build_id: impl Into<String>) -> Self {
+        self.build_id = Some(build_id.into());
+        self
+    }
+
+    /// Sets the locale.
+    pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Adds a custom header.
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns the value of a custom header.
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers.get(key).map(|v| v.as_str())
+    }
+
+    /// Size of the metadata when encoded for transmission in bytes.
+    pub(crate) fn encoded_len(&self) -> usize {
+        let mut data = Vec::new();
+        self.write(&mut data).expect("metadata serialization failed");
+        data.len()
+    }
+
+    pub(crate) fn write(&self, mut writer: impl io::Write) -> Result<(), io::Error> {
+        for field in [&self.app_name, &self.app_version, &self.build_id, &self.locale] {
+            match field {
+                Some(value) => {
+                    writer.write_u8(1)?;
+                    write_str(&mut writer, value)?;
+                }
+                None => writer.write_u8(0)?,
+            }
+        }
+
+        writer.write_u32::<LE>(self.headers.len().try_into().map_err(|_| invalid_len())?)?;
+        for (key, value) in &self.headers {
+            write_str(&mut writer, key)?;
+            write_str(&mut writer, value)?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn read(mut reader: impl io::Read) -> Result<Self, io::Error> {
+        let mut fields: [Option<String>; 4] = Default::default();
+        for field in &mut fields {
+            if reader.read_u8()? != 0 {
+                *field = Some(read_str(&mut reader)?);
+            }
+        }
+        let [app_name, app_version, build_id, locale] = fields;
+
+        let mut headers = BTreeMap::new();
+        for _ in 0..reader.read_u32::<LE>()? {
+            let key = read_str(&mut reader)?;
+            let value = read_str(&mut reader)?;
+            headers.insert(key, value);
+        }
+
+        Ok(Self { app_name, app_version, build_id, locale, headers, _non_exhaustive: () })
+    }
+}
+
+fn invalid_len() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "metadata too long")
+}
+
+fn write_str(mut writer: impl io::Write, value: &str) -> Result<(), io::Error> {
+    writer.write_u32::<LE>(value.len().try_into().map_err(|_| invalid_len())?)?;
+    writer.write_all(value.as_bytes())
+}
+
+fn read_str(mut reader: impl io::Read) -> Result<String, io::Error> {
+    let len = reader.read_u32::<LE>()?;
+    let mut buf = Vec::new();
+    Read::by_ref(&mut reader).take(len.into()).read_to_end(&mut buf)?;
+    if buf.len() != len as usize {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    String::from_utf8(buf).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Function deciding whether to accept a connection based on the metadata of the remote endpoint.
+///
+/// It returns `Ok(())` to accept the connection or `Err(reason)` to reject it.
+/// The reason is sent to the remote endpoint.
+type AcceptFn = dyn Fn(&Metadata) -> Result<(), String> + Send + Sync;
+
+/// Acceptance check for the [metadata](Metadata) of a remote endpoint.
+///
+/// This is called with the metadata of the remote endpoint while the connection is being
+/// established.
+/// If it returns an error, the connection is rejected and the error message is sent
+/// as the reason to the remote endpoint.
+///
+/// Comparison and hashing are performed by the identity of the check function.
+#[derive(Clone)]
+pub struct AcceptMetadata(Arc<AcceptFn>);
+
+impl AcceptMetadata {
+    /// Creates a new acceptance check from the specified function.
+    pub fn new(f: impl Fn(&Metadata) -> Result<(), String> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Checks the metadata of the remote endpoint.
+    pub fn check(&self, remote: &Metadata) -> Result<(), String> {
+        (self.0)(remote)
+    }
+
+    fn addr(&self) -> *const () {
+        Arc::as_ptr(&self.0) as *const ()
+    }
+}
+
+impl fmt::Debug for AcceptMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("AcceptMetadata").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for AcceptMetadata {
+    fn eq(&self, other: &Self) -> bool {
+        self.addr() == other.addr()
+    }
+}
+
+impl Eq for AcceptMetadata {}
+
+impl PartialOrd for AcceptMetadata {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AcceptMetadata {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.addr().cmp(&other.addr())
+    }
+}
+
+impl Hash for AcceptMetadata {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.addr().hash(state)
+    }
+}
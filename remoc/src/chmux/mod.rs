@@ -12,6 +12,16 @@
 //! Two endpoints can only communicate if they have the same [protocol version](PROTOCOL_VERSION).
 //! A change in protocol version will be accompanied by an increase of the
 //! major version number of the Remoc crate.
+//!
+//! Protocol version 4 is backward compatible with version 3.
+//! It adds the [metadata](Metadata) to the hello message and, if both endpoints
+//! support version 4, a subsequent exchange of acceptance replies, which makes a
+//! connection rejected by the remote endpoint fail with [ChMuxError::Rejected].
+//! When connected to an endpoint using version 3, no acceptance replies are exchanged
+//! and the remote metadata is empty.
+//! In this case an [acceptance check](Cfg::accept_metadata) that requires metadata fails
+//! locally with [ChMuxError::Refused], while the remote endpoint only observes the
+//! connection being closed.
 
 use std::{error::Error, fmt};
 
@@ -19,8 +29,10 @@ mod any_storage;
 mod cfg;
 mod client;
 mod credit;
+mod event;
 mod forward;
 mod listener;
+mod metadata;
 mod msg;
 mod mux;
 mod port_allocator;
@@ -30,21 +42,29 @@ mod sender;
 pub use any_storage::{AnyBox, AnyEntry, AnyStorage};
 pub use cfg::{Cfg, PortsExhausted};
 pub use client::{Client, Connect, ConnectError};
+#[cfg(feature = "rch")]
+pub(crate) use event::EventTx;
+pub use event::{Endpoint, Event, PortCloseReason};
 pub use forward::ForwardError;
 pub use listener::{Listener, ListenerError, ListenerStream, Request};
+pub use metadata::{AcceptMetadata, Metadata};
 pub use mux::ChMux;
 pub use port_allocator::{PortAllocator, PortNumber, PortReq};
 pub use receiver::{DataBuf, Received, Receiver, ReceiverStream, RecvAnyError, RecvChunkError, RecvError};
 pub use sender::{ChunkSender, Closed, SendError, Sender, SenderSink, TrySendError};
 
 /// Channel multiplexer protocol version.
-pub const PROTOCOL_VERSION: u8 = 3;
+pub const PROTOCOL_VERSION: u8 = 4;
 
 /// Lowest protocol version that supports port ids.
 const PROTOCOL_VERSION_PORT_ID: u8 = 3;
 
+/// Lowest protocol version that supports metadata exchange.
+const PROTOCOL_VERSION_METADATA: u8 = 4;
+
 /// Channel multiplexer error.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum ChMuxError<SinkError, StreamError> {
     /// An error was encountered while sending data to the transport sink.
     SinkError(SinkError),
@@ -55,10 +75,19 @@ pub enum ChMuxError<SinkError, StreamError> {
     StreamClosed,
     /// The connection was reset by the remote endpoint.
     Reset,
-    /// No messages where received over the configured connection timeout.
+    /// No messages where received over the configured connection timeout
+    /// or the handshake did not complete within the configured handshake timeout.
     Timeout,
     /// A multiplex protocol error occurred.
     Protocol(String),
+    /// The remote endpoint rejected the connection for the specified reason.
+    ///
+    /// This occurs when the [metadata](Metadata) of this endpoint did not pass the
+    /// acceptance check of the remote endpoint.
+    Rejected(String),
+    /// The connection was refused because the [metadata](Metadata) of the remote endpoint
+    /// did not pass the [acceptance check](Cfg::accept_metadata).
+    Refused(String),
 }
 
 impl<SinkError, StreamError> fmt::Display for ChMuxError<SinkError, StreamError>
@@ -74,6 +103,8 @@ where
             Self::Reset => write!(f, "connection reset"),
             Self::Timeout => write!(f, "connection timeout"),
             Self::Protocol(err) => write!(f, "protocol error: {err}"),
+            Self::Rejected(reason) => write!(f, "connection rejected by remote endpoint: {reason}"),
+            Self::Refused(reason) => write!(f, "connection refused: {reason}"),
         }
     }
 }
@@ -95,6 +126,9 @@ impl From<ChMuxError<std::io::Error, std::io::Error>> for std::io::Error {
             ChMuxError::Reset => std::io::Error::new(ErrorKind::ConnectionReset, err.to_string()),
             ChMuxError::Timeout => std::io::Error::new(ErrorKind::TimedOut, err.to_string()),
             ChMuxError::Protocol(_) => std::io::Error::new(ErrorKind::InvalidData, err.to_string()),
+            ChMuxError::Rejected(_) | ChMuxError::Refused(_) => {
+                std::io::Error::new(ErrorKind::ConnectionRefused, err.to_string())
+            }
         }
     }
 }
@@ -4,7 +4,7 @@ use std::{
     time::Duration,
 };
 
-use super::{Cfg, ChMuxError};
+use super::{Cfg, ChMuxError, PROTOCOL_VERSION_METADATA, metadata::Metadata};
 
 fn invalid_data(msg: &str) -> io::Error {
     io::Error::new(io::ErrorKind::InvalidData, format!("invalid value for {msg} received"))
@@ -25,6 +25,16 @@ pub enum MultiplexMsg {
         version: u8,
         /// Configuration of side that sends the message.
         cfg: ExchangedCfg,
+        /// Metadata of side that sends the message.
+        ///
+        /// Only present for protocol version 4 or later.
+        metadata: Metadata,
+    },
+    /// Reply to Hello message indicating whether the connection is accepted.
+    HelloAck {
+        // Flags u8.
+        /// Reason for rejecting the connection, if rejected.
+        rejected: Option<String>,
     },
     /// Ping to keep connection alive when there is no data to send.
     Ping,
@@ -133,12 +143,15 @@ pub const MSG_RECEIVE_FINISH: u8 = 12;
 pub const MSG_CLIENT_FINISH: u8 = 13;
 pub const MSG_LISTENER_FINISH: u8 = 14;
 pub const MSG_GOODBYE: u8 = 15;
+pub const MSG_HELLO_ACK: u8 = 16;
 
 pub const MSG_OPEN_PORT_FLAG_WAIT: u8 = 0b0000_0001;
 pub const MSG_OPEN_PORT_FLAG_ID: u8 = 0b0000_0010;
 
 pub const MSG_REJECTED_FLAG_NO_PORTS: u8 = 0b0000_0001;
 
+pub const MSG_HELLO_ACK_FLAG_REJECTED: u8 = 0b0000_0001;
+
 pub const MSG_DATA_FLAG_FIRST: u8 = 0b0000_0001;
 pub const MSG_DATA_FLAG_LAST: u8 = 0b0000_0010;
 
@@ -159,11 +172,24 @@ impl MultiplexMsg {
             MultiplexMsg::Reset => {
                 writer.write_u8(MSG_RESET)?;
             }
-            MultiplexMsg::Hello { version, cfg } => {
+            MultiplexMsg::Hello { version, cfg, metadata } => {
                 writer.write_u8(MSG_HELLO)?;
                 writer.write_all(MAGIC)?;
                 writer.write_u8(*version)?;
                 cfg.write(&mut writer)?;
+                if *version >= PROTOCOL_VERSION_METADATA {
+                    metadata.write(&mut writer)?;
+                }
+            }
+            MultiplexMsg::HelloAck { rejected } => {
+                writer.write_u8(MSG_HELLO_ACK)?;
+                match rejected {
+                    Some(reason) => {
+                        writer.write_u8(MSG_HELLO_ACK_FLAG_REJECTED)?;
+                        writer.write_all(reason.as_bytes())?;
+                    }
+                    None => writer.write_u8(0)?,
+                }
             }
             MultiplexMsg::Ping => {
                 writer.write_u8(MSG_PING)?;
@@ -276,7 +302,25 @@ impl MultiplexMsg {
                 if magic != MAGIC {
                     return Err(invalid_data("invalid magic"));
                 }
-                Self::Hello { version: reader.read_u8()?, cfg: ExchangedCfg::read(&mut reader)? }
+                let version = reader.read_u8()?;
+                let cfg = ExchangedCfg::read(&mut reader)?;
+                let metadata = if version >= PROTOCOL_VERSION_METADATA {
+                    Metadata::read(&mut reader)?
+                } else {
+                    Metadata::default()
+                };
+                Self::Hello { version, cfg, metadata }
+            }
+            MSG_HELLO_ACK => {
+                let flags = reader.read_u8()?;
+                let rejected = if flags & MSG_HELLO_ACK_FLAG_REJECTED != 0 {
+                    let mut reason = Vec::new();
+                    reader.read_to_end(&mut reason)?;
+                    Some(String::from_utf8_lossy(&reason).into_owned())
+                } else {
+                    None
+                };
+                Self::HelloAck { rejected }
             }
             MSG_PING => Self::Ping,
             MSG_OPEN_PORT => {
@@ -20,16 +20,18 @@ use std::{
     time::Duration,
 };
 use tokio::{
-    sync::{mpsc, mpsc::Permit, oneshot},
+    sync::{broadcast, mpsc, mpsc::Permit, oneshot},
     try_join,
 };
 use tokio_util::sync::ReusableBoxFuture;
 
 use super::{
-    AnyStorage, Cfg, ChMuxError, PROTOCOL_VERSION, PROTOCOL_VERSION_PORT_ID, PortReq,
+    AnyStorage, Cfg, ChMuxError, PROTOCOL_VERSION, PROTOCOL_VERSION_METADATA, PROTOCOL_VERSION_PORT_ID, PortReq,
     client::{Client, ConnectRequest, ConnectResponse},
     credit::{ChannelCreditMonitor, CreditProvider, credit_monitor_pair, credit_send_pair},
+    event::{Endpoint, Event, EventTx, PortCloseReason},
     listener::{Listener, RemoteConnectMsg, Request},
+    metadata::Metadata,
     msg::{ExchangedCfg, MultiplexMsg},
     port_allocator::{PortAllocator, PortNumber},
     receiver::{PortReceiveMsg, ReceivedData, ReceivedPortRequests, Receiver},
@@ -77,6 +79,8 @@ enum PortState {
         /// Remote receiver has been dropped, thus no more sent data will be processed and
         /// no port credits will be returned.
         remote_receiver_dropped: bool,
+        /// Endpoint that first closed or dropped its sender or receiver.
+        closed_by: Option<Endpoint>,
     },
 }
 
@@ -194,6 +198,8 @@ pub struct ChMux<TransportSink, TransportStream> {
     remote_cfg: ExchangedCfg,
     /// Remote protocol version.
     remote_protocol_version: u8,
+    /// Remote metadata.
+    remote_metadata: Metadata,
     /// Channel for connection requests from local client.
     connect_rx: Option<mpsc::UnboundedReceiver<ConnectRequest>>,
     /// Channels for connection requests from remote endpoint with wait set and not set.
@@ -226,6 +232,8 @@ pub struct ChMux<TransportSink, TransportStream> {
     transport_stream: Option<TransportStream>,
     /// Storage.
     storage: AnyStorage,
+    /// Event sender.
+    events: EventTx,
 }
 
 impl<TransportSink, TransportStream> fmt::Debug for ChMux<TransportSink, TransportStream> {
@@ -235,6 +243,7 @@ impl<TransportSink, TransportStream> fmt::Debug for ChMux<TransportSink, Transpo
             .field("remote_cfg", &self.remote_cfg)
             .field("local_protocol_version", &PROTOCOL_VERSION)
             .field("remote_protocol_version", &self.remote_protocol_version)
+            .field("remote_metadata", &self.remote_metadata)
             .finish()
     }
 }
@@ -251,6 +260,9 @@ where
     ///
     /// After creation use the `run` method of the multiplexer to launch the dispatch task.
     ///
+    /// The [metadata](Cfg::metadata) of both endpoints is exchanged and
+    /// checked by the [acceptance check](Cfg::accept_metadata), if any.
+    ///
     /// # Panics
     /// Panics if specified configuration does not obey limits documented in [Cfg].
     #[tracing::instrument(level = "trace", skip_all, fields(cfg))]
@@ -262,7 +274,7 @@ where
 
         // Say hello to remote endpoint and exchange configurations.
         let fut = Self::exchange_hello(&cfg, &mut transport_sink, &mut transport_stream);
-        let (remote_protocol_version, remote_cfg) = match cfg.connection_timeout {
+        let (remote_protocol_version, remote_cfg, remote_metadata) = match cfg.handshake_timeout {
             Some(dur) => timeout(dur, fut).await.map_err(|_| ChMuxError::Timeout)??,
             None => fut.await?,
         };
@@ -277,8 +289,10 @@ where
         // Create user objects.
         let port_allocator = PortAllocator::new(cfg.max_ports);
         let remote_listener_dropped = Arc::new(AtomicBool::new(false));
+        let events = EventTx::new(cfg.event_queue);
         let multiplexer = ChMux {
             remote_protocol_version,
+            remote_metadata,
             local_cfg: cfg,
             remote_cfg: remote_cfg.clone(),
             connect_rx: Some(connect_rx),
@@ -297,6 +311,7 @@ where
             transport_sink: Some(transport_sink),
             transport_stream: Some(transport_stream),
             storage: AnyStorage::new(),
+            events,
         };
 
         let client = Client::new(
@@ -362,17 +377,21 @@ where
         Ok(TransportMsg { msg, data })
     }
 
-    /// Exchange Hello message with remote endpoint.
+    /// Exchange Hello message with remote endpoint and check its metadata.
     #[tracing::instrument(level = "trace", skip_all)]
     async fn exchange_hello(
         cfg: &Cfg, sink: &mut TransportSink, stream: &mut TransportStream,
-    ) -> Result<(u8, ExchangedCfg), ChMuxError<TransportSinkError, TransportStreamError>> {
+    ) -> Result<(u8, ExchangedCfg, Metadata), ChMuxError<TransportSinkError, TransportStreamError>> {
         // Say hello to remote endpoint and send our configuration.
         let send_task = async {
             Self::feed_msg(TransportMsg::new(MultiplexMsg::Reset), sink).await?;
             Self::flush(sink).await?;
             Self::feed_msg(
-                TransportMsg::new(MultiplexMsg::Hello { version: PROTOCOL_VERSION, cfg: cfg.into() }),
+                TransportMsg::new(MultiplexMsg::Hello {
+                    version: PROTOCOL_VERSION,
+                    cfg: cfg.into(),
+                    metadata: cfg.metadata.clone(),
+                }),
                 sink,
             )
             .await?;
@@ -384,8 +403,8 @@ where
         let recv_task = async {
             loop {
                 match Self::recv_msg(stream).await {
-                    Ok(TransportMsg { msg: MultiplexMsg::Hello { version, cfg }, .. }) => {
-                        break Ok((version, cfg));
+                    Ok(TransportMsg { msg: MultiplexMsg::Hello { version, cfg, metadata }, .. }) => {
+                        break Ok((version, cfg, metadata));
                     }
                     Ok(_) => (),
                     Err(ChMuxError::Protocol(_)) => (),
@@ -394,7 +413,52 @@ where
             }
         };
 
-        Ok(try_join!(send_task, recv_task)?.1)
+        let ((), (version, remote_cfg, metadata)) = try_join!(send_task, recv_task)?;
+
+        // Check metadata of remote endpoint.
+        let refused = match &cfg.accept_metadata {
+            Some(accept) => accept.check(&metadata).err(),
+            None => None,
+        };
+
+        // Exchange acceptance with remote endpoint, if supported.
+        if version >= PROTOCOL_VERSION_METADATA {
+            let send_task = async {
+                let mut rejected = refused.clone();
+                if let Some(reason) = &mut rejected {
+                    let max_len = remote_cfg.chunk_size.saturating_sub(16) as usize;
+                    if reason.len() > max_len {
+                        let mut len = max_len;
+                        while !reason.is_char_boundary(len) {
+                            len -= 1;
+                        }
+                        reason.truncate(len);
+                    }
+                }
+                Self::feed_msg(TransportMsg::new(MultiplexMsg::HelloAck { rejected }), sink).await?;
+                Self::flush(sink).await?;
+                Ok(())
+            };
+
+            let recv_task = async {
+                match Self::recv_msg(stream).await? {
+                    TransportMsg { msg: MultiplexMsg::HelloAck { rejected }, .. } => Ok(rejected),
+                    _ => Err(protocol_err("expected HelloAck message")),
+                }
+            };
+
+            let ((), rejected) = try_join!(send_task, recv_task)?;
+            if let Some(reason) = refused {
+                return Err(ChMuxError::Refused(reason));
+            }
+            if let Some(reason) = rejected {
+                return Err(ChMuxError::Rejected(reason));
+            }
+        } else if let Some(reason) = refused {
+            return Err(ChMuxError::Refused(reason));
+        }
+
+        Ok((version, remote_cfg, metadata))
     }
 
     /// Returns true, when multiplexer task should terminate because no more
@@ -426,11 +490,18 @@ where
 
     /// Create port in port registry and return associated sender and receiver.
     #[tracing::instrument(level = "trace", skip(self))]
-    fn create_port(&mut self, local_port: PortNumber, remote_port: u32) -> (Sender, Receiver) {
+    fn create_port(
+        &mut self, local_port: PortNumber, remote_port: u32, initiator: Endpoint,
+    ) -> (Sender, Receiver) {
         let local_port_num = *local_port;
 
         let sender_tx = self.channel_tx.clone();
-        let (sender_credit_provider, sender_credit_user) = credit_send_pair(self.remote_cfg.port_receive_buffer);
+        let (sender_credit_provider, sender_credit_user) = credit_send_pair(
+            self.remote_cfg.port_receive_buffer,
+            self.events.clone(),
+            local_port_num,
+            remote_port,
+        );
 
         let receiver_tx = self.channel_tx.clone();
         let (receiver_tx_data, receiver_rx_data) = mpsc::unbounded_channel();
@@ -453,6 +524,7 @@ where
                 receiver_dropped: false,
                 sender_dropped: false,
                 remote_receiver_dropped: false,
+                closed_by: None,
             },
         ) {
             panic!(
@@ -485,6 +557,8 @@ where
             self.storage.clone(),
         );
 
+        self.events.send(Event::PortOpened { local_port: local_port_num, remote_port, initiator });
+
         (sender, receiver)
     }
 
@@ -492,15 +566,24 @@ where
     /// and no more messages from the remote endpoint can reference it.
     fn maybe_free_port(&mut self, local_port: u32) {
         let mut free = true;
+        let port_closed;
 
         if let Some(PortState::Connected {
+            remote_port,
             receiver_tx_data,
             receiver_dropped,
             sender_dropped,
             remote_receiver_dropped,
+            closed_by,
             ..
         }) = self.ports.get(&local_port)
         {
+            port_closed = Event::PortClosed {
+                local_port,
+                remote_port: *remote_port,
+                reason: PortCloseReason::Closed(closed_by.unwrap_or(Endpoint::Local)),
+            };
+
             // Ensures that local sender is dropped and thus no more
             // Data and SendFinish messages can be sent for port.
             free &= *sender_dropped;
@@ -520,6 +603,7 @@ where
         if free {
             tracing::trace!(local_port, "freed port");
             self.ports.remove(&local_port);
+            self.events.send(port_closed);
         }
     }
 
@@ -588,15 +672,21 @@ where
     /// Watches the connection timeout.
     async fn recv_task(
         stream: &mut TransportStream, connection_timeout: Option<Duration>, tx: mpsc::Sender<TransportMsg>,
+        events: EventTx,
     ) -> Result<(), ChMuxError<TransportSinkError, TransportStreamError>> {
-        async fn get_connection_timeout(connection_timeout: Option<Duration>) {
+        async fn get_connection_timeout(connection_timeout: Option<Duration>, events: EventTx) {
             match connection_timeout {
-                Some(timeout) => sleep(timeout).await,
+                Some(timeout) => {
+                    let remaining = timeout / 4;
+                    sleep(timeout - remaining).await;
+                    events.send(Event::PingTimeoutApproaching { remaining });
+                    sleep(remaining).await;
+                }
                 None => future::pending().await,
             }
         }
 
-        let mut next_timeout = ReusableBoxFuture::new(get_connection_timeout(connection_timeout));
+        let mut next_timeout = ReusableBoxFuture::new(get_connection_timeout(connection_timeout, events.clone()));
 
         while let Ok(tx_permit) = tx.reserve().await {
             tokio::select! {
@@ -610,7 +700,7 @@ where
                         break;
                     }
 
-                    next_timeout.set(get_connection_timeout(connection_timeout));
+                    next_timeout.set(get_connection_timeout(connection_timeout, events.clone()));
                 },
 
                 () = &mut next_timeout => return Err(ChMuxError::Timeout),
@@ -620,12 +710,52 @@ where
         Ok(())
     }
 
+    /// Metadata of the remote endpoint.
+    ///
+    /// This is empty if the remote endpoint does not support metadata exchange.
+    pub fn remote_metadata(&self) -> &Metadata {
+        &self.remote_metadata
+    }
+
+    /// Subscribes to lifecycle [events](Event) of the multiplexer.
+    ///
+    /// Only events that occur after subscribing are received.
+    /// If the receiver does not keep up, the oldest events are skipped,
+    /// see the [event queue length](Cfg::event_queue).
+    pub fn subscribe_events(&self) -> broadcast::Receiver<Event> {
+        self.events.subscribe()
+    }
+
+    /// Event sender of the multiplexer.
+    #[cfg(feature = "rch")]
+    pub(crate) fn event_tx(&self) -> EventTx {
+        self.events.clone()
+    }
+
     /// Runs the multiplexer dispatcher.
     ///
     /// The dispatcher terminates when the client, server and all channels have been dropped or
     /// the transport is closed.
     #[tracing::instrument(name = "remoc::chmux", level = "debug", skip_all, ret)]
     pub async fn run(mut self) -> Result<(), ChMuxError<TransportSinkError, TransportStreamError>> {
+        let res = self.dispatch().await;
+
+        // Report ports that were still open.
+        for (local_port, state) in self.ports.drain() {
+            if let PortState::Connected { remote_port, .. } = state {
+                self.events.send(Event::PortClosed {
+                    local_port: *local_port,
+                    remote_port,
+                    reason: PortCloseReason::Terminated,
+                });
+            }
+        }
+
+        res
+    }
+
+    /// Multiplexer dispatcher.
+    async fn dispatch(&mut self) -> Result<(), ChMuxError<TransportSinkError, TransportStreamError>> {
         let mut transport_sink = self.transport_sink.take().unwrap();
         let mut transport_stream = self.transport_stream.take().unwrap();
 
@@ -638,7 +768,13 @@ where
 
         // Create receive over transport task.
         let (recv_tx, mut recv_rx) = mpsc::channel(self.local_cfg.transport_receive_queue);
-        let recv_task = Self::recv_task(&mut transport_stream, self.local_cfg.connection_timeout, recv_tx).fuse();
+        let recv_task = Self::recv_task(
+            &mut transport_stream,
+            self.local_cfg.connection_timeout,
+            recv_tx,
+            self.events.clone(),
+        )
+        .fuse();
         pin_mut!(recv_task);
 
         // Setup channels.
@@ -753,7 +889,7 @@ where
                     permit,
                     MultiplexMsg::PortOpened { client_port: remote_port, server_port: local_port_num },
                 );
-                let (sender, receiver) = self.create_port(local_port, remote_port);
+                let (sender, receiver) = self.create_port(local_port, remote_port, Endpoint::Remote);
                 let _ = port_tx.send((sender, receiver));
             }
 
@@ -799,13 +935,14 @@ where
 
             // Local port sender has been dropped.
             GlobalEvt::Port(PortEvt::SenderDropped { local_port }) => {
-                if let Some(PortState::Connected { remote_port, sender_dropped, .. }) =
+                if let Some(PortState::Connected { remote_port, sender_dropped, closed_by, .. }) =
                     self.ports.get_mut(&local_port)
                 {
                     if *sender_dropped {
                         panic!("PortEvt SenderDropped more than once for port {}", &local_port);
                     }
                     *sender_dropped = true;
+                    closed_by.get_or_insert(Endpoint::Local);
                     send_msg(permit, MultiplexMsg::SendFinish { port: *remote_port });
                     self.maybe_free_port(local_port);
                 } else {
@@ -815,8 +952,13 @@ where
 
             // Local port receiver has been closed.
             GlobalEvt::Port(PortEvt::ReceiverClosed { local_port }) => {
-                if let Some(PortState::Connected { remote_port, receiver_closed, receiver_dropped, .. }) =
-                    self.ports.get_mut(&local_port)
+                if let Some(PortState::Connected {
+                    remote_port,
+                    receiver_closed,
+                    receiver_dropped,
+                    closed_by,
+                    ..
+                }) = self.ports.get_mut(&local_port)
                 {
                     if *receiver_closed || *receiver_dropped {
                         panic!(
@@ -825,6 +967,7 @@ where
                         );
                     }
                     *receiver_closed = true;
+                    closed_by.get_or_insert(Endpoint::Local);
                     send_msg(permit, MultiplexMsg::ReceiveClose { port: *remote_port });
                 } else {
                     panic!("PortEvt ReceiverClosed for non-connected port {}", &local_port);
@@ -834,11 +977,12 @@ where
             // Local port receiver has been dropped.
             // No port credits can be returned afterwards.
             GlobalEvt::Port(PortEvt::ReceiverDropped { local_port }) => match self.ports.get_mut(&local_port) {
-                Some(PortState::Connected { remote_port, receiver_dropped, .. }) => {
+                Some(PortState::Connected { remote_port, receiver_dropped, closed_by, .. }) => {
                     if *receiver_dropped {
                         panic!("PortEvt ReceiverDropped more than once for port {}.", &local_port);
                     }
                     *receiver_dropped = true;
+                    closed_by.get_or_insert(Endpoint::Local);
                     send_msg(permit, MultiplexMsg::ReceiveFinish { port: *remote_port });
                     self.maybe_free_port(local_port);
                 }
@@ -863,6 +1007,7 @@ where
             GlobalEvt::SendGoodbye => {
                 self.goodbye_sent = true;
                 send_msg(permit, MultiplexMsg::Goodbye);
+                self.events.send(Event::Goodbye { initiator: Endpoint::Local });
             }
         }
         Ok(())
@@ -881,8 +1026,8 @@ where
                 return Err(ChMuxError::Reset);
             }
 
-            // Hello message only allowed when establishing connection.
-            MultiplexMsg::Hello { .. } => {
+            // Hello messages only allowed when establishing connection.
+            MultiplexMsg::Hello { .. } | MultiplexMsg::HelloAck { .. } => {
                 return Err(protocol_err(
                     "received Hello message for already established multiplexer connection",
                 ));
@@ -917,7 +1062,7 @@ where
             MultiplexMsg::PortOpened { client_port, server_port } => {
                 match self.ports.remove_entry(&client_port) {
                     Some((local_port, PortState::Connecting { response_tx })) => {
-                        let (sender, receiver) = self.create_port(local_port, server_port);
+                        let (sender, receiver) = self.create_port(local_port, server_port, Endpoint::Local);
                         let _ = response_tx.send(ConnectResponse::Accepted(sender, receiver));
                     }
                     _ => {
@@ -1041,10 +1186,12 @@ where
 
             // Remote endpoint indicates that it will send no more data for port.
             MultiplexMsg::SendFinish { port } => {
-                if let Some(PortState::Connected { receiver_tx_data, .. }) = self.ports.get_mut(&port) {
+                if let Some(PortState::Connected { receiver_tx_data, closed_by, .. }) = self.ports.get_mut(&port)
+                {
                     match receiver_tx_data.take() {
                         Some(receiver_tx_data) => {
                             let _ = receiver_tx_data.send(PortReceiveMsg::Finished);
+                            closed_by.get_or_insert(Endpoint::Remote);
                             self.maybe_free_port(port);
                         }
                         _ => {
@@ -1069,10 +1216,13 @@ where
                     sender_credit_provider,
                     remote_receiver_closed_notify,
                     remote_receiver_closed,
+                    closed_by,
                     ..
                 }) = self.ports.get_mut(&port)
                 {
                     if !remote_receiver_closed.load(Ordering::Relaxed) {
+                        closed_by.get_or_insert(Endpoint::Remote);
+
                         // Disable credits provider.
                         sender_credit_provider.close(true);
 
@@ -1105,9 +1255,12 @@ where
                     remote_receiver_closed_notify,
                     remote_receiver_closed,
                     remote_receiver_dropped,
+                    closed_by,
                     ..
                 }) = self.ports.get_mut(&port)
                 {
+                    closed_by.get_or_insert(Endpoint::Remote);
+
                     if !remote_receiver_closed.load(Ordering::Relaxed) {
                         // Disable credits provider.
                         sender_credit_provider.close(false);
@@ -1154,16 +1307,19 @@ where
                 }
 
                 self.remote_client_dropped = true;
+                self.events.send(Event::RemoteClientFinished);
             }
 
             // Remote endpoint will process no more connect requests.
             MultiplexMsg::ListenerFinish => {
                 self.remote_listener_dropped.store(true, Ordering::Relaxed);
+                self.events.send(Event::RemoteListenerFinished);
             }
 
             // Remote endpoint terminates connection.
             MultiplexMsg::Goodbye => {
                 self.goodbye_received = true;
+                self.events.send(Event::Goodbye { initiator: Endpoint::Remote });
             }
         }
 
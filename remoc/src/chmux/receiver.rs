@@ -180,6 +180,13 @@ impl DataBuf {
             _ => Err(buf),
         }
     }
+
+    /// Appends data to the buffer.
+    #[cfg(feature = "robj")]
+    pub(crate) fn push(&mut self, buf: Bytes) {
+        self.remaining += buf.len();
+        self.bufs.push_back(buf);
+    }
 }
 
 impl Default for DataBuf {
@@ -590,7 +597,8 @@ impl Receiver {
 
 impl Drop for Receiver {
     fn drop(&mut self) {
-        // required for correct drop order
+        // Must be queued before the port event for dropping the receiver.
+        self.credits.return_remaining(self.remote_port, &self.tx);
     }
 }
 
@@ -14,7 +14,7 @@ use std::{
         atomic::{AtomicBool, Ordering},
     },
 };
-use tokio::sync::{Mutex, mpsc, oneshot};
+use tokio::sync::{Mutex, mpsc, oneshot, watch};
 use tokio_util::sync::ReusableBoxFuture;
 
 use super::{
@@ -270,6 +270,19 @@ impl Sender {
         self.max_data_size
     }
 
+    /// Watches the total number of bytes acknowledged by the remote endpoint.
+    ///
+    /// Data is acknowledged when the remote receiver has taken it out of its receive buffer
+    /// and returned the corresponding flow-control credits.
+    /// Credits are returned in batches, thus the value is updated in steps of up to half
+    /// the [receive buffer size](super::Cfg::receive_buffer) of the remote endpoint.
+    /// Remaining credits are returned when the remote receiver is dropped.
+    ///
+    /// Sending empty data consumes one byte of credit and sending a port consumes four.
+    pub fn acknowledged(&self) -> watch::Receiver<u64> {
+        self.credits.returned()
+    }
+
     /// Sends data over the channel.
     ///
     /// Waits until send space becomes available.
@@ -9,24 +9,44 @@ use std::{
     pin::Pin,
     task::{Context, Poll},
 };
-use tokio::io::{AsyncRead, AsyncWrite, BufReader, BufWriter};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, BufReader, BufWriter},
+    sync::broadcast,
+};
 use tokio_util::codec::LengthDelimitedCodec;
 
 use crate::{
     RemoteSend,
-    chmux::{ChMux, ChMuxError},
+    chmux::{ChMux, ChMuxError, Event, EventTx, Metadata},
     codec,
+    exec::time::timeout,
     rch::base,
 };
 
 /// Error occurred during establishing a connection over a physical transport.
 #[cfg_attr(docsrs, doc(cfg(feature = "rch")))]
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum ConnectError<TransportSinkError, TransportStreamError> {
     /// Establishing [chmux](crate::chmux) connection failed.
     ChMux(ChMuxError<TransportSinkError, TransportStreamError>),
     /// Opening initial [remote](crate::rch::base) channel failed.
     RemoteConnect(base::ConnectError),
+    /// The [handshake](crate::Cfg::handshake_timeout) or opening the
+    /// [initial remote channel](crate::Cfg::base_channel_timeout) timed out.
+    Timeout,
+    /// Spawning the child process failed.
+    ///
+    /// This is only returned by [Connect::child].
+    #[cfg(feature = "process")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+    Spawn(std::sync::Arc<io::Error>),
+    /// The child process exited before the connection was established.
+    ///
+    /// This is only returned by [Connect::child].
+    #[cfg(feature = "process")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+    ProcessExited(std::process::ExitStatus),
 }
 
 impl<TransportSinkError, TransportStreamError> fmt::Display
@@ -39,6 +59,11 @@ where
         match self {
             Self::ChMux(err) => write!(f, "chmux error: {err}"),
             Self::RemoteConnect(err) => write!(f, "channel connect failed: {err}"),
+            Self::Timeout => write!(f, "connect timeout"),
+            #[cfg(feature = "process")]
+            Self::Spawn(err) => write!(f, "spawning child process failed: {err}"),
+            #[cfg(feature = "process")]
+            Self::ProcessExited(status) => write!(f, "child process exited: {status}"),
         }
     }
 }
@@ -179,6 +204,8 @@ impl<TransportSinkError, TransportStreamError> From<base::ConnectError>
 #[must_use = "You must poll or spawn the Connect future for the connection to work."]
 pub struct Connect<'transport, TransportSinkError, TransportStreamError>(
     BoxFuture<'transport, Result<(), ChMuxError<TransportSinkError, TransportStreamError>>>,
+    Metadata,
+    EventTx,
 );
 
 impl<'transport, TransportSinkError, TransportStreamError>
@@ -188,6 +215,13 @@ impl<'transport, TransportSinkError, TransportStreamError>
     /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
     ///
     /// This establishes a [chmux](crate::chmux) connection over the transport and opens a remote channel.
+    /// During the handshake the [metadata](crate::Cfg::metadata) of both endpoints is exchanged
+    /// and checked by the [acceptance check](crate::Cfg::accept_metadata), if any.
+    /// The metadata of the remote endpoint is available from [remote_metadata](Self::remote_metadata).
+    ///
+    /// If the [handshake](crate::Cfg::handshake_timeout) or opening the
+    /// [remote channel](crate::Cfg::base_channel_timeout) does not complete in time,
+    /// [ConnectError::Timeout] is returned.
     ///
     /// You must poll the returned [Connect] future or spawn it for the connection to work.
     ///
@@ -212,20 +246,49 @@ impl<'transport, TransportSinkError, TransportStreamError>
         Rx: RemoteSend,
         Codec: codec::Codec,
     {
-        let (mux, client, mut listener) = ChMux::new(cfg, transport_sink, transport_stream).await?;
-        let mut connection = Self(mux.run().boxed());
+        let base_channel_timeout = cfg.base_channel_timeout;
+        let (mux, client, mut listener) =
+            ChMux::new(cfg, transport_sink, transport_stream).await.map_err(|err| match err {
+                ChMuxError::Timeout => ConnectError::Timeout,
+                err => ConnectError::ChMux(err),
+            })?;
+        let remote_metadata = mux.remote_metadata().clone();
+        let events = mux.event_tx();
+        let mut connection = Self(mux.run().boxed(), remote_metadata, events);
+
+        let base_connect = async {
+            let fut = base::connect(&client, &mut listener);
+            match base_channel_timeout {
+                Some(dur) => {
+                    timeout(dur, fut).await.map_err(|_| ConnectError::Timeout)?.map_err(ConnectError::from)
+                }
+                None => fut.await.map_err(ConnectError::from),
+            }
+        };
 
         tokio::select! {
             biased;
             Err(err) = &mut connection => Err(err.into()),
-            result = base::connect(&client, &mut listener) => {
-                match result {
-                    Ok((tx, rx)) => Ok((connection, tx, rx)),
-                    Err(err) => Err(err.into()),
-                }
+            result = base_connect => {
+                let (tx, rx) = result?;
+                Ok((connection, tx, rx))
             }
         }
     }
+
+    /// Metadata of the remote endpoint exchanged during the connection handshake.
+    ///
+    /// This is empty if the remote endpoint did not provide any or does not support metadata exchange.
+    pub fn remote_metadata(&self) -> &Metadata {
+        &self.1
+    }
+
+    /// Subscribes to lifecycle events of the underlying [chmux](crate::chmux) connection.
+    ///
+    /// See [ChMux::subscribe_events] for details.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<Event> {
+        self.2.subscribe()
+    }
 }
 
 impl<'transport> Connect<'transport, io::Error, io::Error> {
@@ -348,12 +411,16 @@ impl LoopbackConnect {
         )
         .unwrap();
 
+        let remote_metadata = a_connect.remote_metadata().clone();
+        let events = a_connect.2.clone();
         let connection = Self(
             async move {
                 tokio::try_join!(a_connect, b_connect)?;
                 Ok(())
             }
             .boxed(),
+            remote_metadata,
+            events,
         );
 
         (connection, a_base_tx, b_base_rx)
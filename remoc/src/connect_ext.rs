@@ -1,6 +1,7 @@
 //! Connection extensions.
 
-use std::{error::Error, fmt, future::Future};
+use futures::FutureExt;
+use std::{error::Error, fmt, future::Future, time::Duration};
 
 use crate::{
     chmux::ChMuxError,
@@ -15,6 +16,7 @@ use crate::{RemoteSend, connect::Connect, rch::base};
 /// Error occurred during establishing a providing connection.
 #[cfg_attr(docsrs, doc(cfg(feature = "rch")))]
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum ProvideError<TransportSinkError, TransportStreamError> {
     /// Channel multiplexer error.
     ChMux(ChMuxError<TransportSinkError, TransportStreamError>),
@@ -22,6 +24,8 @@ pub enum ProvideError<TransportSinkError, TransportStreamError> {
     Connect(ConnectError<TransportSinkError, TransportStreamError>),
     /// Sending provided value failed.
     Send(SendError<()>),
+    /// Providing the value did not complete within the deadline.
+    Timeout,
 }
 
 impl<TransportSinkError, TransportStreamError> From<ChMuxError<TransportSinkError, TransportStreamError>>
@@ -59,6 +63,7 @@ where
             Self::ChMux(err) => write!(f, "chmux error: {err}"),
             Self::Connect(err) => write!(f, "connect error: {err}"),
             Self::Send(err) => write!(f, "send error: {err}"),
+            Self::Timeout => write!(f, "providing value timed out"),
         }
     }
 }
@@ -73,6 +78,7 @@ where
 /// Error occurred during establishing a consuming connection.
 #[cfg_attr(docsrs, doc(cfg(feature = "rch")))]
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum ConsumeError<TransportSinkError, TransportStreamError> {
     /// Channel multiplexer error.
     ChMux(ChMuxError<TransportSinkError, TransportStreamError>),
@@ -82,6 +88,8 @@ pub enum ConsumeError<TransportSinkError, TransportStreamError> {
     Recv(RecvError),
     /// No value to consume was received.
     NoValueReceived,
+    /// Consuming the value did not complete within the deadline.
+    Timeout,
 }
 
 impl<TransportSinkError, TransportStreamError> From<ChMuxError<TransportSinkError, TransportStreamError>>
@@ -120,6 +128,7 @@ where
             Self::Connect(err) => write!(f, "connect error: {err}"),
             Self::Recv(err) => write!(f, "receive error: {err}"),
             Self::NoValueReceived => write!(f, "no value was received for consumption"),
+            Self::Timeout => write!(f, "consuming value timed out"),
         }
     }
 }
@@ -160,6 +169,34 @@ pub trait ConnectExt<T, TransportSinkError, TransportStreamError> {
     fn consume(
         self,
     ) -> impl Future<Output = Result<T, ConsumeError<TransportSinkError, TransportStreamError>>> + Send;
+
+    /// Establishes the connection and provides a single value to the remote endpoint
+    /// within the specified time.
+    ///
+    /// This works like [provide](Self::provide), but fails with [ProvideError::Timeout]
+    /// if establishing the connection and sending the value do not complete within `timeout`.
+    fn provide_timeout(
+        self, value: T, timeout: Duration,
+    ) -> impl Future<Output = Result<(), ProvideError<TransportSinkError, TransportStreamError>>> + Send
+    where
+        Self: Sized,
+    {
+        exec::time::timeout(timeout, self.provide(value)).map(|res| res.unwrap_or(Err(ProvideError::Timeout)))
+    }
+
+    /// Establishes the connection and consumes a single value from the remote endpoint
+    /// within the specified time.
+    ///
+    /// This works like [consume](Self::consume), but fails with [ConsumeError::Timeout]
+    /// if establishing the connection and receiving the value do not complete within `timeout`.
+    fn consume_timeout(
+        self, timeout: Duration,
+    ) -> impl Future<Output = Result<T, ConsumeError<TransportSinkError, TransportStreamError>>> + Send
+    where
+        Self: Sized,
+    {
+        exec::time::timeout(timeout, self.consume()).map(|res| res.unwrap_or(Err(ConsumeError::Timeout)))
+    }
 }
 
 #[cfg(feature = "default-codec-set")]
@@ -0,0 +1,165 @@
+//! Connection over the standard input and output of a child process.
+
+use std::{io, process::Stdio, sync::Arc, time::Duration};
+use tokio::process::{Child, Command};
+
+use crate::{
+    RemoteSend,
+    chmux::ChMuxError,
+    codec,
+    connect::{Connect, ConnectError},
+    exec::time::timeout,
+    rch::base,
+};
+
+/// Handling of the standard error output of a child process.
+#[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ChildStderr {
+    /// Pass standard error through to the standard error of this process.
+    Inherit,
+    /// Discard standard error output.
+    Null,
+    /// Capture standard error output.
+    ///
+    /// It is available through the [stderr](tokio::process::Child::stderr) field of the returned
+    /// child handle and must be read, otherwise the child process may block when the pipe is full.
+    Piped,
+}
+
+impl From<ChildStderr> for Stdio {
+    fn from(stderr: ChildStderr) -> Self {
+        match stderr {
+            ChildStderr::Inherit => Stdio::inherit(),
+            ChildStderr::Null => Stdio::null(),
+            ChildStderr::Piped => Stdio::piped(),
+        }
+    }
+}
+
+/// Configuration for establishing a connection to a child process.
+#[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChildCfg {
+    /// Handling of the standard error output of the child process.
+    ///
+    /// By default it is passed through to this process.
+    pub stderr: ChildStderr,
+    /// Whether the child process is killed when the returned [Child] handle is dropped.
+    ///
+    /// By default this is true.
+    pub kill_on_drop: bool,
+    /// Size of read and write buffers for the standard input and output pipes in bytes.
+    ///
+    /// By default this is 8 kB.
+    pub buffer: usize,
+    /// Time the child process is given to exit after it has closed its standard output,
+    /// before a transport error is reported instead of its exit status.
+    ///
+    /// By default this is 1 second.
+    pub exit_grace: Duration,
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl Default for ChildCfg {
+    fn default() -> Self {
+        Self {
+            stderr: ChildStderr::Inherit,
+            kill_on_drop: true,
+            buffer: 8192,
+            exit_grace: Duration::from_secs(1),
+            _non_exhaustive: (),
+        }
+    }
+}
+
+impl Connect<'static, io::Error, io::Error> {
+    /// Spawns a child process and establishes a connection over its standard input and output.
+    ///
+    /// The standard input and output of the `command` are replaced by pipes and a
+    /// buffered [chmux](crate::chmux) connection is established over them.
+    /// The child process should use [Connect::stdio] to accept the connection.
+    /// Standard error is handled as specified in the [child configuration](ChildCfg).
+    ///
+    /// Returns the connection, a remote [sender](base::Sender) and [receiver](base::Receiver),
+    /// as well as the handle of the spawned child process.
+    /// If the child process exits before the connection is established,
+    /// [ConnectError::ProcessExited] is returned.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    pub async fn child<Tx, Rx, Codec>(
+        cfg: crate::Cfg, child_cfg: ChildCfg, mut command: Command,
+    ) -> Result<
+        (Connect<'static, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>, Child),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(child_cfg.stderr)
+            .kill_on_drop(child_cfg.kill_on_drop);
+
+        let mut child = command.spawn().map_err(|err| ConnectError::Spawn(Arc::new(err)))?;
+        let input = child.stdout.take().expect("child stdout not piped");
+        let output = child.stdin.take().expect("child stdin not piped");
+
+        let res = tokio::select! {
+            biased;
+            res = Self::io_buffered(cfg, input, output, child_cfg.buffer) => res,
+            res = child.wait() => match res {
+                Ok(status) => return Err(ConnectError::ProcessExited(status)),
+                Err(err) => return Err(ConnectError::ChMux(ChMuxError::StreamError(err))),
+            },
+        };
+
+        match res {
+            Ok((conn, tx, rx)) => Ok((conn, tx, rx, child)),
+            Err(err) => match timeout(child_cfg.exit_grace, child.wait()).await {
+                Ok(Ok(status)) => Err(ConnectError::ProcessExited(status)),
+                _ => Err(err),
+            },
+        }
+    }
+
+    /// Establishes a connection over the standard input and output of this process.
+    ///
+    /// This is intended to be used by a worker process that has been spawned by
+    /// a parent process using [Connect::child].
+    /// Returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// Nothing else must be written to standard output while the connection is in use,
+    /// since this would corrupt the transport.
+    /// Use standard error for logging and diagnostic output instead.
+    /// The connection terminates when the parent process closes its end of the pipes.
+    ///
+    /// Reads and writes are buffered using `buffer` bytes, which should match the
+    /// [buffer size](ChildCfg::buffer) used by the parent process.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    pub async fn stdio<Tx, Rx, Codec>(
+        cfg: crate::Cfg, buffer: usize,
+    ) -> Result<
+        (Connect<'static, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        Self::io_buffered(cfg, tokio::io::stdin(), tokio::io::stdout(), buffer).await
+    }
+}
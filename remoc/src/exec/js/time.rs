@@ -211,6 +211,22 @@ pub fn sleep(duration: Duration) -> Sleep {
     Sleep::new(duration)
 }
 
+/// A measurement of the system clock in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Instant(f64);
+
+impl Instant {
+    /// Returns an instant corresponding to "now".
+    pub fn now() -> Self {
+        Self(js_sys::Date::now())
+    }
+
+    /// Returns the amount of time elapsed since this instant.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_secs_f64((Self::now().0 - self.0).max(0.0) / 1000.0)
+    }
+}
+
 pub mod error {
     use super::*;
 
@@ -90,6 +90,25 @@
 //! For ease of use all features are enabled by default.
 //! See the [codec module](codec) documentation on how to select a default codec.
 //!
+//! The `process` feature is not enabled by default.
+//! It provides `Connect::child` and `Connect::stdio` for talking to a worker process
+//! over its standard input and output and is not available on WebAssembly targets.
+//! The `websocket` feature is not enabled by default either.
+//! It provides a WebSocket transport in the `websocket` module, which uses tokio-tungstenite on
+//! native platforms and the JavaScript WebSocket API when the `js` feature is enabled.
+//! The `serve` feature, also not enabled by default, provides a server in the `serve` module
+//! that accepts and tracks multiple TCP or UNIX domain socket connections.
+//! The `tunnel` feature, likewise not enabled by default, provides TCP port forwarding
+//! over a Remoc connection in the `tunnel` module.
+//! The `diff-derive` feature, which is part of `full`, provides the derive macro for
+//! the `rch::watch::Diff` trait of delta-encoded watch channels.
+//! The `io-digest` feature enables SHA-256 and BLAKE3 content digest verification
+//! for the binary data channel in `rch::io`.
+//! The `fs` feature allows creating a `robj::lazy_blob::LazyBlob` from a file,
+//! which is streamed from disk when requested, and provides `rch::mpsc::SpooledSender`,
+//! which persists values to a spool file until they have been acknowledged;
+//! it is not available on WebAssembly targets.
+//!
 //! ### JavaScript and web support
 //!
 //! Remoc supports compiling to the WebAssembly targets `wasm32-unknown-unknown`,
@@ -256,6 +275,28 @@ mod connect_ext;
 #[cfg_attr(docsrs, doc(cfg(feature = "rch")))]
 pub use connect_ext::{ConnectExt, ConsumeError, ProvideError};
 
+#[cfg(feature = "process")]
+mod connect_process;
+#[cfg(feature = "process")]
+#[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+pub use connect_process::{ChildCfg, ChildStderr};
+
+#[cfg(feature = "rch")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rch")))]
+pub mod relay;
+
+#[cfg(feature = "serve")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serve")))]
+pub mod serve;
+
+#[cfg(feature = "tunnel")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tunnel")))]
+pub mod tunnel;
+
+#[cfg(feature = "websocket")]
+#[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+pub mod websocket;
+
 #[cfg(feature = "rfn")]
 #[cfg_attr(docsrs, doc(cfg(feature = "rfn")))]
 pub mod rfn;
@@ -272,9 +313,9 @@ pub mod robs;
 #[cfg_attr(docsrs, doc(cfg(feature = "rtc")))]
 pub mod rtc;
 
-// Re-export serde for remoc_macro used by rtc.
+// Re-export serde for remoc_macro used by rtc and rch::watch.
 #[doc(hidden)]
-#[cfg(feature = "rtc")]
+#[cfg(any(feature = "rtc", feature = "diff-derive"))]
 pub use serde as _serde;
 
 #[cfg(any(feature = "rfn", feature = "robj"))]
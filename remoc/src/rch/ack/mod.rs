@@ -0,0 +1,110 @@
+//! Channel with per-item acknowledgements providing at-least-once delivery.
+//!
+//! The receiver acknowledges each received value after it has processed it.
+//! The sender keeps a copy of each value until it has been acknowledged and
+//! can [report](Sender::unacked), [redeliver](Sender::redeliver) or
+//! [take back](Sender::take_unacked) unacknowledged values, for example to
+//! send them to another receiver after the connection has been lost.
+//! [Sender::send_confirmed] returns only once the receiver has acknowledged
+//! the value.
+//!
+//! Acknowledgements are sent back over a single channel shared by all values,
+//! which is much more efficient than sending a [oneshot](super::oneshot) channel
+//! along with each value.
+//! Nevertheless, you should send further values while waiting for acknowledgements
+//! to avoid being limited by the connection roundtrip time.
+//!
+//! The receiver can be sent to a remote endpoint.
+//! The sender keeps the unacknowledged values and thus must stay on the endpoint
+//! that created the channel.
+//! Both halves may be local.
+//!
+//! # Example
+//!
+//! In the following example the client sends the receiver to the server, which
+//! acknowledges each value after processing it.
+//!
+//! ```
+//! use remoc::prelude::*;
+//!
+//! // This would be run on the client.
+//! async fn client(mut tx: rch::base::Sender<rch::ack::Receiver<u32>>) {
+//!     let (ack_tx, ack_rx) = rch::ack::channel(1);
+//!     tx.send(ack_rx).await.unwrap();
+//!
+//!     for i in 0..4 {
+//!         ack_tx.send_confirmed(i).await.unwrap();
+//!     }
+//!     assert!(ack_tx.unacked().is_empty());
+//! }
+//!
+//! // This would be run on the server.
+//! async fn server(mut rx: rch::base::Receiver<rch::ack::Receiver<u32>>) {
+//!     let mut ack_rx = rx.recv().await.unwrap().unwrap();
+//!     let mut expected = 0;
+//!     while let Some(delivery) = ack_rx.recv().await.unwrap() {
+//!         assert_eq!(*delivery, expected);
+//!         expected += 1;
+//!         delivery.ack().await.unwrap();
+//!     }
+//!     assert_eq!(expected, 4);
+//! }
+//! # tokio_test::block_on(remoc::doctest::client_server(client, server));
+//! ```
+//!
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+use super::mpsc;
+use crate::{RemoteSend, codec, exec};
+
+mod receiver;
+mod sender;
+
+pub use receiver::{Delivery, Receiver};
+pub use sender::{ConfirmError, Confirmation, Sender};
+
+/// Creates a new acknowledged channel.
+///
+/// Up to `local_buffer` values and acknowledgements are queued locally.
+/// The receiver may be sent to remote endpoints via channels.
+pub fn channel<T, Codec>(local_buffer: usize) -> (Sender<T, Codec>, Receiver<T, Codec>)
+where
+    T: RemoteSend + Clone,
+    Codec: codec::Codec,
+{
+    let (tx, rx) = mpsc::channel(local_buffer);
+    let (ack_tx, ack_rx) = mpsc::channel(local_buffer);
+
+    let sender = Sender::new(tx);
+    exec::spawn(process_acks(sender.state(), ack_rx));
+
+    (sender, Receiver::new(rx, ack_tx))
+}
+
+/// Value transmitted over the channel.
+#[derive(Serialize, Deserialize)]
+struct Item<T> {
+    id: u64,
+    value: T,
+    redelivered: bool,
+}
+
+/// Removes acknowledged values from the sender state.
+async fn process_acks<T, Codec>(state: Arc<Mutex<sender::State<T>>>, mut ack_rx: mpsc::Receiver<u64, Codec>)
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    loop {
+        match ack_rx.recv().await {
+            Ok(Some(id)) => state.lock().unwrap().acked(id),
+            Ok(None) => break,
+            Err(err) if err.is_final() => break,
+            Err(_) => (),
+        }
+    }
+
+    state.lock().unwrap().close();
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use super::{super::mpsc, Item};
+use crate::{RemoteSend, codec};
+
+/// Receiving-half of an acknowledged channel.
+///
+/// Can be sent over a remote channel.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "T: RemoteSend, Codec: codec::Codec"))]
+#[serde(bound(deserialize = "T: RemoteSend, Codec: codec::Codec"))]
+pub struct Receiver<T, Codec = codec::Default> {
+    rx: mpsc::Receiver<Item<T>, Codec>,
+    ack_tx: mpsc::Sender<u64, Codec>,
+}
+
+impl<T, Codec> fmt::Debug for Receiver<T, Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Receiver").finish()
+    }
+}
+
+impl<T, Codec> Receiver<T, Codec>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    pub(super) fn new(rx: mpsc::Receiver<Item<T>, Codec>, ack_tx: mpsc::Sender<u64, Codec>) -> Self {
+        Self { rx, ack_tx }
+    }
+
+    /// Receives the next value.
+    ///
+    /// The value must be [acknowledged](Delivery::ack) after it has been processed.
+    /// Returns `Ok(None)` when all senders have been dropped.
+    pub async fn recv(&mut self) -> Result<Option<Delivery<T, Codec>>, mpsc::RecvError> {
+        let Some(Item { id, value, redelivered }) = self.rx.recv().await? else { return Ok(None) };
+        Ok(Some(Delivery { id, value, redelivered, ack_tx: self.ack_tx.clone() }))
+    }
+}
+
+/// A value received over an acknowledged channel.
+///
+/// This dereferences to the received value.
+/// Dropping it without [acknowledging](Self::ack) keeps the value unacknowledged
+/// at the sender.
+pub struct Delivery<T, Codec = codec::Default> {
+    id: u64,
+    value: T,
+    redelivered: bool,
+    ack_tx: mpsc::Sender<u64, Codec>,
+}
+
+impl<T, Codec> fmt::Debug for Delivery<T, Codec>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Delivery")
+            .field("id", &self.id)
+            .field("value", &self.value)
+            .field("redelivered", &self.redelivered)
+            .finish()
+    }
+}
+
+impl<T, Codec> Delivery<T, Codec>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    /// Id of the value assigned by the sender.
+    ///
+    /// A redelivered value has the same id as its original delivery.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Whether the value has been [redelivered](super::Sender::redeliver).
+    ///
+    /// It may have been received and processed before.
+    pub fn is_redelivered(&self) -> bool {
+        self.redelivered
+    }
+
+    /// Acknowledges that the value has been processed.
+    pub async fn ack(self) -> Result<(), mpsc::SendError<()>> {
+        self.ack_tx.send(self.id).await.map_err(|err| err.without_item())?;
+        Ok(())
+    }
+}
+
+impl<T, Codec> Deref for Delivery<T, Codec> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, Codec> DerefMut for Delivery<T, Codec> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
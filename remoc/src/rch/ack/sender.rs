@@ -0,0 +1,244 @@
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use super::{
+    super::{Sending, SendingErrorKind, mpsc},
+    Item,
+};
+use crate::{RemoteSend, codec};
+
+/// An error occurred while waiting for the acknowledgement of a sent value.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ConfirmError {
+    /// Sending the value failed.
+    Send(mpsc::SendError<()>),
+    /// The value failed to send after it has been queued for sending.
+    ///
+    /// The value remains unacknowledged.
+    Sending(SendingErrorKind),
+    /// The receiver was dropped or disconnected before acknowledging the value.
+    ///
+    /// The value remains unacknowledged.
+    Closed,
+    /// The value was taken by [Sender::take_unacked] before it was acknowledged.
+    Taken,
+}
+
+impl fmt::Display for ConfirmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Send(err) => write!(f, "send error: {err}"),
+            Self::Sending(err) => write!(f, "sending error: {err}"),
+            Self::Closed => write!(f, "receiver closed before acknowledging"),
+            Self::Taken => write!(f, "value taken before acknowledgement"),
+        }
+    }
+}
+
+impl Error for ConfirmError {}
+
+/// A value that has not been acknowledged yet.
+struct Unacked<T> {
+    value: T,
+    confirm_tx: Option<tokio::sync::oneshot::Sender<Result<(), ConfirmError>>>,
+}
+
+/// State shared between the senders and the acknowledgement processing task.
+pub(super) struct State<T> {
+    next_id: u64,
+    unacked: BTreeMap<u64, Unacked<T>>,
+    closed: bool,
+}
+
+impl<T> State<T> {
+    /// Marks the value with the specified id as acknowledged.
+    pub(super) fn acked(&mut self, id: u64) {
+        if let Some(Unacked { confirm_tx: Some(confirm_tx), .. }) = self.unacked.remove(&id) {
+            let _ = confirm_tx.send(Ok(()));
+        }
+    }
+
+    /// Marks the channel as closed, failing all pending confirmations.
+    pub(super) fn close(&mut self) {
+        self.closed = true;
+        for unacked in self.unacked.values_mut() {
+            if let Some(confirm_tx) = unacked.confirm_tx.take() {
+                let _ = confirm_tx.send(Err(ConfirmError::Closed));
+            }
+        }
+    }
+}
+
+/// Sending-half of an acknowledged channel.
+///
+/// Keeps a copy of each sent value until the receiver has acknowledged it.
+/// Cloned senders share the unacknowledged values.
+///
+/// Cannot be sent to a remote endpoint.
+pub struct Sender<T, Codec = codec::Default> {
+    tx: mpsc::Sender<Item<T>, Codec>,
+    state: Arc<Mutex<State<T>>>,
+}
+
+impl<T, Codec> fmt::Debug for Sender<T, Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+impl<T, Codec> Clone for Sender<T, Codec> {
+    fn clone(&self) -> Self {
+        Self { tx: self.tx.clone(), state: self.state.clone() }
+    }
+}
+
+impl<T, Codec> Sender<T, Codec>
+where
+    T: RemoteSend + Clone,
+    Codec: codec::Codec,
+{
+    pub(super) fn new(tx: mpsc::Sender<Item<T>, Codec>) -> Self {
+        let state = State { next_id: 0, unacked: BTreeMap::new(), closed: false };
+        Self { tx, state: Arc::new(Mutex::new(state)) }
+    }
+
+    pub(super) fn state(&self) -> Arc<Mutex<State<T>>> {
+        self.state.clone()
+    }
+
+    /// Sends a value over this channel.
+    ///
+    /// A copy of the value is kept until the receiver acknowledges it.
+    /// Await the returned [Confirmation] to wait for the acknowledgement.
+    pub async fn send(&self, value: T) -> Result<Confirmation<T>, mpsc::SendError<T>> {
+        let (confirm_tx, confirm_rx) = tokio::sync::oneshot::channel();
+
+        let id = {
+            let mut state = self.state.lock().unwrap();
+            let id = state.next_id;
+            state.next_id += 1;
+
+            let confirm_tx = if state.closed {
+                let _ = confirm_tx.send(Err(ConfirmError::Closed));
+                None
+            } else {
+                Some(confirm_tx)
+            };
+            state.unacked.insert(id, Unacked { value: value.clone(), confirm_tx });
+
+            id
+        };
+
+        match self.tx.send(Item { id, value, redelivered: false }).await {
+            Ok(sending) => Ok(Confirmation { id, confirm_rx, sending: Some(sending) }),
+            Err(err) => {
+                self.state.lock().unwrap().unacked.remove(&id);
+                Err(match err {
+                    mpsc::SendError::Closed(item) => mpsc::SendError::Closed(item.value),
+                    mpsc::SendError::RemoteSend(err) => mpsc::SendError::RemoteSend(err),
+                    mpsc::SendError::RemoteConnect(err) => mpsc::SendError::RemoteConnect(err),
+                    mpsc::SendError::RemoteListen(err) => mpsc::SendError::RemoteListen(err),
+                    mpsc::SendError::RemoteForward => mpsc::SendError::RemoteForward,
+                })
+            }
+        }
+    }
+
+    /// Sends a value over this channel and waits until the receiver has acknowledged it.
+    pub async fn send_confirmed(&self, value: T) -> Result<(), ConfirmError> {
+        let confirmation = self.send(value).await.map_err(|err| ConfirmError::Send(err.without_item()))?;
+        confirmation.await
+    }
+
+    /// Sends all unacknowledged values again.
+    ///
+    /// The receiver sees them [marked as redelivered](super::Delivery::is_redelivered).
+    /// Pending [confirmations](Confirmation) remain valid.
+    /// Returns the number of values sent.
+    pub async fn redeliver(&self) -> Result<usize, mpsc::SendError<()>> {
+        let unacked = self.unacked();
+        let n = unacked.len();
+
+        for (id, value) in unacked {
+            self.tx.send(Item { id, value, redelivered: true }).await.map_err(|err| err.without_item())?;
+        }
+
+        Ok(n)
+    }
+
+    /// Returns the ids and copies of all values that have not been acknowledged yet
+    /// in the order they were sent.
+    pub fn unacked(&self) -> Vec<(u64, T)> {
+        let state = self.state.lock().unwrap();
+        state.unacked.iter().map(|(id, unacked)| (*id, unacked.value.clone())).collect()
+    }
+
+    /// Removes all values that have not been acknowledged yet and returns them
+    /// in the order they were sent.
+    ///
+    /// Pending [confirmations](Confirmation) of these values fail with [ConfirmError::Taken]
+    /// and acknowledgements received for them later are ignored.
+    pub fn take_unacked(&self) -> Vec<T> {
+        let mut state = self.state.lock().unwrap();
+        let unacked = std::mem::take(&mut state.unacked);
+        unacked.into_values().map(|unacked| unacked.value).collect()
+    }
+
+    /// Returns whether the receiver has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+}
+
+/// Handle to await the acknowledgement of a sent value.
+///
+/// Dropping the handle keeps the value unacknowledged until the receiver
+/// acknowledges it.
+pub struct Confirmation<T> {
+    id: u64,
+    confirm_rx: tokio::sync::oneshot::Receiver<Result<(), ConfirmError>>,
+    sending: Option<Sending<Item<T>>>,
+}
+
+impl<T> fmt::Debug for Confirmation<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Confirmation").field("id", &self.id).finish()
+    }
+}
+
+impl<T> Confirmation<T> {
+    /// Id of the sent value.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl<T> Future for Confirmation<T> {
+    type Output = Result<(), ConfirmError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        if let Poll::Ready(res) = self.confirm_rx.poll_unpin(cx) {
+            return Poll::Ready(res.unwrap_or(Err(ConfirmError::Taken)));
+        }
+
+        if let Some(sending) = &mut self.sending
+            && let Poll::Ready(res) = sending.poll_unpin(cx)
+        {
+            self.sending = None;
+            if let Err(err) = res {
+                return Poll::Ready(Err(ConfirmError::Sending(err.kind())));
+            }
+        }
+
+        Poll::Pending
+    }
+}
@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt,
+    hash::{BuildHasher, Hash},
+    ops::{Deref, DerefMut},
+};
+
+use super::{
+    super::{DEFAULT_BUFFER, DEFAULT_MAX_ITEM_SIZE, watch},
+    Receiver,
+};
+use crate::{RemoteSend, codec};
+
+/// A predicate selecting the values a [FilteredReceiver] is interested in.
+///
+/// The filter is transmitted to and evaluated by the broadcast [Sender](super::Sender),
+/// thus it must be serializable.
+pub trait Filter<T> {
+    /// Returns whether the value should be sent to the receiver.
+    fn matches(&self, value: &T) -> bool;
+}
+
+/// A set matches all values it contains.
+impl<T, S> Filter<T> for HashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    fn matches(&self, value: &T) -> bool {
+        self.contains(value)
+    }
+}
+
+/// A set matches all values it contains.
+impl<T> Filter<T> for BTreeSet<T>
+where
+    T: Ord,
+{
+    fn matches(&self, value: &T) -> bool {
+        self.contains(value)
+    }
+}
+
+/// No filter matches all values.
+impl<T, F> Filter<T> for Option<F>
+where
+    F: Filter<T>,
+{
+    fn matches(&self, value: &T) -> bool {
+        self.as_ref().is_none_or(|filter| filter.matches(value))
+    }
+}
+
+/// Receiving-half of the broadcast channel that only receives values
+/// matching a filter.
+///
+/// Instances are created by [Sender::subscribe_filtered](super::Sender::subscribe_filtered).
+/// This dereferences to a broadcast [Receiver] for receiving values.
+///
+/// Can be sent over a remote channel.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "T: RemoteSend, F: RemoteSend + Sync + Clone, Codec: codec::Codec"))]
+#[serde(bound(deserialize = "T: RemoteSend, F: RemoteSend + Sync + Clone, Codec: codec::Codec"))]
+pub struct FilteredReceiver<
+    T,
+    F,
+    Codec = codec::Default,
+    const BUFFER: usize = DEFAULT_BUFFER,
+    const MAX_ITEM_SIZE: usize = DEFAULT_MAX_ITEM_SIZE,
+> {
+    receiver: Receiver<T, Codec, BUFFER, MAX_ITEM_SIZE>,
+    filter_tx: watch::Sender<F, Codec>,
+}
+
+impl<T, F, Codec, const BUFFER: usize, const MAX_ITEM_SIZE: usize> fmt::Debug
+    for FilteredReceiver<T, F, Codec, BUFFER, MAX_ITEM_SIZE>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FilteredReceiver").finish()
+    }
+}
+
+impl<T, F, Codec, const BUFFER: usize, const MAX_ITEM_SIZE: usize>
+    FilteredReceiver<T, F, Codec, BUFFER, MAX_ITEM_SIZE>
+where
+    F: Send + 'static,
+{
+    pub(crate) fn new(
+        receiver: Receiver<T, Codec, BUFFER, MAX_ITEM_SIZE>, filter_tx: watch::Sender<F, Codec>,
+    ) -> Self {
+        Self { receiver, filter_tx }
+    }
+
+    /// Returns the current filter.
+    pub fn filter(&self) -> watch::Ref<'_, F> {
+        self.filter_tx.borrow()
+    }
+
+    /// Changes the filter.
+    ///
+    /// The new filter applies to values sent after it has reached the sender.
+    /// Values already in transit are received even if they do not match the new filter.
+    pub fn set_filter(&self, filter: F) -> Result<(), watch::SendError> {
+        self.filter_tx.send(filter)
+    }
+
+    /// Converts this into the underlying broadcast [Receiver].
+    ///
+    /// The filter remains in effect but cannot be changed anymore.
+    pub fn into_inner(self) -> Receiver<T, Codec, BUFFER, MAX_ITEM_SIZE> {
+        self.receiver
+    }
+}
+
+impl<T, F, Codec, const BUFFER: usize, const MAX_ITEM_SIZE: usize> Deref
+    for FilteredReceiver<T, F, Codec, BUFFER, MAX_ITEM_SIZE>
+{
+    type Target = Receiver<T, Codec, BUFFER, MAX_ITEM_SIZE>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl<T, F, Codec, const BUFFER: usize, const MAX_ITEM_SIZE: usize> DerefMut
+    for FilteredReceiver<T, F, Codec, BUFFER, MAX_ITEM_SIZE>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.receiver
+    }
+}
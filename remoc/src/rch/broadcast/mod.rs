@@ -7,16 +7,26 @@
 //!
 //! This has similar functionality as [tokio::sync::broadcast] with the additional
 //! ability to work over remote connections.
+//!
+//! A sender created by [`Sender::with_history`] retains recently sent values
+//! and replays them to receivers that subscribe later.
+//!
+//! A receiver created by [`Sender::subscribe_filtered`] only receives values matching
+//! its [filter](Filter).
+//! The filter is evaluated by the sender, so that values are not transmitted
+//! to remote receivers that are not interested in them.
 
 use serde::{Deserialize, Serialize};
 
 use crate::{RemoteSend, codec};
 
+mod filter;
 mod receiver;
 mod sender;
 
+pub use filter::{Filter, FilteredReceiver};
 pub use receiver::{Receiver, ReceiverStream, RecvError, StreamError, TryRecvError};
-pub use sender::{Broadcasting, SendError, Sender, Sending};
+pub use sender::{Broadcasting, History, SendError, Sender, Sending};
 
 /// Broadcast transport message.
 #[derive(Clone, Debug, Serialize, Deserialize)]
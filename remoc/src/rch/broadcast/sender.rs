@@ -1,6 +1,7 @@
 use futures::FutureExt;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::VecDeque,
     convert::{TryFrom, TryInto},
     error::Error,
     fmt,
@@ -9,13 +10,14 @@ use std::{
     pin::Pin,
     sync::{Arc, Mutex},
     task::{Context, Poll, ready},
+    time::Duration,
 };
 
 use super::{
-    super::{SendErrorExt, Sending as BaseSending, SendingError, base, mpsc},
-    BroadcastMsg, Receiver,
+    super::{DEFAULT_MAX_ITEM_SIZE, SendErrorExt, Sending as BaseSending, SendingError, base, mpsc},
+    BroadcastMsg, Filter, FilteredReceiver, Receiver,
 };
-use crate::{RemoteSend, chmux, codec, exec};
+use crate::{RemoteSend, chmux, codec, exec, exec::time::Instant, rch::watch};
 
 /// An error occurred during sending over a broadcast channel.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -114,6 +116,18 @@ impl<T> SendErrorExt for SendError<T> {
     }
 }
 
+/// Values retained by a broadcast [Sender] and replayed to new receivers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum History {
+    /// No values are retained.
+    #[default]
+    None,
+    /// The specified number of most recently sent values are retained.
+    Last(usize),
+    /// Values sent within the specified duration are retained.
+    Window(Duration),
+}
+
 /// Sending-half of the broadcast channel.
 ///
 /// Cannot be sent over a remote channel.
@@ -124,11 +138,48 @@ pub struct Sender<T, Codec = codec::Default> {
     inner: Arc<Mutex<SenderInner<T, Codec>>>,
 }
 
+/// Predicate deciding whether a value is sent to a subscriber.
+type FilterFn<T> = Box<dyn Fn(&T) -> bool + Send>;
+
+/// A receiver subscribed to the broadcast sender.
+struct Subscriber<T, Codec> {
+    tx: mpsc::Sender<BroadcastMsg<T>, Codec, 1>,
+    filter: Option<FilterFn<T>>,
+}
+
+impl<T, Codec> Subscriber<T, Codec> {
+    /// Whether the value should be sent to the subscriber.
+    fn wants(&self, value: &T) -> bool {
+        self.filter.as_ref().is_none_or(|filter| filter(value))
+    }
+}
+
 struct SenderInner<T, Codec> {
-    subs: Vec<mpsc::Sender<BroadcastMsg<T>, Codec, 1>>,
-    ready_tx: tokio::sync::mpsc::UnboundedSender<mpsc::Sender<BroadcastMsg<T>, Codec, 1>>,
-    ready_rx: tokio::sync::mpsc::UnboundedReceiver<mpsc::Sender<BroadcastMsg<T>, Codec, 1>>,
+    subs: Vec<Subscriber<T, Codec>>,
+    ready_tx: tokio::sync::mpsc::UnboundedSender<Subscriber<T, Codec>>,
+    ready_rx: tokio::sync::mpsc::UnboundedReceiver<Subscriber<T, Codec>>,
     not_ready: usize,
+    history: History,
+    retained: VecDeque<(Instant, T)>,
+}
+
+impl<T, Codec> SenderInner<T, Codec> {
+    /// Removes values from the history that are no longer retained.
+    fn prune_history(&mut self) {
+        match self.history {
+            History::None => self.retained.clear(),
+            History::Last(len) => {
+                while self.retained.len() > len {
+                    self.retained.pop_front();
+                }
+            }
+            History::Window(window) => {
+                while self.retained.front().is_some_and(|(sent, _)| sent.elapsed() > window) {
+                    self.retained.pop_front();
+                }
+            }
+        }
+    }
 }
 
 impl<T, Codec> fmt::Debug for Sender<T, Codec> {
@@ -154,17 +205,62 @@ where
 {
     /// Creates the sending-half of the broadcast channel.
     pub fn new() -> Self {
+        Self::with_history(History::None)
+    }
+
+    /// Creates the sending-half of the broadcast channel that retains
+    /// the specified history of sent values.
+    ///
+    /// The retained values are replayed to each new receiver before it
+    /// receives the values sent after its subscription.
+    /// This also applies to receivers that are sent to remote endpoints.
+    pub fn with_history(history: History) -> Self {
         let (ready_tx, ready_rx) = tokio::sync::mpsc::unbounded_channel();
-        let inner = SenderInner { subs: Vec::new(), ready_tx, ready_rx, not_ready: 0 };
+        let inner = SenderInner {
+            subs: Vec::new(),
+            ready_tx,
+            ready_rx,
+            not_ready: 0,
+            history,
+            retained: VecDeque::new(),
+        };
         Self { inner: Arc::new(Mutex::new(inner)) }
     }
 
+    /// Returns which sent values are retained and replayed to new receivers.
+    pub fn history(&self) -> History {
+        self.inner.lock().unwrap().history
+    }
+
+    /// Sets which sent values are retained and replayed to new receivers.
+    pub fn set_history(&self, history: History) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.history = history;
+        inner.prune_history();
+    }
+
+    /// Returns the currently retained values, oldest first.
+    pub fn retained(&self) -> Vec<T> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.prune_history();
+        inner.retained.iter().map(|(_, value)| value.clone()).collect()
+    }
+
     /// Attempts to send a value to all active receivers.
     ///
     /// No back-pressure is provided.
+    ///
+    /// If a [history](History) is configured, the value is retained even if
+    /// there are no active receivers.
     pub fn send(&self, value: T) -> Result<Broadcasting<T>, SendError<T>> {
         let mut inner = self.inner.lock().unwrap();
 
+        // Retain value for replay to future subscribers.
+        if inner.history != History::None {
+            inner.retained.push_back((Instant::now(), value.clone()));
+            inner.prune_history();
+        }
+
         // Fetch subscribers that have become ready again.
         while let Ok(sub) = inner.ready_rx.try_recv() {
             inner.subs.push(sub);
@@ -178,7 +274,12 @@ where
         let subs = mem::take(&mut inner.subs);
         let mut broadcasted = Vec::with_capacity(subs.len());
         for sub in subs {
-            match sub.try_send(BroadcastMsg::Value(value.clone())) {
+            if !sub.wants(&value) {
+                keep.push(sub);
+                continue;
+            }
+
+            match sub.tx.try_send(BroadcastMsg::Value(value.clone())) {
                 Ok(sent) => {
                     broadcasted.push(Sending(sent));
                     keep.push(sub);
@@ -188,9 +289,9 @@ where
                     // then add it back to subscriber list.
                     let ready_tx = inner.ready_tx.clone();
                     exec::spawn(async move {
-                        let _ = sub.send(BroadcastMsg::Lagged).await;
+                        let _ = sub.tx.send(BroadcastMsg::Lagged).await;
                         // Make sure subscriber has space for next message.
-                        let _permit = sub.reserve().await;
+                        let _permit = sub.tx.reserve().await;
                         let _ = ready_tx.send(sub);
                     });
                     inner.not_ready += 1;
@@ -216,36 +317,73 @@ where
     }
 
     /// Creates a new receiver that will receive values sent after this call to subscribe.
+    ///
+    /// If a [history](History) is configured, the retained values are received first.
     pub fn subscribe<const RECEIVE_BUFFER: usize>(
         &self, send_buffer: usize,
     ) -> Receiver<T, Codec, RECEIVE_BUFFER> {
-        let mut inner = self.inner.lock().unwrap();
-
-        let (tx, rx) = mpsc::channel(send_buffer);
-        let tx = tx.set_buffer();
-        let rx = rx.set_buffer();
-        inner.subs.push(tx);
-        Receiver::new(rx)
+        self.subscribe_with_max_item_size::<RECEIVE_BUFFER, DEFAULT_MAX_ITEM_SIZE>(send_buffer)
     }
 
     /// Creates a new receiver with a custom maximum item size.
     pub fn subscribe_with_max_item_size<const RECEIVE_BUFFER: usize, const MAX_ITEM_SIZE: usize>(
         &self, send_buffer: usize,
+    ) -> Receiver<T, Codec, RECEIVE_BUFFER, MAX_ITEM_SIZE> {
+        self.add_subscriber(send_buffer, None)
+    }
+
+    /// Creates a new receiver that will only receive values sent after this call
+    /// to subscribe and matching the specified filter.
+    ///
+    /// The filter is evaluated by this sender, thus values not matching the filter
+    /// are not transmitted to a remote receiver.
+    /// It can be changed at any time using [`FilteredReceiver::set_filter`], also
+    /// after the receiver has been sent to a remote endpoint.
+    ///
+    /// If a [history](History) is configured, the retained values matching the filter
+    /// are received first.
+    pub fn subscribe_filtered<F, const RECEIVE_BUFFER: usize>(
+        &self, send_buffer: usize, filter: F,
+    ) -> FilteredReceiver<T, F, Codec, RECEIVE_BUFFER>
+    where
+        F: Filter<T> + RemoteSend + Sync + Clone,
+    {
+        let (filter_tx, filter_rx) = watch::channel::<F, Codec>(filter);
+        let filter: FilterFn<T> = Box::new(move |value| match filter_rx.borrow() {
+            Ok(filter) => filter.matches(value),
+            Err(_) => true,
+        });
+        let receiver = self.add_subscriber(send_buffer, Some(filter));
+        FilteredReceiver::new(receiver, filter_tx)
+    }
+
+    /// Adds a subscriber receiving values matching the optional filter.
+    fn add_subscriber<const RECEIVE_BUFFER: usize, const MAX_ITEM_SIZE: usize>(
+        &self, send_buffer: usize, filter: Option<FilterFn<T>>,
     ) -> Receiver<T, Codec, RECEIVE_BUFFER, MAX_ITEM_SIZE> {
         let mut inner = self.inner.lock().unwrap();
+        inner.prune_history();
 
-        let (tx, rx) = mpsc::channel(send_buffer);
+        // Enlarge buffer to hold the replayed history.
+        let (tx, rx) = mpsc::channel(send_buffer + inner.retained.len());
         let mut tx = tx.set_buffer();
         tx.set_max_item_size(MAX_ITEM_SIZE);
         let rx = rx.set_buffer().set_max_item_size();
-        inner.subs.push(tx);
+        let sub = Subscriber { tx, filter };
+        for (_, value) in &inner.retained {
+            if sub.wants(value) {
+                let _ = sub.tx.try_send(BroadcastMsg::Value(value.clone()));
+            }
+        }
+        inner.subs.push(sub);
         Receiver::new(rx)
     }
 
     /// Creates an mpsc sender that feeds values to this broadcast sender.
     ///
     /// The mpsc sender can be sent over a remote channel.
-    /// All feeders are disconnected once all receivers are disconnected.
+    /// All feeders are disconnected once all receivers are disconnected,
+    /// unless a [history](History) is configured.
     pub fn feeder<const SEND_BUFFER: usize>(&self) -> mpsc::Sender<T, Codec, SEND_BUFFER> {
         let (tx, rx) = mpsc::channel(1);
         let tx = tx.set_buffer();
@@ -254,7 +392,7 @@ where
 
         exec::spawn(async move {
             while let Ok(Some(value)) = rx.recv().await {
-                if this.send(value).is_err() {
+                if this.send(value).is_err() && this.history() == History::None {
                     break;
                 }
             }
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+#[cfg(feature = "io-digest")]
+use {
+    sha2::Digest as _,
+    std::io,
+    tokio::io::{AsyncRead, AsyncReadExt},
+};
+
+/// Cryptographic hash algorithm used to compute the digest of an I/O channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum DigestAlgorithm {
+    /// SHA-256.
+    Sha256,
+    /// BLAKE3 with 256-bit output.
+    Blake3,
+}
+
+#[cfg(feature = "io-digest")]
+impl DigestAlgorithm {
+    /// Computes the digest of all data read from `reader` until EOF.
+    ///
+    /// This can be used to compute the digest of partially transferred data
+    /// for [resuming a transfer](super::ResumableReceiver::resume_with_digest).
+    pub async fn digest_reader<R>(self, mut reader: R) -> io::Result<Digest>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let mut hasher = Hasher::new(self);
+        let mut buf = vec![0; 65_536];
+        loop {
+            let n = reader.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        Ok(hasher.finalize())
+    }
+}
+
+/// Content digest of the data transmitted over an I/O channel.
+///
+/// The hexadecimal representation is available via the [`Display`](fmt::Display) implementation.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Digest {
+    /// SHA-256 digest.
+    Sha256([u8; 32]),
+    /// BLAKE3 digest.
+    Blake3([u8; 32]),
+}
+
+impl Digest {
+    /// The algorithm used to compute this digest.
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        match self {
+            Self::Sha256(_) => DigestAlgorithm::Sha256,
+            Self::Blake3(_) => DigestAlgorithm::Blake3,
+        }
+    }
+
+    /// The raw digest bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Sha256(bytes) | Self::Blake3(bytes) => bytes,
+        }
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for b in self.as_bytes() {
+            write!(f, "{b:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}({self})", self.algorithm())
+    }
+}
+
+/// Incremental hasher.
+#[cfg(feature = "io-digest")]
+#[derive(Clone)]
+pub(crate) enum Hasher {
+    Sha256(sha2::Sha256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+#[cfg(feature = "io-digest")]
+impl Hasher {
+    /// Creates a new hasher for the specified algorithm.
+    pub fn new(algorithm: DigestAlgorithm) -> Self {
+        match algorithm {
+            DigestAlgorithm::Sha256 => Self::Sha256(sha2::Sha256::new()),
+            DigestAlgorithm::Blake3 => Self::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    /// Hashes the provided data.
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    /// Computes the digest of all data hashed so far.
+    pub fn finalize(self) -> Digest {
+        match self {
+            Self::Sha256(hasher) => Digest::Sha256(hasher.finalize().into()),
+            Self::Blake3(hasher) => Digest::Blake3(hasher.finalize().into()),
+        }
+    }
+}
+
+/// Digest verification of an I/O channel end in transport.
+///
+/// Contains the hash algorithm and the channel used for transmitting the final digest.
+/// It is only serialized when digest verification is enabled, so that the wire format
+/// of I/O channels without digest verification is unchanged.
+/// Endpoints built with and without the `io-digest` feature can exchange I/O channels.
+pub(super) type TransportedDigest<T> = Option<(DigestAlgorithm, T)>;
+
+/// Digest computation state of an I/O channel end.
+///
+/// `T` is the channel used for transmitting the final digest.
+/// The hasher state cannot be transported, thus a fresh hasher is created
+/// after deserialization.
+#[cfg(feature = "io-digest")]
+pub(super) struct DigestState<T> {
+    pub hasher: Hasher,
+    pub chan: T,
+}
+
+#[cfg(feature = "io-digest")]
+impl<T> DigestState<T> {
+    /// Creates new digest state using the specified algorithm.
+    pub fn new(algorithm: DigestAlgorithm, chan: T) -> Self {
+        Self { hasher: Hasher::new(algorithm), chan }
+    }
+
+    /// The hash algorithm.
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        match &self.hasher {
+            Hasher::Sha256(_) => DigestAlgorithm::Sha256,
+            Hasher::Blake3(_) => DigestAlgorithm::Blake3,
+        }
+    }
+
+    /// Converts the state for transport.
+    pub fn into_transported(self) -> (DigestAlgorithm, T) {
+        (self.algorithm(), self.chan)
+    }
+}
+
+#[cfg(feature = "io-digest")]
+impl<T> fmt::Debug for DigestState<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DigestState").field("algorithm", &self.algorithm()).finish()
+    }
+}
+
+/// Restores digest computation state after transport.
+#[cfg(feature = "io-digest")]
+pub(super) fn from_transported<T>(transported: TransportedDigest<T>) -> Option<DigestState<T>> {
+    transported.map(|(algorithm, chan)| DigestState::new(algorithm, chan))
+}
+
+/// Fails if digest verification is requested, since the `io-digest` feature is disabled.
+#[cfg(not(feature = "io-digest"))]
+pub(super) fn reject_transported<T, E>(transported: &TransportedDigest<T>) -> Result<(), E>
+where
+    E: serde::de::Error,
+{
+    match transported {
+        Some(_) => Err(E::custom("digest verification requires the io-digest feature")),
+        None => Ok(()),
+    }
+}
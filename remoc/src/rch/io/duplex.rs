@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use super::{Receiver, Sender};
+use crate::codec;
+
+/// One endpoint of a bidirectional I/O channel.
+///
+/// Implements [`AsyncRead`] for data written by the other endpoint and
+/// [`AsyncWrite`] for data sent to the other endpoint.
+/// Calling [`shutdown`](tokio::io::AsyncWriteExt::shutdown) closes the writing direction
+/// only; data from the other endpoint can still be read until it shuts down as well.
+///
+/// Each direction behaves like an I/O channel of unknown size created by [`channel`](super::channel).
+/// Thus, shutdown must be called to signal completion to the other endpoint.
+///
+/// Create a pair of connected endpoints using [`duplex`].
+/// Both endpoints can be sent to remote endpoints and forwarded.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "Codec: codec::Codec"))]
+#[serde(bound(deserialize = "Codec: codec::Codec"))]
+pub struct Duplex<Codec = codec::Default> {
+    tx: Sender<Codec>,
+    rx: Receiver<Codec>,
+}
+
+impl<Codec> fmt::Debug for Duplex<Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Duplex").field("tx", &self.tx).field("rx", &self.rx).finish()
+    }
+}
+
+/// Creates a new bidirectional I/O channel.
+///
+/// Data written to one endpoint can be read from the other endpoint and vice versa.
+///
+/// Both endpoints can be sent to remote endpoints.
+pub fn duplex<Codec>() -> (Duplex<Codec>, Duplex<Codec>)
+where
+    Codec: codec::Codec,
+{
+    let (a_tx, b_rx) = super::channel();
+    let (b_tx, a_rx) = super::channel();
+    (Duplex { tx: a_tx, rx: a_rx }, Duplex { tx: b_tx, rx: b_rx })
+}
+
+impl<Codec> Duplex<Codec> {
+    /// Total number of bytes written to this endpoint.
+    pub fn bytes_written(&self) -> u64 {
+        self.tx.bytes_written()
+    }
+
+    /// Total number of bytes read from this endpoint.
+    pub fn bytes_received(&self) -> u64 {
+        self.rx.bytes_received()
+    }
+
+    /// Splits the endpoint into its sending and receiving half.
+    pub fn into_split(self) -> (Sender<Codec>, Receiver<Codec>) {
+        (self.tx, self.rx)
+    }
+}
+
+impl<Codec> AsyncRead for Duplex<Codec>
+where
+    Codec: codec::Codec,
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().rx).poll_read(cx, buf)
+    }
+}
+
+impl<Codec> AsyncWrite for Duplex<Codec>
+where
+    Codec: codec::Codec,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().tx).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().tx).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().tx).poll_shutdown(cx)
+    }
+}
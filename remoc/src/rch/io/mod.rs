@@ -19,6 +19,10 @@
 //! In both cases, the receiver returns an error if the received byte count does not match
 //! the expected size.
 //!
+//! For bidirectional streams, [`duplex`] creates a pair of connected endpoints, each
+//! implementing both [`AsyncRead`](tokio::io::AsyncRead) and [`AsyncWrite`](tokio::io::AsyncWrite).
+//! Each direction behaves like a channel of unknown size.
+//!
 //! # Completion
 //!
 //! - **Known size**: Calling [`shutdown`](tokio::io::AsyncWriteExt::shutdown) is optional.
@@ -31,6 +35,32 @@
 //!   This sends the final byte count to the receiver. If the sender is dropped without calling
 //!   shutdown, the receiver will return an [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof) error.
 //!
+//! # Progress
+//!
+//! [`Sender::progress`] and [`Receiver::progress`] provide a watch of the transfer [`Progress`],
+//! suitable for displaying progress bars.
+//! On the sender, only data that has been acknowledged by the remote endpoint is counted as
+//! transferred, which is derived from the returned flow-control credits of the underlying
+//! [chmux](crate::chmux) channel.
+//!
+//! # Digest verification
+//!
+//! With the `io-digest` crate feature enabled, [`channel_with_digest`] and [`sized_with_digest`]
+//! create channels that additionally verify a cryptographic digest (SHA-256 or BLAKE3)
+//! of the transmitted data.
+//! The sender computes the digest while writing and transmits it on
+//! [`shutdown`](tokio::io::AsyncWriteExt::shutdown), which therefore is required.
+//! The receiver fails with an [`InvalidData`](std::io::ErrorKind::InvalidData) error at EOF
+//! if the digest does not match and otherwise provides the verified digest via `Receiver::digest`.
+//!
+//! # Resumable transfers
+//!
+//! [`resumable`] creates a channel for data of known size, where the receiver first reports
+//! how many bytes it already has, for example from a previous interrupted transfer.
+//! The sender then skips the data the receiver already has and only transmits the remaining data.
+//! With the `io-digest` crate feature enabled, the receiver can additionally report the
+//! digest of the data it already has, which the sender verifies before transmitting.
+//!
 //! # Local and remote use
 //!
 //! Both halves of this channel can be used locally without sending either to a remote endpoint.
@@ -128,10 +158,22 @@ use serde::{Deserialize, Serialize};
 use super::{bin, oneshot};
 use crate::codec;
 
+mod digest;
+mod duplex;
+mod progress;
 mod receiver;
+mod resume;
 mod sender;
 
+#[cfg(feature = "io-digest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "io-digest")))]
+pub use digest::{Digest, DigestAlgorithm};
+pub use duplex::{Duplex, duplex};
+pub use progress::Progress;
+#[cfg(feature = "robj")]
+pub(crate) use progress::ProgressTracker;
 pub use receiver::Receiver;
+pub use resume::{ResumableReceiver, ResumableSender, ResumePoint, resumable};
 pub use sender::Sender;
 
 use sender::SizeMode;
@@ -189,3 +231,52 @@ where
 
     (sender, receiver)
 }
+
+/// Creates a new I/O channel with unknown size and content digest verification.
+///
+/// The sender computes the digest of all written data using the specified algorithm
+/// and transmits it together with the final size when
+/// [`shutdown`](tokio::io::AsyncWriteExt::shutdown) is called.
+/// The receiver computes the digest of the received data and fails with an
+/// [`InvalidData`](std::io::ErrorKind::InvalidData) error at EOF if it does not match.
+/// The verified digest is then available via [`Receiver::digest`].
+///
+/// Both ends can be sent to remote endpoints before data has been transferred.
+#[cfg(feature = "io-digest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "io-digest")))]
+pub fn channel_with_digest<Codec>(algorithm: DigestAlgorithm) -> (Sender<Codec>, Receiver<Codec>)
+where
+    Codec: codec::Codec,
+{
+    let (sender, receiver) = channel();
+    with_digest(sender, receiver, algorithm)
+}
+
+/// Creates a new I/O channel with known size and content digest verification.
+///
+/// Unlike with [`sized`], calling [`shutdown`](tokio::io::AsyncWriteExt::shutdown) is **required**,
+/// since it transmits the digest to the receiver.
+/// Otherwise this behaves like [`channel_with_digest`].
+#[cfg(feature = "io-digest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "io-digest")))]
+pub fn sized_with_digest<Codec>(size: u64, algorithm: DigestAlgorithm) -> (Sender<Codec>, Receiver<Codec>)
+where
+    Codec: codec::Codec,
+{
+    let (sender, receiver) = sized(size);
+    with_digest(sender, receiver, algorithm)
+}
+
+/// Enables digest verification on a newly created channel.
+#[cfg(feature = "io-digest")]
+fn with_digest<Codec>(
+    mut sender: Sender<Codec>, mut receiver: Receiver<Codec>, algorithm: DigestAlgorithm,
+) -> (Sender<Codec>, Receiver<Codec>)
+where
+    Codec: codec::Codec,
+{
+    let (digest_tx, digest_rx) = oneshot::channel();
+    sender.set_digest(digest::DigestState::new(algorithm, digest_tx));
+    receiver.set_digest(digest::DigestState::new(algorithm, digest_rx));
+    (sender, receiver)
+}
@@ -0,0 +1,90 @@
+use std::sync::Mutex;
+use tokio::sync::watch;
+
+use crate::exec::time::Instant;
+
+/// Progress of a data transfer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// Number of bytes transferred so far.
+    pub transferred: u64,
+    /// Total number of bytes to transfer, if known.
+    pub total: Option<u64>,
+    /// Average transfer rate in bytes per second since the transfer started.
+    pub rate: f64,
+}
+
+impl Progress {
+    /// Whether all data has been transferred.
+    ///
+    /// Returns `false` if the total size is unknown.
+    pub fn is_complete(&self) -> bool {
+        self.total == Some(self.transferred)
+    }
+
+    /// Fraction of data transferred so far, ranging from 0 to 1.
+    ///
+    /// Returns `None` if the total size is unknown.
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total {
+            Some(0) => Some(1.0),
+            Some(total) => Some(self.transferred as f64 / total as f64),
+            None => None,
+        }
+    }
+}
+
+/// Tracks the progress of a transfer and publishes it to watchers.
+#[derive(Debug)]
+pub(crate) struct ProgressTracker {
+    tx: watch::Sender<Progress>,
+    started: Mutex<Option<Instant>>,
+}
+
+impl Default for ProgressTracker {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl ProgressTracker {
+    /// Creates a new progress tracker for a transfer of the specified total size.
+    pub fn new(total: Option<u64>) -> Self {
+        let (tx, _) = watch::channel(Progress { transferred: 0, total, rate: 0.0 });
+        Self { tx, started: Mutex::new(None) }
+    }
+
+    /// Watches the progress.
+    pub fn subscribe(&self) -> watch::Receiver<Progress> {
+        self.tx.subscribe()
+    }
+
+    /// Starts measuring the transfer rate, if not yet started.
+    pub fn start(&self) {
+        self.started.lock().unwrap().get_or_insert_with(Instant::now);
+    }
+
+    /// Sets the total size of the transfer.
+    pub fn set_total(&self, total: u64) {
+        self.tx.send_if_modified(|progress| {
+            let modified = progress.total != Some(total);
+            progress.total = Some(total);
+            modified
+        });
+    }
+
+    /// Updates the number of transferred bytes and the transfer rate.
+    pub fn update(&self, transferred: u64) {
+        let elapsed = self.started.lock().unwrap().map(|started| started.elapsed().as_secs_f64());
+        self.tx.send_if_modified(|progress| {
+            if progress.transferred == transferred {
+                return false;
+            }
+            progress.transferred = transferred;
+            if let Some(elapsed) = elapsed.filter(|elapsed| *elapsed > 0.0) {
+                progress.rate = transferred as f64 / elapsed;
+            }
+            true
+        });
+    }
+}
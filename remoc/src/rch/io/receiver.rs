@@ -7,12 +7,22 @@ use std::{
     sync::Mutex,
     task::{Context, Poll, ready},
 };
-use tokio::io::AsyncRead;
+use tokio::{io::AsyncRead, sync::watch};
 use tokio_util::sync::ReusableBoxFuture;
 
-use super::{SizeInfo, bin, oneshot};
+use super::digest::{self, Digest, TransportedDigest};
+#[cfg(feature = "io-digest")]
+use super::digest::{DigestAlgorithm, DigestState};
+use super::{
+    SizeInfo, bin, oneshot,
+    progress::{Progress, ProgressTracker},
+};
 use crate::{chmux::DataBuf, codec};
 
+/// Digest state of the receiver.
+#[cfg(feature = "io-digest")]
+type ReceiverDigest<Codec> = DigestState<oneshot::Receiver<Digest, Codec>>;
+
 /// An I/O channel receiver that implements [`AsyncRead`].
 ///
 /// Reads binary data from the underlying channel.
@@ -30,12 +40,23 @@ pub struct Receiver<Codec = codec::Default> {
     state: ReceiverState,
     /// Whether EOF has been reached and verified.
     eof_verified: bool,
+    /// Transfer progress.
+    progress: ProgressTracker,
+    /// Digest computation, if enabled.
+    #[cfg(feature = "io-digest")]
+    digest: Mutex<Option<ReceiverDigest<Codec>>>,
+    /// Verified digest of all received data.
+    #[cfg(feature = "io-digest")]
+    verified_digest: Option<Digest>,
 }
 
 enum ReceiverState {
     Idle,
     Receiving(ReusableBoxFuture<'static, Result<(Option<DataBuf>, bin::Receiver), io::Error>>),
     VerifyingSize(ReusableBoxFuture<'static, Result<u64, io::Error>>),
+    /// Waiting for the digest computed by the sender; contains the locally computed digest.
+    #[cfg(feature = "io-digest")]
+    VerifyingDigest(Digest, ReusableBoxFuture<'static, Result<Digest, io::Error>>),
 }
 
 impl<Codec> fmt::Debug for Receiver<Codec> {
@@ -57,11 +78,19 @@ pub(crate) struct TransportedReceiver<Codec> {
     bin_receiver: bin::Receiver,
     /// Size info for transport.
     size: SizeInfo<Codec>,
+    /// Digest computation, if enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    digest: TransportedDigest<oneshot::Receiver<Digest, Codec>>,
 }
 
 impl<Codec> Receiver<Codec> {
     /// Creates a new receiver.
     pub(super) fn new(bin_receiver: bin::Receiver, size_info: SizeInfo<Codec>) -> Self {
+        let progress = ProgressTracker::new(match &size_info {
+            SizeInfo::Determined(size) => Some(*size),
+            SizeInfo::Undetermined(_) => None,
+        });
+
         Self {
             bin_receiver: Mutex::new(Some(bin_receiver)),
             size_info: Mutex::new(Some(size_info)),
@@ -69,9 +98,20 @@ impl<Codec> Receiver<Codec> {
             current_buf: None,
             state: ReceiverState::Idle,
             eof_verified: false,
+            progress,
+            #[cfg(feature = "io-digest")]
+            digest: Mutex::new(None),
+            #[cfg(feature = "io-digest")]
+            verified_digest: None,
         }
     }
 
+    /// Enables digest verification.
+    #[cfg(feature = "io-digest")]
+    pub(super) fn set_digest(&mut self, digest: ReceiverDigest<Codec>) {
+        *self.digest.get_mut().unwrap() = Some(digest);
+    }
+
     /// Returns the total size of the data, if known.
     ///
     /// Returns `Some(size)` for channels created with [`sized`](super::sized),
@@ -97,6 +137,38 @@ impl<Codec> Receiver<Codec> {
     pub fn remaining(&self) -> Option<u64> {
         self.size().map(|s| s.saturating_sub(self.bytes_read))
     }
+
+    /// Watches the transfer progress.
+    ///
+    /// The number of [transferred](Progress::transferred) bytes is the number of bytes read.
+    /// If the size is unknown, the [total](Progress::total) becomes known at EOF.
+    pub fn progress(&self) -> watch::Receiver<Progress> {
+        self.progress.subscribe()
+    }
+
+    /// Returns the digest algorithm, if digest verification is enabled.
+    #[cfg(feature = "io-digest")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "io-digest")))]
+    pub fn digest_algorithm(&self) -> Option<DigestAlgorithm> {
+        match &self.verified_digest {
+            Some(digest) => Some(digest.algorithm()),
+            None => match &self.state {
+                ReceiverState::VerifyingDigest(digest, _) => Some(digest.algorithm()),
+                _ => self.digest.lock().unwrap().as_ref().map(|digest| digest.algorithm()),
+            },
+        }
+    }
+
+    /// Returns the verified digest of all received data.
+    ///
+    /// This is available after EOF has been reached on a channel with
+    /// digest verification enabled and the digest computed by the sender matched
+    /// the digest of the received data.
+    #[cfg(feature = "io-digest")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "io-digest")))]
+    pub fn digest(&self) -> Option<Digest> {
+        self.verified_digest
+    }
 }
 
 async fn receive_data(mut bin_receiver: bin::Receiver) -> Result<(Option<DataBuf>, bin::Receiver), io::Error> {
@@ -106,6 +178,13 @@ async fn receive_data(mut bin_receiver: bin::Receiver) -> Result<(Option<DataBuf
     Ok((data, bin_receiver))
 }
 
+#[cfg(feature = "io-digest")]
+async fn receive_digest<Codec: codec::Codec>(
+    digest_rx: oneshot::Receiver<Digest, Codec>,
+) -> Result<Digest, io::Error> {
+    digest_rx.await.map_err(|e| io::Error::new(ErrorKind::UnexpectedEof, format!("digest not received: {e}")))
+}
+
 async fn receive_size<Codec: codec::Codec>(size_rx: oneshot::Receiver<u64, Codec>) -> Result<u64, io::Error> {
     size_rx.await.map_err(|e| io::Error::new(ErrorKind::UnexpectedEof, e.to_string()))
 }
@@ -132,6 +211,7 @@ where
 
             // Store the received size
             *self.size_info.lock().unwrap() = Some(SizeInfo::Determined(expected_size));
+            self.progress.set_total(expected_size);
 
             if self.bytes_read != expected_size {
                 return Poll::Ready(Err(io::Error::new(
@@ -143,12 +223,42 @@ where
                 )));
             }
 
+            self.size_verified();
+        }
+
+        #[cfg(feature = "io-digest")]
+        if let ReceiverState::VerifyingDigest(local, ref mut fut) = self.state {
+            let remote = ready!(fut.poll(cx))?;
+            self.state = ReceiverState::Idle;
+
+            if local != remote {
+                return Poll::Ready(Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("digest mismatch: sender computed {remote:?}, received data has {local:?}"),
+                )));
+            }
+
+            self.verified_digest = Some(local);
             self.eof_verified = true;
         }
 
         Poll::Ready(Ok(()))
     }
 
+    /// Called once the size of the received data has been verified.
+    ///
+    /// Starts digest verification, if enabled.
+    fn size_verified(&mut self) {
+        #[cfg(feature = "io-digest")]
+        if let Some(DigestState { hasher, chan }) = self.digest.lock().unwrap().take() {
+            self.state =
+                ReceiverState::VerifyingDigest(hasher.finalize(), ReusableBoxFuture::new(receive_digest(chan)));
+            return;
+        }
+
+        self.eof_verified = true;
+    }
+
     /// Handles EOF: verifies size matches expected or starts async verification.
     fn start_eof_verification(&mut self) -> io::Result<()> {
         let size_info = self.size_info.lock().unwrap().take();
@@ -166,7 +276,7 @@ where
                         ),
                     ));
                 }
-                self.eof_verified = true;
+                self.size_verified();
             }
             Some(SizeInfo::Undetermined(size_rx)) => {
                 self.state = ReceiverState::VerifyingSize(ReusableBoxFuture::new(receive_size(size_rx)));
@@ -188,6 +298,7 @@ where
         mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut tokio::io::ReadBuf<'_>,
     ) -> Poll<io::Result<()>> {
         let this = self.as_mut().get_mut();
+        this.progress.start();
 
         loop {
             // Complete any pending operations
@@ -205,9 +316,11 @@ where
             };
 
             if remaining_allowed == Some(0) {
-                // We've read exactly the expected amount - signal EOF
-                this.eof_verified = true;
-                return Poll::Ready(Ok(()));
+                // We've read exactly the expected amount - signal EOF after verification.
+                // Dropping the channel acknowledges all data to the sender.
+                *this.bin_receiver.lock().unwrap() = None;
+                this.size_verified();
+                continue;
             }
 
             // Try to consume any buffered data
@@ -222,8 +335,13 @@ where
                     }
 
                     buf.put_slice(&chunk[..to_copy]);
+                    #[cfg(feature = "io-digest")]
+                    if let Some(digest) = &mut *this.digest.lock().unwrap() {
+                        digest.hasher.update(&chunk[..to_copy]);
+                    }
                     data_buf.advance(to_copy);
                     this.bytes_read += to_copy as u64;
+                    this.progress.update(this.bytes_read);
                     return Poll::Ready(Ok(()));
                 } else {
                     this.current_buf = None;
@@ -248,6 +366,19 @@ where
     where
         S: serde::Serializer,
     {
+        #[cfg(feature = "io-digest")]
+        let digest = {
+            let mut digest = self.digest.lock().unwrap();
+            if digest.is_some() && self.bytes_read > 0 {
+                return Err(serde::ser::Error::custom(
+                    "cannot serialize: digest computation has already started",
+                ));
+            }
+            digest.take().map(DigestState::into_transported)
+        };
+        #[cfg(not(feature = "io-digest"))]
+        let digest = None;
+
         let bin_receiver =
             self.bin_receiver.lock().unwrap().take().ok_or_else(|| {
                 serde::ser::Error::custom("cannot serialize: channel already connected or closed")
@@ -260,7 +391,7 @@ where
             .take()
             .ok_or_else(|| serde::ser::Error::custom("cannot serialize: size info already consumed"))?;
 
-        TransportedReceiver::<Codec> { bin_receiver, size }.serialize(serializer)
+        TransportedReceiver::<Codec> { bin_receiver, size, digest }.serialize(serializer)
     }
 }
 
@@ -273,6 +404,12 @@ where
         D: serde::Deserializer<'de>,
     {
         let transported = TransportedReceiver::<Codec>::deserialize(deserializer)?;
-        Ok(Self::new(transported.bin_receiver, transported.size))
+        #[cfg(not(feature = "io-digest"))]
+        digest::reject_transported(&transported.digest)?;
+        Ok(Self {
+            #[cfg(feature = "io-digest")]
+            digest: Mutex::new(digest::from_transported(transported.digest)),
+            ..Self::new(transported.bin_receiver, transported.size)
+        })
     }
 }
@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    io::{self, ErrorKind, SeekFrom},
+};
+use tokio::io::{AsyncRead, AsyncSeek, AsyncSeekExt};
+
+use super::{Receiver, Sender, SizeInfo, SizeMode, bin, digest::Digest, oneshot};
+use crate::codec;
+
+/// Point from which a resumable transfer continues.
+///
+/// This is reported by the [`ResumableReceiver`] to the [`ResumableSender`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResumePoint {
+    offset: u64,
+    /// Always transmitted, so that the wire format does not depend on the `io-digest` feature.
+    digest: Option<Digest>,
+}
+
+impl ResumePoint {
+    /// Number of bytes the receiver already has.
+    ///
+    /// The sender skips this many bytes of its data.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Digest of the data the receiver already has, if provided by the receiver.
+    ///
+    /// The sender verifies that it matches the digest of the first [`offset`](Self::offset)
+    /// bytes of its data to ensure that the receiver's partial data is not corrupted.
+    #[cfg(feature = "io-digest")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "io-digest")))]
+    pub fn digest(&self) -> Option<Digest> {
+        self.digest
+    }
+}
+
+/// Sending half of a resumable I/O channel.
+///
+/// Call [`resume`](Self::resume) with the data to transfer to wait for the receiver to
+/// report how much data it already has and obtain an I/O channel sender for the remaining data.
+///
+/// Can be sent to a remote endpoint.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "Codec: codec::Codec"))]
+#[serde(bound(deserialize = "Codec: codec::Codec"))]
+pub struct ResumableSender<Codec = codec::Default> {
+    bin_sender: bin::Sender,
+    size: u64,
+    point_rx: oneshot::Receiver<ResumePoint, Codec>,
+}
+
+impl<Codec> fmt::Debug for ResumableSender<Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ResumableSender").field("size", &self.size).finish()
+    }
+}
+
+impl<Codec> ResumableSender<Codec>
+where
+    Codec: codec::Codec,
+{
+    /// Total size of the data.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Waits for the receiver to report its resume point and positions `data` there.
+    ///
+    /// `data` must provide the complete data of the transfer, starting at position zero.
+    /// It is positioned at [`ResumePoint::offset`] and a sender that expects exactly the
+    /// remaining data, i.e. `size - offset` bytes, is returned together with the resume point.
+    /// Copy the remaining content of `data` into the returned sender to complete the transfer.
+    ///
+    /// If the receiver has provided the [digest](ResumePoint::digest) of the data it already has,
+    /// the first `offset` bytes of `data` are read and their digest is verified.
+    /// On mismatch an [`InvalidData`](ErrorKind::InvalidData) error is returned and the
+    /// transfer is aborted.
+    /// Verification requires the `io-digest` feature, without it an
+    /// [`Unsupported`](ErrorKind::Unsupported) error is returned in this case.
+    ///
+    /// Fails with an [`UnexpectedEof`](ErrorKind::UnexpectedEof) error if the
+    /// receiver is dropped without reporting its resume point.
+    pub async fn resume<R>(self, data: &mut R) -> io::Result<(ResumePoint, Sender<Codec>)>
+    where
+        R: AsyncRead + AsyncSeek + Unpin + ?Sized,
+    {
+        let Self { bin_sender, size, point_rx } = self;
+
+        let point = point_rx
+            .await
+            .map_err(|e| io::Error::new(ErrorKind::UnexpectedEof, format!("resume point not received: {e}")))?;
+        if point.offset > size {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("resume offset {} exceeds total size of {} bytes", point.offset, size),
+            ));
+        }
+
+        match point.digest {
+            Some(expected) => verify_prefix(data, point.offset, expected).await?,
+            None => {
+                data.seek(SeekFrom::Start(point.offset)).await?;
+            }
+        }
+
+        let sender = Sender::new(bin_sender, SizeMode::Known(size - point.offset));
+        Ok((point, sender))
+    }
+}
+
+/// Reads the first `len` bytes of `data` and verifies that their digest matches `expected`.
+#[cfg(feature = "io-digest")]
+async fn verify_prefix<R>(data: &mut R, len: u64, expected: Digest) -> io::Result<()>
+where
+    R: AsyncRead + AsyncSeek + Unpin + ?Sized,
+{
+    use tokio::io::AsyncReadExt;
+
+    data.seek(SeekFrom::Start(0)).await?;
+    let digest = expected.algorithm().digest_reader((&mut *data).take(len)).await?;
+    if digest != expected {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            format!("digest of the first {len} bytes does not match: expected {expected}, got {digest}"),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Verifying the digest requires the `io-digest` feature.
+#[cfg(not(feature = "io-digest"))]
+async fn verify_prefix<R>(_data: &mut R, _len: u64, _expected: Digest) -> io::Result<()>
+where
+    R: AsyncRead + AsyncSeek + Unpin + ?Sized,
+{
+    Err(io::Error::new(ErrorKind::Unsupported, "verifying the resume digest requires the io-digest feature"))
+}
+
+/// Receiving half of a resumable I/O channel.
+///
+/// Call [`resume`](Self::resume) with the number of bytes already present
+/// locally, for example from a previous interrupted transfer, to obtain an I/O channel
+/// receiver for the remaining data.
+///
+/// Can be sent to a remote endpoint.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "Codec: codec::Codec"))]
+#[serde(bound(deserialize = "Codec: codec::Codec"))]
+pub struct ResumableReceiver<Codec = codec::Default> {
+    bin_receiver: bin::Receiver,
+    size: u64,
+    point_tx: oneshot::Sender<ResumePoint, Codec>,
+}
+
+impl<Codec> fmt::Debug for ResumableReceiver<Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ResumableReceiver").field("size", &self.size).finish()
+    }
+}
+
+impl<Codec> ResumableReceiver<Codec>
+where
+    Codec: codec::Codec,
+{
+    /// Total size of the data.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Resumes the transfer after the first `offset` bytes.
+    ///
+    /// Reports the offset to the sender and returns a receiver that provides the
+    /// data starting at `offset`.
+    /// Its [`size`](Receiver::size) is the number of remaining bytes.
+    ///
+    /// Pass zero to start the transfer from the beginning.
+    pub fn resume(self, offset: u64) -> io::Result<Receiver<Codec>> {
+        self.send_point(ResumePoint { offset, digest: None })
+    }
+
+    /// Resumes the transfer after the first `offset` bytes, whose digest is `digest`.
+    ///
+    /// This allows the sender to verify that the data already present locally
+    /// matches its data.
+    /// Otherwise this behaves like [`resume`](Self::resume).
+    #[cfg(feature = "io-digest")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "io-digest")))]
+    pub fn resume_with_digest(self, offset: u64, digest: Digest) -> io::Result<Receiver<Codec>> {
+        self.send_point(ResumePoint { offset, digest: Some(digest) })
+    }
+
+    fn send_point(self, point: ResumePoint) -> io::Result<Receiver<Codec>> {
+        let Self { bin_receiver, size, point_tx } = self;
+
+        if point.offset > size {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("resume offset {} exceeds total size of {} bytes", point.offset, size),
+            ));
+        }
+
+        let remaining = size - point.offset;
+        point_tx.send(point).map_err(|e| io::Error::new(ErrorKind::BrokenPipe, e.to_string()))?;
+
+        Ok(Receiver::new(bin_receiver, SizeInfo::Determined(remaining)))
+    }
+}
+
+/// Creates a new resumable I/O channel for data of known size.
+///
+/// Before data is transferred, the receiver reports how many bytes it already has
+/// using [`ResumableReceiver::resume`].
+/// [`ResumableSender::resume`] positions the data of the sender at this offset,
+/// verifying the digest of the skipped data if the receiver has provided one,
+/// and the sender then writes the data starting at this offset.
+///
+/// An interrupted transfer is continued by creating a new resumable channel,
+/// possibly over a new connection, and resuming from the number of bytes that
+/// have been received and stored so far.
+///
+/// Both ends can be sent to remote endpoints.
+pub fn resumable<Codec>(size: u64) -> (ResumableSender<Codec>, ResumableReceiver<Codec>)
+where
+    Codec: codec::Codec,
+{
+    let (bin_sender, bin_receiver) = bin::channel();
+    let (point_tx, point_rx) = oneshot::channel();
+
+    (ResumableSender { bin_sender, size, point_rx }, ResumableReceiver { bin_receiver, size, point_tx })
+}
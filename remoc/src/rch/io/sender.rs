@@ -5,14 +5,24 @@ use std::{
     io::{self, ErrorKind},
     mem,
     pin::Pin,
-    sync::Mutex,
+    sync::{Arc, Mutex},
     task::{Context, Poll, ready},
 };
-use tokio::io::AsyncWrite;
+use tokio::{io::AsyncWrite, sync::watch};
 use tokio_util::sync::ReusableBoxFuture;
 
-use super::{bin, oneshot};
-use crate::codec;
+use super::digest::{self, Digest, TransportedDigest};
+#[cfg(feature = "io-digest")]
+use super::digest::{DigestAlgorithm, DigestState};
+use super::{
+    bin, oneshot,
+    progress::{Progress, ProgressTracker},
+};
+use crate::{codec, exec};
+
+/// Digest state of the sender.
+#[cfg(feature = "io-digest")]
+type SenderDigest<Codec> = DigestState<oneshot::Sender<Digest, Codec>>;
 
 /// Size handling mode for the sender.
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,6 +36,16 @@ pub(super) enum SizeMode<Codec> {
     Unknown(oneshot::Sender<u64, Codec>),
 }
 
+impl<Codec> SizeMode<Codec> {
+    /// The expected size, if known.
+    fn expected(&self) -> Option<u64> {
+        match self {
+            Self::Known(expected) => Some(*expected),
+            Self::Unknown(_) => None,
+        }
+    }
+}
+
 /// An I/O channel sender that implements [`AsyncWrite`].
 ///
 /// Writes binary data to the underlying channel.
@@ -40,9 +60,17 @@ pub struct Sender<Codec = codec::Default> {
     /// Cached chunk size from chmux sender.
     chunk_size: Option<usize>,
     /// Pending connect operation, if any.
-    connecting: Option<ReusableBoxFuture<'static, Result<(bin::Sender, usize), io::Error>>>,
+    connecting: Option<ReusableBoxFuture<'static, Result<Connected, io::Error>>>,
     /// Pending send operation, if any.
     sending: Option<ReusableBoxFuture<'static, Result<(bin::Sender, u64), io::Error>>>,
+    /// Transfer progress.
+    progress: Arc<ProgressTracker>,
+    /// Digest computation, if enabled.
+    #[cfg(feature = "io-digest")]
+    digest: Mutex<Option<SenderDigest<Codec>>>,
+    /// Digest of all written data, available after shutdown.
+    #[cfg(feature = "io-digest")]
+    final_digest: Option<Digest>,
 }
 
 impl<Codec> fmt::Debug for Sender<Codec> {
@@ -65,11 +93,15 @@ pub(crate) struct TransportedSender<Codec> {
     size_mode: SizeMode<Codec>,
     /// Total bytes written so far.
     bytes_written: u64,
+    /// Digest computation, if enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    digest: TransportedDigest<oneshot::Sender<Digest, Codec>>,
 }
 
 impl<Codec> Sender<Codec> {
     /// Creates a new sender.
     pub(super) fn new(bin_sender: bin::Sender, size_mode: SizeMode<Codec>) -> Self {
+        let progress = Arc::new(ProgressTracker::new(size_mode.expected()));
         Self {
             bin_sender: Mutex::new(Some(bin_sender)),
             size_mode: Mutex::new(size_mode),
@@ -77,9 +109,20 @@ impl<Codec> Sender<Codec> {
             chunk_size: None,
             connecting: None,
             sending: None,
+            progress,
+            #[cfg(feature = "io-digest")]
+            digest: Mutex::new(None),
+            #[cfg(feature = "io-digest")]
+            final_digest: None,
         }
     }
 
+    /// Enables digest computation.
+    #[cfg(feature = "io-digest")]
+    pub(super) fn set_digest(&mut self, digest: SenderDigest<Codec>) {
+        *self.digest.get_mut().unwrap() = Some(digest);
+    }
+
     /// Returns the total number of bytes written so far.
     pub fn bytes_written(&self) -> u64 {
         self.bytes_written
@@ -87,16 +130,46 @@ impl<Codec> Sender<Codec> {
 
     /// Returns the expected size, if known.
     pub fn expected_size(&self) -> Option<u64> {
-        match &*self.size_mode.lock().unwrap() {
-            SizeMode::Known(expected) => Some(*expected),
-            SizeMode::Unknown(_) => None,
-        }
+        self.size_mode.lock().unwrap().expected()
     }
 
     /// Returns the remaining bytes that can be written, if size is known.
     pub fn remaining(&self) -> Option<u64> {
         self.expected_size().map(|s| s.saturating_sub(self.bytes_written))
     }
+
+    /// Watches the transfer progress.
+    ///
+    /// Unlike [`bytes_written`](Self::bytes_written), the number of
+    /// [transferred](Progress::transferred) bytes only includes data that has been
+    /// acknowledged by the remote endpoint.
+    /// Acknowledgements are derived from the flow-control credits returned by the
+    /// remote endpoint and thus arrive in batches.
+    /// If the size is unknown, the [total](Progress::total) becomes known after
+    /// [`shutdown`](tokio::io::AsyncWriteExt::shutdown).
+    pub fn progress(&self) -> watch::Receiver<Progress> {
+        self.progress.subscribe()
+    }
+
+    /// Returns the digest algorithm, if digest verification is enabled.
+    #[cfg(feature = "io-digest")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "io-digest")))]
+    pub fn digest_algorithm(&self) -> Option<DigestAlgorithm> {
+        match &self.final_digest {
+            Some(digest) => Some(digest.algorithm()),
+            None => self.digest.lock().unwrap().as_ref().map(|digest| digest.algorithm()),
+        }
+    }
+
+    /// Returns the digest of all written data.
+    ///
+    /// This is available after [`shutdown`](tokio::io::AsyncWriteExt::shutdown) has
+    /// completed successfully on a channel with digest verification enabled.
+    #[cfg(feature = "io-digest")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "io-digest")))]
+    pub fn digest(&self) -> Option<Digest> {
+        self.final_digest
+    }
 }
 
 async fn send_data(mut bin_sender: bin::Sender, data: Bytes) -> Result<(bin::Sender, u64), io::Error> {
@@ -107,21 +180,41 @@ async fn send_data(mut bin_sender: bin::Sender, data: Bytes) -> Result<(bin::Sen
     Ok((bin_sender, len))
 }
 
-async fn connect_sender(mut bin_sender: bin::Sender) -> Result<(bin::Sender, usize), io::Error> {
+/// Connected binary channel sender.
+struct Connected {
+    bin_sender: bin::Sender,
+    chunk_size: usize,
+    acknowledged: watch::Receiver<u64>,
+}
+
+async fn connect_sender(mut bin_sender: bin::Sender) -> Result<Connected, io::Error> {
     let chmux_sender =
         bin_sender.get().await.map_err(|e| io::Error::new(ErrorKind::ConnectionRefused, e.to_string()))?;
     let chunk_size = chmux_sender.chunk_size();
-    Ok((bin_sender, chunk_size))
+    let acknowledged = chmux_sender.acknowledged();
+    Ok(Connected { bin_sender, chunk_size, acknowledged })
+}
+
+/// Updates the progress from the bytes acknowledged by the remote endpoint.
+async fn track_progress(mut acknowledged: watch::Receiver<u64>, progress: Arc<ProgressTracker>) {
+    progress.start();
+    loop {
+        progress.update(*acknowledged.borrow_and_update());
+        if acknowledged.changed().await.is_err() {
+            break;
+        }
+    }
 }
 
 impl<Codec> Sender<Codec> {
     /// Polls to complete any pending connect or send operations.
     fn poll_complete(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         if let Some(future) = &mut self.connecting {
-            let (bin_sender, chunk_size) = ready!(future.poll(cx))?;
+            let Connected { bin_sender, chunk_size, acknowledged } = ready!(future.poll(cx))?;
             self.chunk_size = Some(chunk_size);
             *self.bin_sender.lock().unwrap() = Some(bin_sender);
             self.connecting = None;
+            exec::spawn(track_progress(acknowledged, self.progress.clone()));
         }
 
         if let Some(future) = &mut self.sending {
@@ -204,6 +297,11 @@ where
         let write_len = max_write.min(chunk_size);
         this.bytes_written += write_len as u64;
 
+        #[cfg(feature = "io-digest")]
+        if let Some(digest) = &mut *this.digest.lock().unwrap() {
+            digest.hasher.update(&buf[..write_len]);
+        }
+
         this.sending =
             Some(ReusableBoxFuture::new(send_data(bin_sender, Bytes::copy_from_slice(&buf[..write_len]))));
         Poll::Ready(Ok(write_len))
@@ -226,19 +324,31 @@ where
 
         // Handle size verification and notification based on mode
         match mem::replace(&mut *this.size_mode.lock().unwrap(), SizeMode::Known(this.bytes_written)) {
-            SizeMode::Known(expected) if this.bytes_written == expected => Poll::Ready(Ok(())),
-            SizeMode::Known(expected) => Poll::Ready(Err(io::Error::new(
-                ErrorKind::UnexpectedEof,
-                format!(
-                    "not enough data written: expected {} bytes but only {} bytes were written",
-                    expected, this.bytes_written
-                ),
-            ))),
+            SizeMode::Known(expected) if this.bytes_written == expected => (),
+            SizeMode::Known(expected) => {
+                return Poll::Ready(Err(io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    format!(
+                        "not enough data written: expected {} bytes but only {} bytes were written",
+                        expected, this.bytes_written
+                    ),
+                )));
+            }
             SizeMode::Unknown(tx) => {
                 let _ = tx.send(this.bytes_written);
-                Poll::Ready(Ok(()))
+                this.progress.set_total(this.bytes_written);
             }
         }
+
+        // Send digest of written data
+        #[cfg(feature = "io-digest")]
+        if let Some(DigestState { hasher, chan }) = this.digest.lock().unwrap().take() {
+            let digest = hasher.finalize();
+            let _ = chan.send(digest);
+            this.final_digest = Some(digest);
+        }
+
+        Poll::Ready(Ok(()))
     }
 }
 
@@ -250,13 +360,26 @@ where
     where
         S: serde::Serializer,
     {
+        #[cfg(feature = "io-digest")]
+        let digest = {
+            let mut digest = self.digest.lock().unwrap();
+            if digest.is_some() && self.bytes_written > 0 {
+                return Err(serde::ser::Error::custom(
+                    "cannot serialize: digest computation has already started",
+                ));
+            }
+            digest.take().map(DigestState::into_transported)
+        };
+        #[cfg(not(feature = "io-digest"))]
+        let digest = None;
+
         let bin_sender = self.bin_sender.lock().unwrap().take();
         let size_mode = mem::replace(
             &mut *self.size_mode.lock().unwrap(),
             SizeMode::Known(0), // Placeholder, sender is consumed anyway
         );
 
-        TransportedSender::<Codec> { bin_sender, size_mode, bytes_written: self.bytes_written }
+        TransportedSender::<Codec> { bin_sender, size_mode, bytes_written: self.bytes_written, digest }
             .serialize(serializer)
     }
 }
@@ -270,7 +393,10 @@ where
         D: serde::Deserializer<'de>,
     {
         let transported = TransportedSender::<Codec>::deserialize(deserializer)?;
+        #[cfg(not(feature = "io-digest"))]
+        digest::reject_transported(&transported.digest)?;
 
+        let progress = Arc::new(ProgressTracker::new(transported.size_mode.expected()));
         Ok(Self {
             bin_sender: Mutex::new(transported.bin_sender),
             size_mode: Mutex::new(transported.size_mode),
@@ -278,6 +404,11 @@ where
             chunk_size: None,
             connecting: None,
             sending: None,
+            progress,
+            #[cfg(feature = "io-digest")]
+            digest: Mutex::new(digest::from_transported(transported.digest)),
+            #[cfg(feature = "io-digest")]
+            final_digest: None,
         })
     }
 }
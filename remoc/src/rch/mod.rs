@@ -39,6 +39,17 @@
 //! It does support forwarding.
 //! However, at least one half of it must be on a remote endpoint.
 //!
+//! An [acknowledged channel](ack) is a channel whose receiver acknowledges each value
+//! after processing it, providing at-least-once delivery.
+//! Only its receiver can be sent to a remote endpoint.
+//!
+//! A [publish/subscribe hub](pubsub) distributes messages published to hierarchical
+//! topics to subscribers interested in them.
+//! It is built on a [broadcast channel](broadcast).
+//!
+//! Arbitrary [streams and sinks](stream) can be made available to remote endpoints
+//! by forwarding them over an MPSC channel.
+//!
 //! An [I/O channel](io) provides [`AsyncWrite`](tokio::io::AsyncWrite) and
 //! [`AsyncRead`](tokio::io::AsyncRead) implementations for streaming binary data.
 //! It supports both known and unknown sizes, with integrity verification on completion.
@@ -57,8 +68,9 @@
 //! occurs on the underlying physical connection the whole [chmux] connection will
 //! be terminated eventually and sending will fail.
 //!
-//! If you need confirmation for every sent value, consider sending a [`oneshot::Sender`]`<()>`
-//! along with it (for example as a tuple).
+//! If you need confirmation for every sent value, use an [acknowledged channel](ack).
+//! For occasional confirmations, consider sending a [`oneshot::Sender`]`<()>`
+//! along with the value (for example as a tuple).
 //! The remote endpoint can then send back an empty message as confirmation over the oneshot channel
 //! after it has processed the received value.
 //!
@@ -114,6 +126,7 @@ use crate::chmux;
 
 mod interlock;
 
+pub mod ack;
 pub mod base;
 pub mod bin;
 pub mod broadcast;
@@ -121,6 +134,8 @@ pub mod io;
 pub mod lr;
 pub mod mpsc;
 pub mod oneshot;
+pub mod pubsub;
+pub mod stream;
 pub mod watch;
 
 /// Error connecting a remote channel.
@@ -163,6 +178,9 @@ pub(crate) const BACKCHANNEL_MSG_CLOSE: u8 = 0x01;
 /// Back channel message that error has occurred.
 pub(crate) const BACKCHANNEL_MSG_ERROR: u8 = 0x02;
 
+/// Back channel message that receiver is out of sync and requests the full value.
+pub(crate) const BACKCHANNEL_MSG_RESYNC: u8 = 0x03;
+
 /// Remote sending error.
 #[derive(Clone)]
 pub(crate) enum RemoteSendError {
@@ -1,15 +1,42 @@
 use futures::future;
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
 use tracing::Instrument;
 
 use super::{
     super::{DEFAULT_BUFFER, DEFAULT_MAX_ITEM_SIZE},
-    Permit, Receiver, Sender, channel,
+    DistributedReceiverStats, DistributionStrategy, Permit, Receiver, Sender, channel,
 };
 use crate::{RemoteSend, codec, exec};
 
+/// Statistics of a distributed receiver shared with its handle.
+struct Stats {
+    id: u64,
+    sent: AtomicU64,
+    /// Number of values queued in the channel to the receiver.
+    queued: Box<dyn Fn() -> usize + Send + Sync>,
+}
+
+impl Stats {
+    fn new(id: u64, queued: impl Fn() -> usize + Send + Sync + 'static) -> Self {
+        Self { id, sent: AtomicU64::new(0), queued: Box::new(queued) }
+    }
+
+    fn get(&self) -> DistributedReceiverStats {
+        DistributedReceiverStats {
+            id: self.id,
+            sent: self.sent.load(Ordering::Relaxed),
+            outstanding: (self.queued)(),
+        }
+    }
+}
+
 struct DistributedReceiver<T, Codec, const BUFFER: usize = DEFAULT_BUFFER> {
     tx: Sender<T, Codec, BUFFER>,
     remove_rx: Option<tokio::sync::mpsc::UnboundedReceiver<()>>,
+    stats: Arc<Stats>,
 }
 
 impl<T, Codec, const BUFFER: usize> DistributedReceiver<T, Codec, BUFFER>
@@ -29,35 +56,53 @@ where
             };
 
             tokio::select! {
-                res = tx.reserve() => return res.ok(),
+                biased;
                 res = remove => {
                     match res {
                         Some(()) => return None,
                         None => self.remove_rx = None,
                     }
                 }
+                res = tx.reserve() => return res.ok(),
             }
         }
     }
 }
 
 /// A handle to a receiver that receives its values from a distributor.
-pub struct DistributedReceiverHandle(tokio::sync::mpsc::UnboundedSender<()>);
+pub struct DistributedReceiverHandle {
+    remove_tx: tokio::sync::mpsc::UnboundedSender<()>,
+    stats: Arc<Stats>,
+}
 
 impl DistributedReceiverHandle {
     /// Removes the associated receiver from the distributor.
     pub fn remove(self) {
-        let _ = self.0.send(());
+        let _ = self.remove_tx.send(());
     }
 
     /// Waits for the associated receiver to be closed or fail due to an error.
     pub async fn closed(&mut self) {
-        self.0.closed().await
+        self.remove_tx.closed().await
+    }
+
+    /// Id of the associated receiver, unique within its distributor.
+    pub fn id(&self) -> u64 {
+        self.stats.id
+    }
+
+    /// Statistics of the associated receiver.
+    pub fn stats(&self) -> DistributedReceiverStats {
+        self.stats.get()
     }
 }
 
 /// Distributes items of an mpsc channel over multiple receivers.
 ///
+/// By default each value is handed to whichever subscribed receiver is ready first.
+/// A [DistributionStrategy] passed to [Receiver::distribute_with] selects the receiver
+/// of each value deterministically instead.
+///
 /// Distribution is stopped and all subscribers are closed when the distributor
 /// is dropped.
 pub struct Distributor<
@@ -77,9 +122,12 @@ where
     T: RemoteSend + Clone,
     Codec: codec::Codec,
 {
-    pub(crate) fn new(rx: Receiver<T, Codec, BUFFER, MAX_ITEM_SIZE>, wait_on_empty: bool) -> Self {
+    pub(crate) fn new(
+        rx: Receiver<T, Codec, BUFFER, MAX_ITEM_SIZE>, wait_on_empty: bool,
+        strategy: Option<Box<dyn DistributionStrategy<T>>>,
+    ) -> Self {
         let (sub_tx, sub_rx) = tokio::sync::mpsc::channel(1);
-        exec::spawn(Self::distribute(rx, sub_rx, wait_on_empty).in_current_span());
+        exec::spawn(Self::distribute(rx, sub_rx, wait_on_empty, strategy).in_current_span());
         Self { sub_tx }
     }
 
@@ -89,11 +137,15 @@ where
         mut sub_rx: tokio::sync::mpsc::Receiver<
             tokio::sync::oneshot::Sender<(Receiver<T, Codec, BUFFER, MAX_ITEM_SIZE>, DistributedReceiverHandle)>,
         >,
-        wait_on_empty: bool,
+        wait_on_empty: bool, mut strategy: Option<Box<dyn DistributionStrategy<T>>>,
     ) {
         let mut txs: Vec<DistributedReceiver<T, Codec, BUFFER>> = Vec::new();
+        let mut next_id = 0;
         let mut first = true;
 
+        // Value received but not yet handed to the receiver selected by the strategy.
+        let mut pending = None;
+
         loop {
             if txs.is_empty() && !(wait_on_empty || first) {
                 return;
@@ -103,6 +155,29 @@ where
             let send_task = async {
                 if txs.is_empty() {
                     future::pending().await
+                } else if let Some(strategy) = &mut strategy {
+                    let value = match &pending {
+                        Some(value) => value,
+                        None => match rx.recv().await {
+                            Ok(Some(value)) => pending.insert(value),
+                            _ => return false,
+                        },
+                    };
+
+                    let stats: Vec<_> = txs.iter().map(|dr| dr.stats.get()).collect();
+                    let pos = strategy.select(value, &stats).min(txs.len() - 1);
+
+                    match txs[pos].reserve().await {
+                        None => {
+                            txs.remove(pos);
+                        }
+                        Some(permit) => {
+                            permit.send(pending.take().unwrap());
+                            txs[pos].stats.sent.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+
+                    true
                 } else {
                     let permits = txs.iter_mut().map(|dr| Box::pin(dr.reserve()));
                     let (permit_opt, pos, _) = future::select_all(permits).await;
@@ -139,10 +214,12 @@ where
                             tx.set_max_item_size(MAX_ITEM_SIZE);
                             let rx = rx.set_buffer().set_max_item_size();
                             let (remove_tx, remove_rx) = tokio::sync::mpsc::unbounded_channel();
+                            let stats = Arc::new(Stats::new(next_id, tx.queued_fn()));
+                            next_id += 1;
                             let dr = DistributedReceiver {
-                                tx, remove_rx: Some(remove_rx)
+                                tx, remove_rx: Some(remove_rx), stats: stats.clone()
                             };
-                            let drh = DistributedReceiverHandle(remove_tx);
+                            let drh = DistributedReceiverHandle { remove_tx, stats };
                             txs.push(dr);
                             let _ = sub_tx.send((rx, drh));
                         }
@@ -0,0 +1,443 @@
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    fmt,
+    pin::Pin,
+    sync::{
+        Arc, Mutex, Weak,
+        atomic::{AtomicU64, Ordering},
+    },
+    task::{Context, Poll},
+};
+use uuid::Uuid;
+
+use super::{
+    super::base::PortSerializer, Receiver, RecvError, SendError, Sender, TryRecvError, TrySendError, channel,
+};
+use crate::{RemoteSend, codec};
+
+/// Origin of a value received over a [fan-in channel](fan_in_channel).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Origin {
+    /// Id of the peer the value was sent from.
+    ///
+    /// All [FanInSenders](FanInSender) sent to a remote endpoint over the same
+    /// connection share the peer id of that connection.
+    pub peer: Uuid,
+    /// Id of the sender clone within the peer that sent the value.
+    pub sender: u64,
+}
+
+/// A value tagged with the id of the sender clone.
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    sender: u64,
+    value: T,
+}
+
+/// Values from one peer.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "T: RemoteSend, Codec: codec::Codec"))]
+#[serde(bound(deserialize = "T: RemoteSend, Codec: codec::Codec"))]
+struct Lane<T, Codec> {
+    peer: Uuid,
+    rx: Receiver<Envelope<T>, Codec>,
+}
+
+/// Creates a bounded channel that identifies the sender of each value.
+///
+/// The receiver obtains the [Origin] of each value and can
+/// [close the channel for one peer](FanInReceiver::close_peer) without affecting the others.
+/// The sender and receiver may be sent to remote endpoints via channels.
+pub fn fan_in_channel<T, Codec>(local_buffer: usize) -> (FanInSender<T, Codec>, FanInReceiver<T, Codec>)
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    let (register_tx, register_rx) = channel(1);
+    let (tx, rx) = channel(local_buffer);
+    let peer = Uuid::new_v4();
+
+    let sender = FanInSender::new(peer, 0, local_buffer, tx, register_tx);
+    let receiver = FanInReceiver { register_rx, lanes: vec![Lane { peer, rx }], registered: false, next: 0 };
+    (sender, receiver)
+}
+
+/// Lane opened for the remote endpoint of a connection.
+struct RemoteLane<T, Codec> {
+    /// Alive as long as the connection.
+    conn: Weak<Uuid>,
+    tx: Sender<Envelope<T>, Codec>,
+    /// Number of senders transmitted over the connection.
+    transmitted: u64,
+}
+
+/// Sending-half of a fan-in channel.
+///
+/// Each clone is assigned a sender id that is unique within its peer.
+///
+/// Can be cloned and sent over a remote channel.
+pub struct FanInSender<T, Codec = codec::Default> {
+    peer: Uuid,
+    sender: u64,
+    local_buffer: usize,
+    tx: Sender<Envelope<T>, Codec>,
+    register_tx: Sender<Lane<T, Codec>, Codec>,
+    next_sender: Arc<AtomicU64>,
+    /// Lanes by connection id, shared by all clones.
+    remote_lanes: Arc<Mutex<HashMap<Uuid, RemoteLane<T, Codec>>>>,
+}
+
+impl<T, Codec> fmt::Debug for FanInSender<T, Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FanInSender").field("peer", &self.peer).field("sender", &self.sender).finish()
+    }
+}
+
+impl<T, Codec> Clone for FanInSender<T, Codec> {
+    fn clone(&self) -> Self {
+        Self {
+            peer: self.peer,
+            sender: self.next_sender.fetch_add(1, Ordering::Relaxed),
+            local_buffer: self.local_buffer,
+            tx: self.tx.clone(),
+            register_tx: self.register_tx.clone(),
+            next_sender: self.next_sender.clone(),
+            remote_lanes: self.remote_lanes.clone(),
+        }
+    }
+}
+
+impl<T, Codec> FanInSender<T, Codec>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    fn new(
+        peer: Uuid, sender: u64, local_buffer: usize, tx: Sender<Envelope<T>, Codec>,
+        register_tx: Sender<Lane<T, Codec>, Codec>,
+    ) -> Self {
+        Self {
+            peer,
+            sender,
+            local_buffer,
+            tx,
+            register_tx,
+            next_sender: Arc::new(AtomicU64::new(sender + 1)),
+            remote_lanes: Default::default(),
+        }
+    }
+
+    /// Origin of the values sent by this sender, as seen by the receiver.
+    pub fn origin(&self) -> Origin {
+        Origin { peer: self.peer, sender: self.sender }
+    }
+
+    /// Sends a value over this channel.
+    ///
+    /// # Error reporting
+    /// Sending and error reporting are done asynchronously.
+    /// Thus, the reporting of an error may be delayed and this function may
+    /// return errors caused by previous invocations.
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        match self.tx.send(Envelope { sender: self.sender, value }).await {
+            Ok(_) => Ok(()),
+            Err(SendError::Closed(envelope)) => Err(SendError::Closed(envelope.value)),
+            Err(SendError::RemoteSend(err)) => Err(SendError::RemoteSend(err)),
+            Err(SendError::RemoteConnect(err)) => Err(SendError::RemoteConnect(err)),
+            Err(SendError::RemoteListen(err)) => Err(SendError::RemoteListen(err)),
+            Err(SendError::RemoteForward) => Err(SendError::RemoteForward),
+        }
+    }
+
+    /// Attempts to immediately send a value over this channel.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        match self.tx.try_send(Envelope { sender: self.sender, value }) {
+            Ok(_) => Ok(()),
+            Err(TrySendError::Closed(envelope)) => Err(TrySendError::Closed(envelope.value)),
+            Err(TrySendError::Full(envelope)) => Err(TrySendError::Full(envelope.value)),
+            Err(TrySendError::RemoteSend(err)) => Err(TrySendError::RemoteSend(err)),
+            Err(TrySendError::RemoteConnect(err)) => Err(TrySendError::RemoteConnect(err)),
+            Err(TrySendError::RemoteListen(err)) => Err(TrySendError::RemoteListen(err)),
+            Err(TrySendError::RemoteForward) => Err(TrySendError::RemoteForward),
+        }
+    }
+
+    /// Waits for the receiver to be closed or dropped or this peer to be closed by the receiver.
+    pub async fn closed(&self) {
+        self.tx.closed().await
+    }
+
+    /// Returns whether the receiver has been closed or dropped or this peer has
+    /// been closed by the receiver.
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+}
+
+/// Fan-in sender in transport.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "T: RemoteSend, Codec: codec::Codec"))]
+#[serde(bound(deserialize = "T: RemoteSend, Codec: codec::Codec"))]
+struct TransportedFanInSender<T, Codec> {
+    peer: Uuid,
+    /// First sender id of the received sender and its clones.
+    sender: u64,
+    local_buffer: usize,
+    tx: Sender<Envelope<T>, Codec>,
+    register_tx: Sender<Lane<T, Codec>, Codec>,
+}
+
+/// Number of sender ids reserved for each transmitted sender and its clones.
+const SENDER_IDS_PER_TRANSMISSION: u64 = 1 << 32;
+
+impl<T, Codec> Serialize for FanInSender<T, Codec>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    /// Serializes this sender for sending over a chmux channel.
+    ///
+    /// When the first sender is sent over a connection, a lane is opened for the
+    /// remote endpoint and registered with the receiver.
+    /// Following senders sent over the same connection share that lane.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let storage = PortSerializer::storage()?;
+        let peer = storage.id();
+
+        let mut remote_lanes = self.remote_lanes.lock().unwrap();
+        remote_lanes.retain(|_, lane| lane.conn.strong_count() > 0);
+        let lane = match remote_lanes.entry(peer) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let (tx, rx) = channel(self.local_buffer);
+                let register_tx = self.register_tx.clone();
+                PortSerializer::spawn(async move {
+                    let _ = register_tx.send(Lane { peer, rx }).await;
+                })?;
+                entry.insert(RemoteLane { conn: storage.alive(), tx, transmitted: 0 })
+            }
+        };
+        lane.transmitted += 1;
+
+        TransportedFanInSender {
+            peer,
+            sender: lane.transmitted * SENDER_IDS_PER_TRANSMISSION,
+            local_buffer: self.local_buffer,
+            tx: lane.tx.clone(),
+            register_tx: self.register_tx.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T, Codec> Deserialize<'de> for FanInSender<T, Codec>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    /// Deserializes this sender after it has been received over a chmux channel.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let TransportedFanInSender { peer, sender, local_buffer, tx, register_tx } =
+            TransportedFanInSender::deserialize(deserializer)?;
+        Ok(Self::new(peer, sender, local_buffer, tx, register_tx))
+    }
+}
+
+/// Receiving-half of a fan-in channel.
+///
+/// Values from different peers are received in turn.
+/// Values from the same peer are received in the order they were sent.
+///
+/// Can be sent over a remote channel.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "T: RemoteSend, Codec: codec::Codec"))]
+#[serde(bound(deserialize = "T: RemoteSend, Codec: codec::Codec"))]
+pub struct FanInReceiver<T, Codec = codec::Default> {
+    register_rx: Receiver<Lane<T, Codec>, Codec>,
+    lanes: Vec<Lane<T, Codec>>,
+    /// All senders have been dropped, thus no more peers can be registered.
+    #[serde(skip)]
+    registered: bool,
+    #[serde(skip)]
+    next: usize,
+}
+
+impl<T, Codec> fmt::Debug for FanInReceiver<T, Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FanInReceiver").finish()
+    }
+}
+
+impl<T, Codec> FanInReceiver<T, Codec>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    /// Receives the next value together with its origin.
+    ///
+    /// This function returns `Ok(None)` when all channel senders have been dropped.
+    ///
+    /// ### Cancel safety
+    /// This method is cancel safe.
+    pub async fn recv(&mut self) -> Result<Option<(Origin, T)>, RecvError> {
+        std::future::poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Polls to receive the next value together with its origin.
+    ///
+    /// This function returns `Poll::Ready(Ok(None))` when all channel senders have been dropped.
+    pub fn poll_recv(&mut self, cx: &mut Context) -> Poll<Result<Option<(Origin, T)>, RecvError>> {
+        while !self.registered {
+            match self.register_rx.poll_recv(cx) {
+                Poll::Ready(Ok(Some(lane))) => self.lanes.push(lane),
+                Poll::Ready(Ok(None)) => self.registered = true,
+                Poll::Ready(Err(err)) => {
+                    self.registered = err.is_final();
+                    return Poll::Ready(Err(err));
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        let len = self.lanes.len();
+        let mut closed = Vec::new();
+        let mut res = Poll::Pending;
+        for i in 0..len {
+            let pos = (self.next + i) % len;
+            let lane = &mut self.lanes[pos];
+            match lane.rx.poll_recv(cx) {
+                Poll::Ready(Ok(Some(Envelope { sender, value }))) => {
+                    self.next = pos + 1;
+                    res = Poll::Ready(Ok(Some((Origin { peer: lane.peer, sender }, value))));
+                    break;
+                }
+                Poll::Ready(Ok(None)) => closed.push(pos),
+                Poll::Ready(Err(err)) => {
+                    if err.is_final() {
+                        closed.push(pos);
+                    }
+                    res = Poll::Ready(Err(err));
+                    break;
+                }
+                Poll::Pending => (),
+            }
+        }
+        self.remove_lanes(closed);
+
+        match res {
+            Poll::Pending if self.registered && self.lanes.is_empty() => Poll::Ready(Ok(None)),
+            res => res,
+        }
+    }
+
+    /// Tries to receive the next value together with its origin, if one is immediately available.
+    ///
+    /// This function returns `Err(TryRecvError::Closed)` when all channel senders have been dropped
+    /// and `Err(TryRecvError::Empty)` if no value to receive is currently available.
+    pub fn try_recv(&mut self) -> Result<(Origin, T), TryRecvError> {
+        while !self.registered {
+            match self.register_rx.try_recv() {
+                Ok(lane) => self.lanes.push(lane),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Closed) => self.registered = true,
+                Err(err) => {
+                    self.registered = err.is_final();
+                    return Err(err);
+                }
+            }
+        }
+
+        let len = self.lanes.len();
+        let mut closed = Vec::new();
+        let mut res = Err(TryRecvError::Empty);
+        for i in 0..len {
+            let pos = (self.next + i) % len;
+            let lane = &mut self.lanes[pos];
+            match lane.rx.try_recv() {
+                Ok(Envelope { sender, value }) => {
+                    self.next = pos + 1;
+                    res = Ok((Origin { peer: lane.peer, sender }, value));
+                    break;
+                }
+                Err(TryRecvError::Empty) => (),
+                Err(TryRecvError::Closed) => closed.push(pos),
+                Err(err) => {
+                    if err.is_final() {
+                        closed.push(pos);
+                    }
+                    res = Err(err);
+                    break;
+                }
+            }
+        }
+        self.remove_lanes(closed);
+
+        match res {
+            Err(TryRecvError::Empty) if self.registered && self.lanes.is_empty() => Err(TryRecvError::Closed),
+            res => res,
+        }
+    }
+
+    /// Removes the lanes at the specified positions, keeping the round-robin position.
+    fn remove_lanes(&mut self, mut closed: Vec<usize>) {
+        closed.sort_unstable();
+        for pos in closed.into_iter().rev() {
+            self.lanes.remove(pos);
+            if pos < self.next {
+                self.next -= 1;
+            }
+        }
+    }
+
+    /// Ids of the peers currently connected to this receiver.
+    pub fn peers(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.lanes
+            .iter()
+            .enumerate()
+            .filter(|(pos, lane)| !self.lanes[..*pos].iter().any(|other| other.peer == lane.peer))
+            .map(|(_, lane)| lane.peer)
+    }
+
+    /// Closes the channel for the specified peer without affecting other peers.
+    ///
+    /// Values already sent by the peer can still be received.
+    /// Returns whether the peer was connected.
+    pub fn close_peer(&mut self, peer: Uuid) -> bool {
+        let mut found = false;
+        for lane in self.lanes.iter_mut().filter(|lane| lane.peer == peer) {
+            lane.rx.close();
+            found = true;
+        }
+        found
+    }
+
+    /// Closes the receiving half of the channel for all peers without dropping it.
+    pub fn close(&mut self) {
+        self.register_rx.close();
+        for lane in &mut self.lanes {
+            lane.rx.close();
+        }
+    }
+}
+
+impl<T, Codec> Stream for FanInReceiver<T, Codec>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    type Item = Result<(Origin, T), RecvError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        Pin::into_inner(self).poll_recv(cx).map(Result::transpose)
+    }
+}
+
+impl<T, Codec> Unpin for FanInReceiver<T, Codec> {}
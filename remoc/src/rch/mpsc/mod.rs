@@ -44,6 +44,27 @@
 //! # tokio_test::block_on(remoc::doctest::client_server(client, server));
 //! ```
 //!
+//! # Priorities
+//!
+//! A [priority channel](priority_channel) transmits each [Priority] independently, so that
+//! urgent values, such as control commands, do not wait behind bulk values in the send buffer.
+//! Its [receiver](PriorityReceiver) yields the available values of higher priority first.
+//!
+//! # Fan-in
+//!
+//! A [fan-in channel](fan_in_channel) tags each received value with its [Origin], consisting
+//! of the peer the [sender](FanInSender) was sent to and the id of the sender clone.
+//! Its [receiver](FanInReceiver) can close the channel for one peer without affecting the others.
+//!
+//! # Spooling
+//!
+//! A [SpooledSender] persists each value to a local file until the receiver has
+//! acknowledged it.
+//! Unacknowledged values are redelivered over a new connection after the connection
+//! to the receiver has been lost or the program has been restarted.
+//! The [SpooledReceiver] suppresses duplicates by their sequence id.
+//! This requires the `fs` feature.
+//!
 
 use bytes::Buf;
 use futures::FutureExt;
@@ -62,12 +83,23 @@ use crate::{
 };
 
 mod distributor;
+mod fan_in;
+mod priority;
 mod receiver;
 mod sender;
+#[cfg(feature = "fs")]
+mod spool;
+mod strategy;
 
 pub use distributor::{DistributedReceiverHandle, Distributor};
+pub use fan_in::{FanInReceiver, FanInSender, Origin, fan_in_channel};
+pub use priority::{Priority, PriorityReceiver, PrioritySender, priority_channel};
 pub use receiver::{Receiver, RecvError, TryRecvError};
 pub use sender::{Permit, SendError, Sender, SenderSink, TrySendError};
+#[cfg(feature = "fs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+pub use spool::{SpooledDelivery, SpooledReceiver, SpooledSender};
+pub use strategy::{DistributedReceiverStats, DistributionStrategy, KeyAffinity, LeastOutstanding, RoundRobin};
 
 /// Creates a bounded channel for communicating between asynchronous tasks with back pressure.
 ///
@@ -0,0 +1,228 @@
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::{super::Sending, Receiver, RecvError, SendError, Sender, TryRecvError, TrySendError, channel};
+use crate::{RemoteSend, codec};
+
+/// Priority of a value sent over a [priority channel](priority_channel).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Priority {
+    /// Bulk work that is received after all other values.
+    Bulk,
+    /// Normal priority.
+    #[default]
+    Normal,
+    /// Urgent values, such as control commands, that are received first.
+    Urgent,
+}
+
+/// Creates a bounded channel whose values carry a [Priority].
+///
+/// Each priority is transmitted over its own channel with a local buffer of size `local_buffer`.
+/// Sending waits only when the buffer of the same priority is full, thus a backlog of
+/// bulk values does not delay sending urgent values.
+/// Urgent values are not exempt from flow control: they wait for buffer space if
+/// the receiver does not keep up with them.
+/// The sender and receiver may be sent to remote endpoints via channels.
+pub fn priority_channel<T, Codec>(local_buffer: usize) -> (PrioritySender<T, Codec>, PriorityReceiver<T, Codec>)
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    let (bulk_tx, bulk_rx) = channel(local_buffer);
+    let (normal_tx, normal_rx) = channel(local_buffer);
+    let (urgent_tx, urgent_rx) = channel(local_buffer);
+
+    let sender = PrioritySender { bulk: bulk_tx, normal: normal_tx, urgent: urgent_tx };
+    let receiver = PriorityReceiver { bulk: bulk_rx, normal: normal_rx, urgent: urgent_rx };
+    (sender, receiver)
+}
+
+/// Sending-half of a priority channel.
+///
+/// Can be cloned and sent over a remote channel.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "T: RemoteSend, Codec: codec::Codec"))]
+#[serde(bound(deserialize = "T: RemoteSend, Codec: codec::Codec"))]
+pub struct PrioritySender<T, Codec = codec::Default> {
+    bulk: Sender<T, Codec>,
+    normal: Sender<T, Codec>,
+    urgent: Sender<T, Codec>,
+}
+
+impl<T, Codec> fmt::Debug for PrioritySender<T, Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PrioritySender").finish()
+    }
+}
+
+impl<T, Codec> Clone for PrioritySender<T, Codec> {
+    fn clone(&self) -> Self {
+        Self { bulk: self.bulk.clone(), normal: self.normal.clone(), urgent: self.urgent.clone() }
+    }
+}
+
+impl<T, Codec> PrioritySender<T, Codec>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    fn lane(&self, priority: Priority) -> &Sender<T, Codec> {
+        match priority {
+            Priority::Bulk => &self.bulk,
+            Priority::Normal => &self.normal,
+            Priority::Urgent => &self.urgent,
+        }
+    }
+
+    /// Sends a value with the specified priority over this channel.
+    ///
+    /// Waits only for buffer space of the specified priority.
+    ///
+    /// # Error reporting
+    /// Sending and error reporting are done asynchronously.
+    /// Thus, the reporting of an error may be delayed and this function may
+    /// return errors caused by previous invocations.
+    pub async fn send(&self, value: T, priority: Priority) -> Result<Sending<T>, SendError<T>> {
+        self.lane(priority).send(value).await
+    }
+
+    /// Attempts to immediately send a value with the specified priority over this channel.
+    pub fn try_send(&self, value: T, priority: Priority) -> Result<Sending<T>, TrySendError<T>> {
+        self.lane(priority).try_send(value)
+    }
+
+    /// Waits for the associated receiver to be closed or dropped.
+    ///
+    /// This completes when the channels of all priorities have been closed.
+    pub async fn closed(&self) {
+        tokio::join!(self.bulk.closed(), self.normal.closed(), self.urgent.closed());
+    }
+
+    /// Returns whether the receiver has been closed or dropped.
+    ///
+    /// This is true when the channels of all priorities have been closed.
+    pub fn is_closed(&self) -> bool {
+        self.bulk.is_closed() && self.normal.is_closed() && self.urgent.is_closed()
+    }
+}
+
+/// Receiving-half of a priority channel.
+///
+/// Among the values that are available for receiving, values of higher
+/// [priority](Priority) are received first.
+/// Values of the same priority are received in the order they were sent.
+///
+/// Can be sent over a remote channel.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "T: RemoteSend, Codec: codec::Codec"))]
+#[serde(bound(deserialize = "T: RemoteSend, Codec: codec::Codec"))]
+pub struct PriorityReceiver<T, Codec = codec::Default> {
+    bulk: Receiver<T, Codec>,
+    normal: Receiver<T, Codec>,
+    urgent: Receiver<T, Codec>,
+}
+
+impl<T, Codec> fmt::Debug for PriorityReceiver<T, Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PriorityReceiver").finish()
+    }
+}
+
+impl<T, Codec> PriorityReceiver<T, Codec>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    /// Receivers of all priorities, highest priority first.
+    fn lanes(&mut self) -> [(Priority, &mut Receiver<T, Codec>); 3] {
+        [
+            (Priority::Urgent, &mut self.urgent),
+            (Priority::Normal, &mut self.normal),
+            (Priority::Bulk, &mut self.bulk),
+        ]
+    }
+
+    /// Receives the next value with the highest priority available.
+    ///
+    /// This function returns `Ok(None)` when all channel senders have been dropped.
+    ///
+    /// ### Cancel safety
+    /// This method is cancel safe.
+    pub async fn recv(&mut self) -> Result<Option<T>, RecvError> {
+        Ok(self.recv_with_priority().await?.map(|(_, value)| value))
+    }
+
+    /// Receives the next value with the highest priority available together with its priority.
+    ///
+    /// This function returns `Ok(None)` when all channel senders have been dropped.
+    pub async fn recv_with_priority(&mut self) -> Result<Option<(Priority, T)>, RecvError> {
+        std::future::poll_fn(|cx| self.poll_recv_with_priority(cx)).await
+    }
+
+    /// Polls to receive the next value with the highest priority available together
+    /// with its priority.
+    ///
+    /// This function returns `Poll::Ready(Ok(None))` when all channel senders have been dropped.
+    pub fn poll_recv_with_priority(
+        &mut self, cx: &mut Context,
+    ) -> Poll<Result<Option<(Priority, T)>, RecvError>> {
+        let mut closed = true;
+        for (priority, lane) in self.lanes() {
+            match lane.poll_recv(cx) {
+                Poll::Ready(Ok(Some(value))) => return Poll::Ready(Ok(Some((priority, value)))),
+                Poll::Ready(Ok(None)) => (),
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => closed = false,
+            }
+        }
+
+        if closed { Poll::Ready(Ok(None)) } else { Poll::Pending }
+    }
+
+    /// Tries to receive the value with the highest priority, if one is immediately available.
+    ///
+    /// This function returns `Err(TryRecvError::Closed)` when all channel senders have been dropped
+    /// and `Err(TryRecvError::Empty)` if no value to receive is currently available.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut closed = true;
+        for (_, lane) in self.lanes() {
+            match lane.try_recv() {
+                Ok(value) => return Ok(value),
+                Err(TryRecvError::Closed) => (),
+                Err(TryRecvError::Empty) => closed = false,
+                Err(err) => return Err(err),
+            }
+        }
+
+        if closed { Err(TryRecvError::Closed) } else { Err(TryRecvError::Empty) }
+    }
+
+    /// Closes the receiving half of a channel without dropping it.
+    pub fn close(&mut self) {
+        for (_, lane) in self.lanes() {
+            lane.close();
+        }
+    }
+}
+
+impl<T, Codec> Stream for PriorityReceiver<T, Codec>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    type Item = Result<T, RecvError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        Pin::into_inner(self)
+            .poll_recv_with_priority(cx)
+            .map(|res| res.map(|opt| opt.map(|(_, value)| value)).transpose())
+    }
+}
+
+impl<T, Codec> Unpin for PriorityReceiver<T, Codec> {}
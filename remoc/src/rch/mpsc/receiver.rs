@@ -15,7 +15,7 @@ use super::{
         ClosedReason, DEFAULT_BUFFER, DEFAULT_MAX_ITEM_SIZE, RemoteSendError,
         base::{self, PortDeserializer, PortSerializer},
     },
-    Distributor, SendReq,
+    DistributionStrategy, Distributor, SendReq,
 };
 use crate::{RemoteSend, chmux, codec, exec};
 
@@ -467,7 +467,21 @@ where
     /// If `wait_on_empty` is true, the distributor waits if all subscribers are closed.
     /// Otherwise it terminates.
     pub fn distribute(self, wait_on_empty: bool) -> Distributor<T, Codec, BUFFER, MAX_ITEM_SIZE> {
-        Distributor::new(self, wait_on_empty)
+        Distributor::new(self, wait_on_empty, None)
+    }
+
+    /// Distribute received items over multiple receivers according to a strategy.
+    ///
+    /// Each value is received by the receiver selected by the [strategy](DistributionStrategy).
+    /// The distributor waits for the selected receiver to become ready, even if
+    /// other receivers could accept the value immediately.
+    ///
+    /// If `wait_on_empty` is true, the distributor waits if all subscribers are closed.
+    /// Otherwise it terminates.
+    pub fn distribute_with(
+        self, wait_on_empty: bool, strategy: impl DistributionStrategy<T>,
+    ) -> Distributor<T, Codec, BUFFER, MAX_ITEM_SIZE> {
+        Distributor::new(self, wait_on_empty, Some(Box::new(strategy)))
     }
 }
 
@@ -472,6 +472,18 @@ where
         }
     }
 
+    /// Returns a function reporting the number of values queued locally that have
+    /// not been transmitted yet.
+    ///
+    /// The function does not keep the channel open.
+    pub(crate) fn queued_fn(&self) -> impl Fn() -> usize + Send + Sync + 'static {
+        let tx = self.tx.clone();
+        move || match tx.upgrade() {
+            Some(tx) => tx.max_capacity() - tx.capacity(),
+            None => 0,
+        }
+    }
+
     /// Completes when the receiver has been closed, dropped or the connection failed.
     ///
     /// Use [closed_reason](Self::closed_reason) to obtain the cause for closure.
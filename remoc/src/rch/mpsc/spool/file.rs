@@ -0,0 +1,249 @@
+//! Append-only spool file.
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    collections::VecDeque,
+    io,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+use tokio::{fs, io::AsyncWriteExt};
+use uuid::Uuid;
+
+use crate::codec;
+
+/// Length of the record length prefix.
+const LEN_PREFIX: usize = 4;
+
+/// Record within the spool file.
+#[derive(Serialize, Deserialize)]
+enum Entry<T> {
+    /// First record of the file.
+    Header { id: Uuid, next_seq: u64 },
+    /// A spooled value.
+    Item { seq: u64, value: T },
+    /// All values up to and including the sequence id have been acknowledged.
+    Acked { seq: u64 },
+}
+
+/// Encodes a record including its length prefix.
+fn encode<T, Codec>(entry: &Entry<T>) -> io::Result<Vec<u8>>
+where
+    T: Serialize,
+    Codec: codec::Codec,
+{
+    let mut buf = vec![0; LEN_PREFIX];
+    <Codec as codec::Codec>::serialize(&mut buf, entry)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    let len = u32::try_from(buf.len() - LEN_PREFIX)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "spool record too large"))?;
+    buf[..LEN_PREFIX].copy_from_slice(&len.to_le_bytes());
+
+    Ok(buf)
+}
+
+/// Encodes a spooled value.
+pub(super) fn encode_item<T, Codec>(seq: u64, value: &T) -> io::Result<Vec<u8>>
+where
+    T: Serialize,
+    Codec: codec::Codec,
+{
+    encode::<_, Codec>(&Entry::Item { seq, value })
+}
+
+/// Opens a file for appending.
+async fn open_append(path: &Path) -> io::Result<fs::File> {
+    fs::OpenOptions::new().create(true).append(true).open(path).await
+}
+
+/// Append-only file storing spooled values and their acknowledgements.
+pub(super) struct SpoolFile<Codec> {
+    path: PathBuf,
+    file: fs::File,
+    /// Length of the file, i.e. the end of the last completely written record.
+    len: u64,
+    id: Uuid,
+    next_seq: u64,
+    acked: usize,
+    _codec: PhantomData<Codec>,
+}
+
+impl<Codec> SpoolFile<Codec>
+where
+    Codec: codec::Codec,
+{
+    /// Opens or creates a spool file and returns the values not acknowledged yet.
+    ///
+    /// A partially written record at the end of the file is discarded.
+    /// A complete record that cannot be decoded results in an [InvalidData](io::ErrorKind::InvalidData)
+    /// error, since discarding it would lose all following records.
+    pub(super) async fn open<T>(path: &Path) -> io::Result<(Self, VecDeque<(u64, T)>)>
+    where
+        T: DeserializeOwned,
+    {
+        let data = match fs::read(path).await {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+
+        let mut pos = 0;
+        let mut id = None;
+        let mut next_seq = 0;
+        let mut acked = 0;
+        let mut pending = VecDeque::new();
+        while let Some(len) = data.get(pos..pos + LEN_PREFIX) {
+            let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+            let Some(record) = data.get(pos + LEN_PREFIX..pos + LEN_PREFIX + len) else { break };
+            let entry = <Codec as codec::Codec>::deserialize::<_, Entry<T>>(record).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("corrupted spool record at offset {pos}: {err}"),
+                )
+            })?;
+
+            match (entry, id) {
+                (Entry::Header { id: header_id, next_seq: header_next_seq }, None) => {
+                    id = Some(header_id);
+                    next_seq = header_next_seq;
+                }
+                (Entry::Item { seq, value }, Some(_)) => {
+                    next_seq = next_seq.max(seq + 1);
+                    pending.push_back((seq, value));
+                }
+                (Entry::Acked { seq }, Some(_)) => {
+                    while pending.front().is_some_and(|(pending_seq, _)| *pending_seq <= seq) {
+                        pending.pop_front();
+                        acked += 1;
+                    }
+                }
+                _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid spool file")),
+            }
+
+            pos += LEN_PREFIX + len;
+        }
+
+        if id.is_none() && !data.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid spool file header"));
+        }
+
+        let file = open_append(path).await?;
+        if pos < data.len() {
+            tracing::warn!(path = %path.display(), "discarding partially written record from spool file");
+            file.set_len(pos as u64).await?;
+        }
+
+        let mut this = Self {
+            path: path.to_path_buf(),
+            file,
+            len: pos as u64,
+            id: id.unwrap_or_else(Uuid::new_v4),
+            next_seq,
+            acked,
+            _codec: PhantomData,
+        };
+        if id.is_none() {
+            this.write_header().await?;
+        }
+
+        Ok((this, pending))
+    }
+
+    /// Id of the spool.
+    pub(super) fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Number of acknowledged values still stored in the file.
+    pub(super) fn acked(&self) -> usize {
+        self.acked
+    }
+
+    fn header(&self) -> io::Result<Vec<u8>> {
+        encode::<(), Codec>(&Entry::Header { id: self.id, next_seq: self.next_seq })
+    }
+
+    async fn write_header(&mut self) -> io::Result<()> {
+        let header = self.header()?;
+        self.append(&header, true).await
+    }
+
+    /// Appends a record, optionally waiting until it has been persisted.
+    ///
+    /// On failure the file is truncated to its previous length, so that a partially
+    /// written record does not corrupt the records appended afterwards.
+    async fn append(&mut self, record: &[u8], sync: bool) -> io::Result<()> {
+        let res = async {
+            self.file.write_all(record).await?;
+            if sync { self.file.sync_data().await } else { self.file.flush().await }
+        }
+        .await;
+
+        match res {
+            Ok(()) => {
+                self.len += record.len() as u64;
+                Ok(())
+            }
+            Err(err) => {
+                if let Err(truncate_err) = self.file.set_len(self.len).await {
+                    tracing::warn!(path = %self.path.display(), %truncate_err, "cannot truncate spool file after failed write");
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Appends a value and waits until it has been persisted.
+    ///
+    /// Returns the sequence id assigned to the value.
+    pub(super) async fn append_item<T>(&mut self, value: &T) -> io::Result<u64>
+    where
+        T: Serialize,
+    {
+        let seq = self.next_seq;
+        let record = encode_item::<_, Codec>(seq, value)?;
+        self.append(&record, true).await?;
+        self.next_seq += 1;
+        Ok(seq)
+    }
+
+    /// Appends an acknowledgement of `count` values up to and including `seq`.
+    ///
+    /// The acknowledgement is not synced to disk, since losing it only results
+    /// in redelivery.
+    pub(super) async fn append_ack(&mut self, seq: u64, count: usize) -> io::Result<()> {
+        let record = encode::<(), Codec>(&Entry::Acked { seq })?;
+        self.append(&record, false).await?;
+        self.acked += count;
+        Ok(())
+    }
+
+    /// Discards all records from the file, since all values have been acknowledged.
+    pub(super) async fn clear(&mut self) -> io::Result<()> {
+        self.file.set_len(0).await?;
+        self.len = 0;
+        self.write_header().await?;
+        self.acked = 0;
+        Ok(())
+    }
+
+    /// Replaces the file contents by the specified encoded items.
+    pub(super) async fn rewrite(&mut self, items: Vec<u8>) -> io::Result<()> {
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".compact");
+
+        let header = self.header()?;
+        let mut tmp = fs::File::create(&tmp_path).await?;
+        tmp.write_all(&header).await?;
+        tmp.write_all(&items).await?;
+        tmp.sync_all().await?;
+        drop(tmp);
+
+        fs::rename(&tmp_path, &self.path).await?;
+        self.file = open_append(&self.path).await?;
+        self.len = (header.len() + items.len()) as u64;
+        self.acked = 0;
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+//! Spooled MPSC channel persisting values until they have been acknowledged.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fmt, io,
+    path::Path,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+use super::super::{DEFAULT_BUFFER, mpsc};
+use crate::{RemoteSend, codec, exec};
+
+mod file;
+mod receiver;
+
+pub use receiver::{SpooledDelivery, SpooledReceiver};
+
+use file::SpoolFile;
+
+/// Number of acknowledged values in the spool file that triggers its compaction.
+const COMPACT_THRESHOLD: usize = 1024;
+
+/// Value transmitted to the receiver.
+#[derive(Serialize, Deserialize)]
+struct Record<T> {
+    seq: u64,
+    value: T,
+}
+
+/// State shared between the senders and connection tasks.
+struct Shared<T, Codec> {
+    file: tokio::sync::Mutex<SpoolFile<Codec>>,
+    pending: Mutex<VecDeque<(u64, T)>>,
+    id: Uuid,
+    /// Notifies connection tasks of new values.
+    items_tx: watch::Sender<()>,
+    /// Values with lower sequence ids have been acknowledged.
+    ///
+    /// Shared with receivers that have not been sent to a remote endpoint.
+    next: Arc<AtomicU64>,
+}
+
+impl<T, Codec> Shared<T, Codec>
+where
+    T: RemoteSend + Clone,
+    Codec: codec::Codec,
+{
+    /// Records the acknowledgement of all values up to and including `seq`.
+    async fn acked(&self, seq: u64) -> io::Result<()> {
+        let mut file = self.file.lock().await;
+
+        let count = {
+            let mut pending = self.pending.lock().unwrap();
+            let count = pending.partition_point(|(pending_seq, _)| *pending_seq <= seq);
+            pending.drain(..count);
+            count
+        };
+        if count == 0 {
+            return Ok(());
+        }
+        self.next.fetch_max(seq + 1, Ordering::AcqRel);
+
+        file.append_ack(seq, count).await?;
+
+        let items = {
+            let pending = self.pending.lock().unwrap();
+            if pending.is_empty() {
+                None
+            } else if file.acked() >= COMPACT_THRESHOLD && file.acked() >= pending.len() {
+                let mut items = Vec::new();
+                for (seq, value) in &*pending {
+                    items.extend(file::encode_item::<_, Codec>(*seq, value)?);
+                }
+                Some(items)
+            } else {
+                return Ok(());
+            }
+        };
+
+        match items {
+            Some(items) => file.rewrite(items).await,
+            None => file.clear().await,
+        }
+    }
+
+    /// Transmits spooled values over a connection and processes its acknowledgements.
+    async fn transmit(
+        self: Arc<Self>, tx: mpsc::Sender<Record<T>, Codec>, mut ack_rx: mpsc::Receiver<u64, Codec>,
+        mut conn_rx: watch::Receiver<u64>,
+    ) {
+        let mut items_rx = self.items_tx.subscribe();
+        let mut next = 0;
+        let mut closing = false;
+
+        loop {
+            items_rx.borrow_and_update();
+            let item = {
+                let pending = self.pending.lock().unwrap();
+                if closing && pending.is_empty() {
+                    break;
+                }
+                let idx = pending.partition_point(|(seq, _)| *seq < next);
+                pending.get(idx).cloned()
+            };
+
+            tokio::select! {
+                res = conn_rx.changed(), if !closing => match res {
+                    // Superseded by a new connection.
+                    Ok(()) => break,
+                    // All senders dropped, finish delivering spooled values.
+                    Err(_) => closing = true,
+                },
+
+                res = ack_rx.recv() => match res {
+                    Ok(Some(seq)) => {
+                        if let Err(err) = self.acked(seq).await {
+                            tracing::warn!(%err, "recording acknowledgement in spool file failed");
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(err) if err.is_final() => break,
+                    Err(_) => (),
+                },
+
+                res = tx.reserve(), if item.is_some() => match (res, item) {
+                    (Ok(permit), Some((seq, value))) => {
+                        permit.send(Record { seq, value });
+                        next = seq + 1;
+                    }
+                    _ => break,
+                },
+
+                _ = items_rx.changed(), if item.is_none() => (),
+            }
+        }
+    }
+}
+
+/// Sending-half of a spooled MPSC channel.
+///
+/// Each value is appended to a local spool file before it is transmitted and
+/// kept there until the receiver has acknowledged it.
+/// When the connection to the receiver is lost, values can no longer be lost:
+/// call [connect](Self::connect) to obtain a new receiver, which is sent to the
+/// remote endpoint over a new connection and receives all unacknowledged values again.
+/// Unacknowledged values also survive restarts of the program and are redelivered
+/// after the spool file has been reopened.
+///
+/// The values are serialized into the spool file using the codec `Codec`.
+/// A copy of each unacknowledged value is also kept in memory.
+///
+/// Cannot be sent to a remote endpoint.
+/// Dropping all senders finishes delivering the spooled values over the current
+/// connection and then closes it.
+pub struct SpooledSender<T, Codec = codec::Default> {
+    shared: Arc<Shared<T, Codec>>,
+    conn_tx: Arc<watch::Sender<u64>>,
+}
+
+impl<T, Codec> fmt::Debug for SpooledSender<T, Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SpooledSender").field("id", &self.shared.id).finish()
+    }
+}
+
+impl<T, Codec> Clone for SpooledSender<T, Codec> {
+    fn clone(&self) -> Self {
+        Self { shared: self.shared.clone(), conn_tx: self.conn_tx.clone() }
+    }
+}
+
+impl<T, Codec> SpooledSender<T, Codec>
+where
+    T: RemoteSend + Clone,
+    Codec: codec::Codec,
+{
+    /// Opens the spool file at the specified path, creating it if it does not exist.
+    ///
+    /// Values not acknowledged when the spool file was used before are delivered
+    /// over the first [connection](Self::connect).
+    /// The spool file must not be used by multiple senders simultaneously.
+    pub async fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let (file, pending) = SpoolFile::<Codec>::open::<T>(path.as_ref()).await?;
+        let shared = Shared {
+            id: file.id(),
+            file: tokio::sync::Mutex::new(file),
+            pending: Mutex::new(pending),
+            items_tx: watch::channel(()).0,
+            next: Arc::new(AtomicU64::new(0)),
+        };
+        Ok(Self { shared: Arc::new(shared), conn_tx: Arc::new(watch::channel(0).0) })
+    }
+
+    /// Unique id of the spool.
+    ///
+    /// It is persisted in the spool file and can be used by the receiving endpoint
+    /// to track the [last acknowledged value](SpooledReceiver::last_acked) of this spool
+    /// over multiple connections.
+    pub fn id(&self) -> Uuid {
+        self.shared.id
+    }
+
+    /// Creates a new connection and returns its receiver.
+    ///
+    /// The receiver may be sent to a remote endpoint.
+    /// All unacknowledged values, followed by values sent in the future, are
+    /// delivered to it.
+    /// A previous connection is closed.
+    pub fn connect(&self) -> SpooledReceiver<T, Codec> {
+        let (tx, rx) = mpsc::channel(DEFAULT_BUFFER);
+        let (ack_tx, ack_rx) = mpsc::channel(DEFAULT_BUFFER);
+
+        self.conn_tx.send_modify(|conn| *conn += 1);
+        exec::spawn(self.shared.clone().transmit(tx, ack_rx, self.conn_tx.subscribe()));
+
+        SpooledReceiver::new(self.shared.id, rx, ack_tx, self.shared.next.clone())
+    }
+
+    /// Sends a value over this channel.
+    ///
+    /// Returns the sequence id assigned to the value once it has been persisted in
+    /// the spool file.
+    /// The value is transmitted when a receiver is [connected](Self::connect).
+    pub async fn send(&self, value: T) -> io::Result<u64> {
+        let mut file = self.shared.file.lock().await;
+        let seq = file.append_item(&value).await?;
+        self.shared.pending.lock().unwrap().push_back((seq, value));
+        self.shared.items_tx.send_replace(());
+        Ok(seq)
+    }
+
+    /// Returns the number of values that have not been acknowledged yet.
+    pub fn pending(&self) -> usize {
+        self.shared.pending.lock().unwrap().len()
+    }
+}
@@ -0,0 +1,193 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    ops::Deref,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+use uuid::Uuid;
+
+use super::{super::super::mpsc, Record};
+use crate::{RemoteSend, codec};
+
+/// Receiving-half of a spooled MPSC channel.
+///
+/// Instances are created by [SpooledSender::connect](super::SpooledSender::connect).
+/// Values are received in the order they were sent and must be
+/// [acknowledged](SpooledDelivery::ack) after they have been processed.
+///
+/// Values are identified by their sequence id.
+/// A new receiver starts after the last value whose acknowledgement has reached the sender.
+/// A value received again, because its acknowledgement was lost together with the
+/// previous connection, is suppressed if the receiver has been told where to
+/// [resume](Self::resume_after).
+///
+/// Can be sent over a remote channel.
+pub struct SpooledReceiver<T, Codec = codec::Default> {
+    id: Uuid,
+    rx: mpsc::Receiver<Record<T>, Codec>,
+    ack_tx: mpsc::Sender<u64, Codec>,
+    /// Values with lower sequence ids have been acknowledged.
+    next: Arc<AtomicU64>,
+}
+
+/// Spooled receiver in transport.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "T: RemoteSend, Codec: codec::Codec"))]
+#[serde(bound(deserialize = "T: RemoteSend, Codec: codec::Codec"))]
+struct TransportedSpooledReceiver<T, Codec> {
+    id: Uuid,
+    rx: mpsc::Receiver<Record<T>, Codec>,
+    ack_tx: mpsc::Sender<u64, Codec>,
+    next: u64,
+}
+
+impl<T, Codec> Serialize for SpooledReceiver<T, Codec>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        #[serde(bound(serialize = "T: RemoteSend, Codec: codec::Codec"))]
+        struct Ref<'a, T, Codec> {
+            id: Uuid,
+            rx: &'a mpsc::Receiver<Record<T>, Codec>,
+            ack_tx: &'a mpsc::Sender<u64, Codec>,
+            next: u64,
+        }
+
+        Ref { id: self.id, rx: &self.rx, ack_tx: &self.ack_tx, next: self.next.load(Ordering::Acquire) }
+            .serialize(serializer)
+    }
+}
+
+impl<'de, T, Codec> Deserialize<'de> for SpooledReceiver<T, Codec>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let TransportedSpooledReceiver { id, rx, ack_tx, next } =
+            TransportedSpooledReceiver::deserialize(deserializer)?;
+        Ok(Self::new(id, rx, ack_tx, Arc::new(AtomicU64::new(next))))
+    }
+}
+
+impl<T, Codec> fmt::Debug for SpooledReceiver<T, Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SpooledReceiver").field("id", &self.id).finish()
+    }
+}
+
+impl<T, Codec> SpooledReceiver<T, Codec>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    pub(super) fn new(
+        id: Uuid, rx: mpsc::Receiver<Record<T>, Codec>, ack_tx: mpsc::Sender<u64, Codec>, next: Arc<AtomicU64>,
+    ) -> Self {
+        Self { id, rx, ack_tx, next }
+    }
+
+    /// Unique id of the spool this receiver is connected to.
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Sequence id of the last value acknowledged by this receiver.
+    pub fn last_acked(&self) -> Option<u64> {
+        self.next.load(Ordering::Acquire).checked_sub(1)
+    }
+
+    /// Suppresses all values up to and including the specified sequence id.
+    ///
+    /// Pass the [last acknowledged value](Self::last_acked) of the previous receiver
+    /// of the [same spool](Self::id) to avoid processing values twice.
+    pub fn resume_after(&mut self, last_acked: u64) {
+        self.next.fetch_max(last_acked.saturating_add(1), Ordering::AcqRel);
+    }
+
+    /// Receives the next value.
+    ///
+    /// Returns `Ok(None)` when the connection has been closed by the sender.
+    pub async fn recv(&mut self) -> Result<Option<SpooledDelivery<T, Codec>>, mpsc::RecvError> {
+        loop {
+            let Some(Record { seq, value }) = self.rx.recv().await? else { return Ok(None) };
+
+            // Acknowledge duplicate without delivering it.
+            if seq < self.next.load(Ordering::Acquire) {
+                let _ = self.ack_tx.send(seq).await;
+                continue;
+            }
+
+            return Ok(Some(SpooledDelivery {
+                seq,
+                value,
+                ack_tx: self.ack_tx.clone(),
+                next: self.next.clone(),
+            }));
+        }
+    }
+}
+
+/// A value received over a spooled MPSC channel.
+///
+/// This dereferences to the received value.
+pub struct SpooledDelivery<T, Codec = codec::Default> {
+    seq: u64,
+    value: T,
+    ack_tx: mpsc::Sender<u64, Codec>,
+    next: Arc<AtomicU64>,
+}
+
+impl<T, Codec> fmt::Debug for SpooledDelivery<T, Codec>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SpooledDelivery").field("seq", &self.seq).field("value", &self.value).finish()
+    }
+}
+
+impl<T, Codec> SpooledDelivery<T, Codec>
+where
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    /// Sequence id of the value assigned by the sender.
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// Acknowledges that this and all previously received values have been processed.
+    ///
+    /// The sender then removes them from its spool file.
+    pub async fn ack(self) -> Result<(), mpsc::SendError<()>> {
+        self.next.fetch_max(self.seq + 1, Ordering::AcqRel);
+        self.ack_tx.send(self.seq).await.map_err(|err| err.without_item())?;
+        Ok(())
+    }
+
+    /// Returns the value without acknowledging it.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, Codec> Deref for SpooledDelivery<T, Codec> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
@@ -0,0 +1,112 @@
+use std::{
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+/// Statistics of a receiver subscribed to a [Distributor](super::Distributor).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DistributedReceiverStats {
+    /// Id of the receiver, unique within its distributor and increasing in subscription order.
+    pub id: u64,
+    /// Number of values handed to the receiver so far.
+    ///
+    /// This is only counted if the distributor uses a [DistributionStrategy].
+    pub sent: u64,
+    /// Number of values handed to the receiver that are still queued for transmission to it.
+    pub outstanding: usize,
+}
+
+/// A strategy selecting the receiver of each value distributed by a [Distributor](super::Distributor).
+///
+/// Use [Receiver::distribute_with](super::Receiver::distribute_with) to distribute values
+/// according to a strategy.
+pub trait DistributionStrategy<T>: Send + 'static {
+    /// Returns the index of the receiver within `receivers` that should receive the value.
+    ///
+    /// `receivers` is never empty and ordered by subscription.
+    /// If the returned index is out of bounds, the last receiver is selected.
+    fn select(&mut self, value: &T, receivers: &[DistributedReceiverStats]) -> usize;
+}
+
+/// Hands values to the subscribed receivers in turn.
+#[derive(Debug, Clone, Default)]
+pub struct RoundRobin {
+    next_id: u64,
+}
+
+impl RoundRobin {
+    /// Creates a new round-robin strategy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T> DistributionStrategy<T> for RoundRobin {
+    fn select(&mut self, _value: &T, receivers: &[DistributedReceiverStats]) -> usize {
+        let pos = receivers.iter().position(|stats| stats.id >= self.next_id).unwrap_or_default();
+        self.next_id = receivers[pos].id + 1;
+        pos
+    }
+}
+
+/// Hands each value to the receiver with the fewest outstanding values.
+///
+/// Ties are resolved in favor of the receiver that subscribed first.
+#[derive(Debug, Clone, Default)]
+pub struct LeastOutstanding;
+
+impl<T> DistributionStrategy<T> for LeastOutstanding {
+    fn select(&mut self, _value: &T, receivers: &[DistributedReceiverStats]) -> usize {
+        receivers
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, stats)| stats.outstanding)
+            .map(|(pos, _)| pos)
+            .unwrap_or_default()
+    }
+}
+
+/// Hands all values with the same key to the same receiver.
+///
+/// The key is extracted from each value by a function and mapped to a receiver by
+/// consistent (rendezvous) hashing.
+/// Thus, when a receiver subscribes or is removed, only the keys assigned to it
+/// move to a different receiver.
+#[derive(Clone)]
+pub struct KeyAffinity<F> {
+    key: F,
+}
+
+impl<F> fmt::Debug for KeyAffinity<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("KeyAffinity").finish()
+    }
+}
+
+impl<F> KeyAffinity<F> {
+    /// Creates a new key-affinity strategy using the specified key extraction function.
+    pub fn new(key: F) -> Self {
+        Self { key }
+    }
+}
+
+impl<T, K, F> DistributionStrategy<T> for KeyAffinity<F>
+where
+    F: Fn(&T) -> K + Send + 'static,
+    K: Hash,
+{
+    fn select(&mut self, value: &T, receivers: &[DistributedReceiverStats]) -> usize {
+        let key = (self.key)(value);
+        receivers
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, stats)| {
+                let mut hasher = DefaultHasher::new();
+                key.hash(&mut hasher);
+                stats.id.hash(&mut hasher);
+                hasher.finish()
+            })
+            .map(|(pos, _)| pos)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt};
+
+use super::{
+    super::{broadcast, mpsc, oneshot},
+    Message, Pattern, Subscriber, TopicError,
+    topic::check_topic,
+};
+use crate::{RemoteSend, codec, exec};
+
+/// An error occurred during publishing to a hub.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum PublishError {
+    /// The topic is invalid.
+    InvalidTopic(TopicError),
+    /// Sending to the hub failed.
+    Send(mpsc::SendError<()>),
+}
+
+impl fmt::Display for PublishError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidTopic(err) => write!(f, "invalid topic: {err}"),
+            Self::Send(err) => write!(f, "send error: {err}"),
+        }
+    }
+}
+
+impl Error for PublishError {}
+
+/// An error occurred during subscribing to a hub.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum SubscribeError {
+    /// Sending the subscription request to the hub failed.
+    Send(mpsc::SendError<()>),
+    /// Receiving the subscriber from the hub failed.
+    Recv(oneshot::RecvError),
+}
+
+impl fmt::Display for SubscribeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Send(err) => write!(f, "send error: {err}"),
+            Self::Recv(err) => write!(f, "receive error: {err}"),
+        }
+    }
+}
+
+impl Error for SubscribeError {}
+
+/// Subscription request sent to the hub.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "T: RemoteSend + Clone, Codec: codec::Codec"))]
+#[serde(bound(deserialize = "T: RemoteSend + Clone, Codec: codec::Codec"))]
+struct SubscribeReq<T, Codec> {
+    pattern: Pattern,
+    send_buffer: usize,
+    reply_tx: oneshot::Sender<Subscriber<T, Codec>, Codec>,
+}
+
+/// Handle to a topic hub for publishing and subscribing.
+///
+/// The hub itself runs on the endpoint that created it using [Hub::new].
+/// The handle can be cloned and sent to remote endpoints.
+/// The hub is shut down once all handles have been dropped, which
+/// closes all subscribers.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "T: RemoteSend + Clone, Codec: codec::Codec"))]
+#[serde(bound(deserialize = "T: RemoteSend + Clone, Codec: codec::Codec"))]
+pub struct Hub<T, Codec = codec::Default> {
+    publish_tx: mpsc::Sender<Message<T>, Codec>,
+    subscribe_tx: mpsc::Sender<SubscribeReq<T, Codec>, Codec>,
+}
+
+impl<T, Codec> fmt::Debug for Hub<T, Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Hub").finish()
+    }
+}
+
+impl<T, Codec> Clone for Hub<T, Codec> {
+    fn clone(&self) -> Self {
+        Self { publish_tx: self.publish_tx.clone(), subscribe_tx: self.subscribe_tx.clone() }
+    }
+}
+
+impl<T, Codec> Default for Hub<T, Codec>
+where
+    T: RemoteSend + Clone,
+    Codec: codec::Codec,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, Codec> Hub<T, Codec>
+where
+    T: RemoteSend + Clone,
+    Codec: codec::Codec,
+{
+    /// Creates a new hub running on this endpoint and returns a handle to it.
+    pub fn new() -> Self {
+        Self::with_history(broadcast::History::None)
+    }
+
+    /// Creates a new hub that retains the specified history of published messages
+    /// and replays the matching ones to new subscribers.
+    pub fn with_history(history: broadcast::History) -> Self {
+        let (publish_tx, publish_rx) = mpsc::channel(1);
+        let (subscribe_tx, subscribe_rx) = mpsc::channel(1);
+        exec::spawn(Self::run(broadcast::Sender::with_history(history), publish_rx, subscribe_rx));
+        Self { publish_tx, subscribe_tx }
+    }
+
+    /// Hub task distributing published messages to subscribers.
+    async fn run(
+        tx: broadcast::Sender<Message<T>, Codec>, mut publish_rx: mpsc::Receiver<Message<T>, Codec>,
+        mut subscribe_rx: mpsc::Receiver<SubscribeReq<T, Codec>, Codec>,
+    ) {
+        let mut publishing = true;
+        let mut subscribing = true;
+
+        while publishing || subscribing {
+            tokio::select! {
+                res = publish_rx.recv(), if publishing => match res {
+                    Ok(Some(msg)) => {
+                        let _ = tx.send(msg);
+                    }
+                    Ok(None) => publishing = false,
+                    Err(err) => publishing = !err.is_final(),
+                },
+
+                res = subscribe_rx.recv(), if subscribing => match res {
+                    Ok(Some(SubscribeReq { pattern, send_buffer, reply_tx })) => {
+                        let rx = tx.subscribe_filtered(send_buffer.max(1), pattern);
+                        let _ = reply_tx.send(Subscriber::new(rx));
+                    }
+                    Ok(None) => subscribing = false,
+                    Err(err) => subscribing = !err.is_final(),
+                },
+            }
+        }
+    }
+
+    /// Publishes a value to the specified topic.
+    ///
+    /// The topic must not contain wildcards.
+    /// Publishing succeeds even if no subscriber is interested in the topic.
+    pub async fn publish(&self, topic: impl Into<String>, value: T) -> Result<(), PublishError> {
+        let topic = topic.into();
+        check_topic(&topic).map_err(PublishError::InvalidTopic)?;
+        self.publish_tx
+            .send(Message { topic, value })
+            .await
+            .map_err(|err| PublishError::Send(err.without_item()))?;
+        Ok(())
+    }
+
+    /// Subscribes to all topics matching the specified pattern.
+    ///
+    /// Up to `send_buffer` messages are queued by the hub for the subscriber.
+    /// If the subscriber falls further behind, it receives a
+    /// [lagged error](broadcast::RecvError::Lagged) and misses messages.
+    pub async fn subscribe(
+        &self, pattern: Pattern, send_buffer: usize,
+    ) -> Result<Subscriber<T, Codec>, SubscribeError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.subscribe_tx
+            .send(SubscribeReq { pattern, send_buffer, reply_tx })
+            .await
+            .map_err(|err| SubscribeError::Send(err.without_item()))?;
+        reply_rx.await.map_err(SubscribeError::Recv)
+    }
+}
@@ -0,0 +1,63 @@
+//! Topic-based publish/subscribe with publishers and subscribers that may be located on remote endpoints.
+//!
+//! A [Hub] distributes messages published to hierarchical topics to all subscribers
+//! whose [pattern](Pattern) matches the topic.
+//! The hub runs on the endpoint that created it, while handles to it can be sent to
+//! remote endpoints for publishing and subscribing.
+//! [Subscribers](Subscriber) can be sent to remote endpoints as well.
+//!
+//! The hub is built on a [broadcast channel](super::broadcast).
+//! Each subscriber has its own send buffer and reports missed messages
+//! by a [lagged error](super::broadcast::RecvError::Lagged).
+//! Topic patterns are evaluated by the hub, so that messages are only transmitted
+//! to interested subscribers.
+//!
+//! # Example
+//!
+//! In the following example the server publishes measurements to a hub it
+//! obtained from the client, which subscribes to all temperature measurements.
+//!
+//! ```
+//! use remoc::prelude::*;
+//! use remoc::rch::pubsub::{Hub, Pattern};
+//!
+//! // This would be run on the client.
+//! async fn client(mut tx: rch::base::Sender<Hub<f32>>) {
+//!     let hub = Hub::new();
+//!     let pattern = Pattern::new("sensors/+/temperature").unwrap();
+//!     let mut sub = hub.subscribe(pattern, 16).await.unwrap();
+//!     tx.send(hub).await.unwrap();
+//!
+//!     let msg = sub.recv().await.unwrap();
+//!     assert_eq!(msg.topic, "sensors/kitchen/temperature");
+//!     assert_eq!(msg.value, 21.5);
+//! }
+//!
+//! // This would be run on the server.
+//! async fn server(mut rx: rch::base::Receiver<Hub<f32>>) {
+//!     let hub = rx.recv().await.unwrap().unwrap();
+//!     hub.publish("sensors/kitchen/humidity", 40.0).await.unwrap();
+//!     hub.publish("sensors/kitchen/temperature", 21.5).await.unwrap();
+//! }
+//! # tokio_test::block_on(remoc::doctest::client_server(client, server));
+//! ```
+//!
+
+use serde::{Deserialize, Serialize};
+
+mod hub;
+mod subscriber;
+mod topic;
+
+pub use hub::{Hub, PublishError, SubscribeError};
+pub use subscriber::Subscriber;
+pub use topic::{MULTI_LEVEL_WILDCARD, Pattern, SEPARATOR, SINGLE_LEVEL_WILDCARD, TopicError};
+
+/// A message published to a topic.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Message<T> {
+    /// Topic the message was published to.
+    pub topic: String,
+    /// Published value.
+    pub value: T,
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::{
+    super::{broadcast, watch},
+    Message, Pattern,
+};
+use crate::{RemoteSend, codec};
+
+/// Receives the messages published to a [Hub](super::Hub) on topics matching its pattern.
+///
+/// Instances are created by [Hub::subscribe](super::Hub::subscribe).
+/// The pattern is evaluated by the hub, thus messages on other topics are not
+/// transmitted to the subscriber.
+///
+/// Can be sent over a remote channel.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "T: RemoteSend + Clone, Codec: codec::Codec"))]
+#[serde(bound(deserialize = "T: RemoteSend + Clone, Codec: codec::Codec"))]
+pub struct Subscriber<T, Codec = codec::Default> {
+    rx: broadcast::FilteredReceiver<Message<T>, Pattern, Codec>,
+}
+
+impl<T, Codec> fmt::Debug for Subscriber<T, Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Subscriber").finish()
+    }
+}
+
+impl<T, Codec> Subscriber<T, Codec>
+where
+    T: RemoteSend + Clone,
+    Codec: codec::Codec,
+{
+    pub(crate) fn new(rx: broadcast::FilteredReceiver<Message<T>, Pattern, Codec>) -> Self {
+        Self { rx }
+    }
+
+    /// Receives the next message.
+    ///
+    /// Returns a [lagged error](broadcast::RecvError::Lagged) if messages have been
+    /// missed because the subscriber fell behind and a [closed error](broadcast::RecvError::Closed)
+    /// once the hub has been shut down.
+    pub async fn recv(&mut self) -> Result<Message<T>, broadcast::RecvError> {
+        self.rx.recv().await
+    }
+
+    /// Attempts to return a pending message without awaiting.
+    pub fn try_recv(&mut self) -> Result<Message<T>, broadcast::TryRecvError> {
+        self.rx.try_recv()
+    }
+
+    /// Returns the pattern of topics this subscriber is interested in.
+    pub fn pattern(&self) -> Pattern {
+        self.rx.filter().clone()
+    }
+
+    /// Changes the pattern of topics this subscriber is interested in.
+    ///
+    /// The new pattern applies to messages published after it has reached the hub.
+    pub fn set_pattern(&self, pattern: Pattern) -> Result<(), watch::SendError> {
+        self.rx.set_filter(pattern)
+    }
+}
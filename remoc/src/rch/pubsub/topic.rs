@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt, str::FromStr};
+
+use super::{super::broadcast::Filter, Message};
+
+/// Separator between the levels of a topic.
+pub const SEPARATOR: char = '/';
+
+/// Wildcard matching exactly one topic level.
+pub const SINGLE_LEVEL_WILDCARD: &str = "+";
+
+/// Wildcard matching all remaining topic levels.
+pub const MULTI_LEVEL_WILDCARD: &str = "#";
+
+/// Characters that make up wildcards.
+const WILDCARD_CHARS: [char; 2] = ['+', '#'];
+
+/// An invalid topic or topic pattern.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TopicError {
+    /// The topic or pattern is empty.
+    Empty,
+    /// A topic to publish to contains a wildcard.
+    Wildcard,
+    /// A wildcard in a pattern does not occupy a whole level or
+    /// the multi-level wildcard is not at the last level.
+    MisplacedWildcard,
+}
+
+impl fmt::Display for TopicError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "topic is empty"),
+            Self::Wildcard => write!(f, "topic contains wildcard"),
+            Self::MisplacedWildcard => write!(f, "misplaced wildcard in topic pattern"),
+        }
+    }
+}
+
+impl Error for TopicError {}
+
+/// Checks that the topic is valid for publishing.
+pub(crate) fn check_topic(topic: &str) -> Result<(), TopicError> {
+    if topic.is_empty() {
+        return Err(TopicError::Empty);
+    }
+    if topic.contains(WILDCARD_CHARS) {
+        return Err(TopicError::Wildcard);
+    }
+    Ok(())
+}
+
+/// A pattern matching hierarchical topics.
+///
+/// Topic levels are separated by `/`.
+/// Within a pattern, the level `+` matches exactly one arbitrary level and
+/// the level `#`, which must be the last level, matches all remaining levels
+/// including none.
+///
+/// For example, the pattern `sensors/+/temperature` matches the topic
+/// `sensors/kitchen/temperature` and the pattern `sensors/#` matches
+/// the topics `sensors`, `sensors/kitchen` and `sensors/kitchen/temperature`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Pattern(String);
+
+impl Pattern {
+    /// Parses a topic pattern.
+    pub fn new(pattern: impl Into<String>) -> Result<Self, TopicError> {
+        let pattern = pattern.into();
+        if pattern.is_empty() {
+            return Err(TopicError::Empty);
+        }
+
+        let mut levels = pattern.split(SEPARATOR).peekable();
+        while let Some(level) = levels.next() {
+            match level {
+                MULTI_LEVEL_WILDCARD if levels.peek().is_some() => return Err(TopicError::MisplacedWildcard),
+                SINGLE_LEVEL_WILDCARD | MULTI_LEVEL_WILDCARD => (),
+                _ if level.contains(WILDCARD_CHARS) => return Err(TopicError::MisplacedWildcard),
+                _ => (),
+            }
+        }
+
+        Ok(Self(pattern))
+    }
+
+    /// Returns whether the pattern matches the topic.
+    pub fn matches(&self, topic: &str) -> bool {
+        let mut topic_levels = topic.split(SEPARATOR);
+        for level in self.0.split(SEPARATOR) {
+            if level == MULTI_LEVEL_WILDCARD {
+                return true;
+            }
+            match topic_levels.next() {
+                Some(topic_level) if level == SINGLE_LEVEL_WILDCARD || level == topic_level => (),
+                _ => return false,
+            }
+        }
+        topic_levels.next().is_none()
+    }
+
+    /// The pattern as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", &self.0)
+    }
+}
+
+impl TryFrom<String> for Pattern {
+    type Error = TopicError;
+
+    fn try_from(pattern: String) -> Result<Self, Self::Error> {
+        Self::new(pattern)
+    }
+}
+
+impl From<Pattern> for String {
+    fn from(pattern: Pattern) -> Self {
+        pattern.0
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = TopicError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl<T> Filter<Message<T>> for Pattern {
+    fn matches(&self, msg: &Message<T>) -> bool {
+        Pattern::matches(self, &msg.topic)
+    }
+}
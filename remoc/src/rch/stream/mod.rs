@@ -0,0 +1,162 @@
+//! Forwarding of arbitrary streams and sinks over remote channels.
+//!
+//! A local [Stream] is made available to a remote endpoint by [forward] or [try_forward],
+//! which return an [MPSC receiver](super::mpsc::Receiver) that can be sent to the remote endpoint.
+//! A local [Sink](futures::Sink) is made available by [forward_sink], which returns a [Sender]
+//! that can be sent to the remote endpoint.
+//!
+//! The stream is only polled when the remote receiver has buffer space available
+//! and values are only received from the remote sender when the sink is ready to
+//! accept them, thus back-pressure is applied in both directions.
+//! When the remote endpoint drops its channel half, the stream or sink is dropped,
+//! which cancels the underlying operation.
+//!
+//! # Example
+//!
+//! In the following example the client makes a stream of numbers available to
+//! the server, which sums them up.
+//!
+//! ```
+//! use futures::StreamExt;
+//! use remoc::prelude::*;
+//!
+//! // This would be run on the client.
+//! async fn client(mut tx: rch::base::Sender<rch::mpsc::Receiver<u32>>) {
+//!     let numbers = futures::stream::iter(0..5).map(|n| n * 10);
+//!     let (_, rx) = rch::stream::forward(numbers);
+//!     tx.send(rx).await.unwrap();
+//! }
+//!
+//! // This would be run on the server.
+//! async fn server(mut rx: rch::base::Receiver<rch::mpsc::Receiver<u32>>) {
+//!     let mut numbers = rx.recv().await.unwrap().unwrap();
+//!     let mut sum = 0;
+//!     while let Some(n) = numbers.next().await {
+//!         sum += n.unwrap();
+//!     }
+//!     assert_eq!(sum, 100);
+//! }
+//! # tokio_test::block_on(remoc::doctest::client_server(client, server));
+//! ```
+//!
+
+use futures::{FutureExt, Stream, StreamExt};
+use std::{
+    fmt,
+    future::Future,
+    pin::{Pin, pin},
+    task::{Context, Poll, ready},
+};
+
+use super::mpsc;
+use crate::{RemoteSend, codec, exec};
+
+mod sink;
+
+pub use sink::{SendError, Sender, forward_sink};
+
+/// Makes a local stream available to remote endpoints.
+///
+/// The returned [`Forwarding`] future resolves once forwarding has completed or an error occurs.
+/// The returned receiver may be sent to remote endpoints via channels.
+/// It receives all items of the stream and is closed when the stream ends.
+pub fn forward<S, Codec>(stream: S) -> (Forwarding, mpsc::Receiver<S::Item, Codec>)
+where
+    S: Stream + Send + 'static,
+    S::Item: RemoteSend,
+    Codec: codec::Codec,
+{
+    forward_impl(stream, |_| false)
+}
+
+/// Makes a local stream of results available to remote endpoints.
+///
+/// This is like [forward], but forwarding ends after the first error returned by the stream.
+/// The error is received by the remote endpoint as the terminal item.
+pub fn try_forward<S, T, E, Codec>(stream: S) -> (Forwarding, mpsc::Receiver<Result<T, E>, Codec>)
+where
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+    T: RemoteSend,
+    E: RemoteSend,
+    Codec: codec::Codec,
+{
+    forward_impl(stream, Result::is_err)
+}
+
+/// Forwards the items of the stream until `terminal` returns true for an item.
+fn forward_impl<S, Codec>(
+    stream: S, terminal: fn(&S::Item) -> bool,
+) -> (Forwarding, mpsc::Receiver<S::Item, Codec>)
+where
+    S: Stream + Send + 'static,
+    S::Item: RemoteSend,
+    Codec: codec::Codec,
+{
+    let (tx, rx) = mpsc::channel(1);
+
+    let hnd = exec::spawn(async move {
+        let mut stream = pin!(stream);
+
+        loop {
+            let permit = match tx.reserve().await {
+                Ok(permit) => permit,
+                Err(err) if err.is_closed() => break,
+                Err(err) => return Err(err),
+            };
+
+            tokio::select! {
+                biased;
+                () = tx.closed() => break,
+                item = stream.next() => match item {
+                    Some(item) => {
+                        let last = terminal(&item);
+                        permit.send(item);
+                        if last {
+                            break;
+                        }
+                    }
+                    None => break,
+                },
+            }
+        }
+
+        Ok(())
+    });
+
+    (Forwarding(hnd), rx)
+}
+
+/// Handle to obtain the result of forwarding a local stream remotely by [`forward`]
+/// or [`try_forward`].
+///
+/// Await this to obtain the result of the forwarding operation.
+/// The operation is assumed to have finished successfully if either the stream
+/// ended or the remote receiver is closed or dropped.
+///
+/// Dropping this *does not* stop forwarding.
+pub struct Forwarding(exec::task::JoinHandle<Result<(), mpsc::SendError<()>>>);
+
+impl fmt::Debug for Forwarding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Forwarding").finish()
+    }
+}
+
+impl Future for Forwarding {
+    type Output = Result<(), mpsc::SendError<()>>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        match ready!(self.0.poll_unpin(cx)) {
+            Ok(res) => Poll::Ready(res),
+            Err(_) => Poll::Ready(Err(mpsc::SendError::Closed(()))),
+        }
+    }
+}
+
+impl Forwarding {
+    /// Stops forwarding.
+    ///
+    /// The stream and the remote receiving half are dropped.
+    pub fn stop(self) {
+        self.0.abort();
+    }
+}
@@ -0,0 +1,137 @@
+use futures::{Sink, SinkExt};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt, pin::pin};
+
+use super::super::{mpsc, oneshot};
+use crate::{RemoteSend, codec, exec};
+
+/// Makes a local sink available to remote endpoints.
+///
+/// The returned sender may be sent to remote endpoints via channels.
+/// Values sent using it are fed into the sink.
+/// The sink is closed when the sender is [closed](Sender::close) or dropped.
+/// If the sink fails, its error is returned by the sender.
+///
+/// The error type of the sink must be remote sendable.
+/// Use [SinkExt::sink_map_err] to convert it, if necessary.
+pub fn forward_sink<K, T, Codec>(sink: K) -> Sender<T, K::Error, Codec>
+where
+    K: Sink<T> + Send + 'static,
+    K::Error: RemoteSend,
+    T: RemoteSend,
+    Codec: codec::Codec,
+{
+    let (tx, mut rx) = mpsc::channel::<T, Codec>(1);
+    let (result_tx, result_rx) = oneshot::channel();
+
+    exec::spawn(async move {
+        let mut sink = pin!(sink);
+
+        let res = async {
+            loop {
+                match rx.recv().await {
+                    Ok(Some(value)) => sink.feed(value).await?,
+                    Ok(None) => break,
+                    Err(err) if err.is_final() => break,
+                    Err(_) => continue,
+                }
+
+                while let Ok(value) = rx.try_recv() {
+                    sink.feed(value).await?;
+                }
+                sink.flush().await?;
+            }
+
+            sink.close().await
+        }
+        .await;
+
+        let _ = result_tx.send(res);
+    });
+
+    Sender { tx, result_rx: Some(result_rx) }
+}
+
+/// An error occurred during sending to a forwarded sink.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum SendError<E> {
+    /// The sink failed.
+    Sink(E),
+    /// Sending to the sink failed.
+    Send(mpsc::SendError<()>),
+    /// Receiving the result of closing the sink failed.
+    Recv(oneshot::RecvError),
+}
+
+impl<E> fmt::Display for SendError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Sink(err) => write!(f, "sink error: {err}"),
+            Self::Send(err) => write!(f, "send error: {err}"),
+            Self::Recv(err) => write!(f, "receive error: {err}"),
+        }
+    }
+}
+
+impl<E> Error for SendError<E> where E: fmt::Debug + fmt::Display {}
+
+/// Sending-half for a forwarded sink.
+///
+/// Instances are created by [forward_sink].
+///
+/// Can be sent over a remote channel.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "T: RemoteSend, E: RemoteSend, Codec: codec::Codec"))]
+#[serde(bound(deserialize = "T: RemoteSend, E: RemoteSend, Codec: codec::Codec"))]
+pub struct Sender<T, E, Codec = codec::Default> {
+    tx: mpsc::Sender<T, Codec>,
+    result_rx: Option<oneshot::Receiver<Result<(), E>, Codec>>,
+}
+
+impl<T, E, Codec> fmt::Debug for Sender<T, E, Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+impl<T, E, Codec> Sender<T, E, Codec>
+where
+    T: RemoteSend,
+    E: RemoteSend,
+    Codec: codec::Codec,
+{
+    /// Sends a value to the sink.
+    ///
+    /// Waits until the sink is ready to accept the value.
+    /// If the sink has failed, its error is returned once.
+    pub async fn send(&mut self, value: T) -> Result<(), SendError<E>> {
+        match self.tx.send(value).await {
+            Ok(_) => Ok(()),
+            Err(err) if err.is_closed() => match self.result_rx.take() {
+                Some(result_rx) => match result_rx.await {
+                    Ok(Err(err)) => Err(SendError::Sink(err)),
+                    _ => Err(SendError::Send(err.without_item())),
+                },
+                None => Err(SendError::Send(err.without_item())),
+            },
+            Err(err) => Err(SendError::Send(err.without_item())),
+        }
+    }
+
+    /// Closes the sink and waits until all sent values have been flushed.
+    pub async fn close(mut self) -> Result<(), SendError<E>> {
+        let Some(result_rx) = self.result_rx.take() else {
+            return Err(SendError::Send(mpsc::SendError::Closed(())));
+        };
+        drop(self.tx);
+
+        match result_rx.await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(err)) => Err(SendError::Sink(err)),
+            Err(err) => Err(SendError::Recv(err)),
+        }
+    }
+}
@@ -0,0 +1,388 @@
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    hash::{BuildHasher, Hash},
+    ops::{Deref, DerefMut},
+};
+
+use super::{
+    super::DEFAULT_MAX_ITEM_SIZE, DecodeError, Encoding, Receiver, RecvError, Sender,
+    receiver::TransportedReceiver, sender::TransportedSender,
+};
+use crate::{RemoteSend, codec};
+
+/// Default number of patches sent between two full snapshots of the value.
+pub const DEFAULT_SNAPSHOT_INTERVAL: u32 = 64;
+
+/// A value that can compute and apply patches describing its changes.
+///
+/// This is used by a [delta-encoded watch channel](diff_channel) to transmit only
+/// the changes of a value instead of the whole value.
+///
+/// It can be derived for structs using `#[derive(Diff)]`.
+pub trait Diff {
+    /// Patch describing the changes between two values.
+    type Patch: RemoteSend;
+
+    /// Computes the patch that transforms `self` into `new`.
+    ///
+    /// Returns `None` if both values are equal.
+    fn diff(&self, new: &Self) -> Option<Self::Patch>;
+
+    /// Applies a patch previously computed by [`diff`](Self::diff).
+    fn apply(&mut self, patch: Self::Patch);
+}
+
+/// Patch for a map, as computed by the [`Diff`] implementations of [`HashMap`] and [`BTreeMap`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MapPatch<K, V> {
+    /// Entries that have been inserted or whose value has changed.
+    pub inserted: Vec<(K, V)>,
+    /// Keys that have been removed.
+    pub removed: Vec<K>,
+}
+
+impl<K, V, S> Diff for HashMap<K, V, S>
+where
+    K: Eq + Hash + Clone + RemoteSend,
+    V: PartialEq + Clone + RemoteSend,
+    S: BuildHasher,
+{
+    type Patch = MapPatch<K, V>;
+
+    fn diff(&self, new: &Self) -> Option<Self::Patch> {
+        let inserted: Vec<_> =
+            new.iter().filter(|(k, v)| self.get(*k) != Some(*v)).map(|(k, v)| (k.clone(), v.clone())).collect();
+        let removed: Vec<_> = self.keys().filter(|k| !new.contains_key(*k)).cloned().collect();
+
+        if inserted.is_empty() && removed.is_empty() { None } else { Some(MapPatch { inserted, removed }) }
+    }
+
+    fn apply(&mut self, patch: Self::Patch) {
+        for k in &patch.removed {
+            self.remove(k);
+        }
+        self.extend(patch.inserted);
+    }
+}
+
+impl<K, V> Diff for BTreeMap<K, V>
+where
+    K: Ord + Clone + RemoteSend,
+    V: PartialEq + Clone + RemoteSend,
+{
+    type Patch = MapPatch<K, V>;
+
+    fn diff(&self, new: &Self) -> Option<Self::Patch> {
+        let inserted: Vec<_> =
+            new.iter().filter(|(k, v)| self.get(*k) != Some(*v)).map(|(k, v)| (k.clone(), v.clone())).collect();
+        let removed: Vec<_> = self.keys().filter(|k| !new.contains_key(*k)).cloned().collect();
+
+        if inserted.is_empty() && removed.is_empty() { None } else { Some(MapPatch { inserted, removed }) }
+    }
+
+    fn apply(&mut self, patch: Self::Patch) {
+        for k in &patch.removed {
+            self.remove(k);
+        }
+        self.extend(patch.inserted);
+    }
+}
+
+/// Message of a delta-encoded watch channel.
+#[derive(Serialize, Deserialize)]
+pub(crate) enum DiffMsg<T, P> {
+    /// Full value.
+    Value(Result<T, RecvError>),
+    /// Patch to apply to the current value.
+    Patch(P),
+}
+
+/// Encoding that transmits patches and periodically a full snapshot.
+pub(crate) struct Patched<T> {
+    /// Value the remote endpoint has.
+    last: Option<T>,
+    /// Number of patches sent since the last full value.
+    patches: u32,
+    snapshot_interval: u32,
+}
+
+impl<T> Patched<T>
+where
+    T: Clone,
+{
+    /// Creates the encoding for a remote endpoint that has the value `init`.
+    pub(crate) fn new(init: &Result<T, RecvError>, snapshot_interval: u32) -> Self {
+        Self { last: init.as_ref().ok().cloned(), patches: 0, snapshot_interval }
+    }
+}
+
+impl<T> Encoding<T> for Patched<T>
+where
+    T: Diff + RemoteSend + Clone,
+{
+    type Msg = DiffMsg<T, T::Patch>;
+
+    fn encode(&mut self, value: Result<T, RecvError>) -> Option<Self::Msg> {
+        let value = match value {
+            Ok(value) => value,
+            Err(err) => {
+                self.last = None;
+                return Some(DiffMsg::Value(Err(err)));
+            }
+        };
+
+        let msg = match &self.last {
+            Some(last) => {
+                let patch = last.diff(&value)?;
+                if self.patches < self.snapshot_interval {
+                    self.patches += 1;
+                    DiffMsg::Patch(patch)
+                } else {
+                    self.patches = 0;
+                    DiffMsg::Value(Ok(value.clone()))
+                }
+            }
+            None => {
+                self.patches = 0;
+                DiffMsg::Value(Ok(value.clone()))
+            }
+        };
+
+        self.last = Some(value);
+        Some(msg)
+    }
+
+    fn decode(msg: Self::Msg, tx: &tokio::sync::watch::Sender<Result<T, RecvError>>) -> Result<(), DecodeError> {
+        match msg {
+            DiffMsg::Value(value) => tx.send(value).map_err(|_| DecodeError::Closed),
+            DiffMsg::Patch(patch) => {
+                if tx.is_closed() {
+                    return Err(DecodeError::Closed);
+                }
+
+                // A patch cannot be applied to an error, which replaced the value the
+                // remote endpoint computed the patch against.
+                let mut applied = false;
+                tx.send_if_modified(|value| match value {
+                    Ok(value) => {
+                        value.apply(patch);
+                        applied = true;
+                        true
+                    }
+                    Err(_) => false,
+                });
+                if applied { Ok(()) } else { Err(DecodeError::Desync) }
+            }
+        }
+    }
+
+    fn resync(&mut self) {
+        self.last = None;
+    }
+}
+
+/// Creates a new delta-encoded watch channel, returning the sender and receiver.
+///
+/// This behaves like a [watch channel](super::channel), but when a value changes only
+/// a patch computed by [`Diff::diff`] is sent to remote receivers.
+/// A full snapshot of the value is sent when the channel is connected,
+/// after every [snapshot interval](DiffSender::snapshot_interval) patches and
+/// when a receiver cannot apply a patch, for example because receiving a previous one failed.
+///
+/// The sender and receiver may be sent to remote endpoints via channels.
+pub fn diff_channel<T, Codec>(init: T) -> (DiffSender<T, Codec>, DiffReceiver<T, Codec>)
+where
+    T: Diff + RemoteSend + Clone,
+{
+    let (inner_tx, inner_rx) = super::channel(init);
+    let snapshot_interval = DEFAULT_SNAPSHOT_INTERVAL;
+    (DiffSender { inner: inner_tx, snapshot_interval }, DiffReceiver { inner: inner_rx, snapshot_interval })
+}
+
+/// Sends values to the associated [DiffReceiver], transmitting only the changes
+/// to remote endpoints.
+///
+/// Instances are created by the [diff_channel] function.
+/// This dereferences to a watch [Sender] for sending and inspecting values.
+pub struct DiffSender<T, Codec = codec::Default> {
+    inner: Sender<T, Codec>,
+    snapshot_interval: u32,
+}
+
+impl<T, Codec> fmt::Debug for DiffSender<T, Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DiffSender").field("snapshot_interval", &self.snapshot_interval).finish()
+    }
+}
+
+impl<T, Codec> DiffSender<T, Codec>
+where
+    T: Send + 'static,
+{
+    /// Number of patches sent between two full snapshots of the value.
+    pub fn snapshot_interval(&self) -> u32 {
+        self.snapshot_interval
+    }
+
+    /// Sets the number of patches sent between two full snapshots of the value.
+    ///
+    /// This applies to receivers subscribed and to connections established afterwards.
+    /// If zero, the full value is always sent.
+    pub fn set_snapshot_interval(&mut self, snapshot_interval: u32) {
+        self.snapshot_interval = snapshot_interval;
+    }
+
+    /// Creates a new receiver subscribed to this sender.
+    pub fn subscribe(&self) -> DiffReceiver<T, Codec> {
+        DiffReceiver { inner: self.inner.subscribe(), snapshot_interval: self.snapshot_interval }
+    }
+}
+
+impl<T, Codec> Deref for DiffSender<T, Codec> {
+    type Target = Sender<T, Codec>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T, Codec> DerefMut for DiffSender<T, Codec> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+/// Delta-encoded watch sender in transport.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize"))]
+#[serde(bound(deserialize = "T: DeserializeOwned"))]
+struct TransportedDiffSender<T, Codec> {
+    sender: TransportedSender<T, Codec>,
+    snapshot_interval: u32,
+}
+
+impl<T, Codec> Serialize for DiffSender<T, Codec>
+where
+    T: Diff + RemoteSend + Sync + Clone,
+    Codec: codec::Codec,
+{
+    /// Serializes this sender for sending over a chmux channel.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let transported = TransportedDiffSender {
+            sender: self.inner.transport::<Patched<T>, _>()?,
+            snapshot_interval: self.snapshot_interval,
+        };
+        transported.serialize(serializer)
+    }
+}
+
+impl<'de, T, Codec> Deserialize<'de> for DiffSender<T, Codec>
+where
+    T: Diff + RemoteSend + Sync + Clone,
+    Codec: codec::Codec,
+{
+    /// Deserializes this sender after it has been received over a chmux channel.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let TransportedDiffSender { sender, snapshot_interval } =
+            TransportedDiffSender::<T, Codec>::deserialize(deserializer)?;
+        let inner = Sender::from_transported(sender, |data| Patched::new(data, snapshot_interval))?;
+        Ok(Self { inner, snapshot_interval })
+    }
+}
+
+/// Receives values from the associated [DiffSender], which may be located on a remote endpoint.
+///
+/// Instances are created by the [diff_channel] function.
+/// This dereferences to a watch [Receiver] for obtaining and awaiting values.
+#[derive(Clone)]
+pub struct DiffReceiver<T, Codec = codec::Default, const MAX_ITEM_SIZE: usize = DEFAULT_MAX_ITEM_SIZE> {
+    inner: Receiver<T, Codec, MAX_ITEM_SIZE>,
+    snapshot_interval: u32,
+}
+
+impl<T, Codec, const MAX_ITEM_SIZE: usize> fmt::Debug for DiffReceiver<T, Codec, MAX_ITEM_SIZE> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DiffReceiver").finish()
+    }
+}
+
+impl<T, Codec, const MAX_ITEM_SIZE: usize> DiffReceiver<T, Codec, MAX_ITEM_SIZE> {
+    /// Sets the maximum allowed item size in bytes when receiving items.
+    pub fn set_max_item_size<const NEW_MAX_ITEM_SIZE: usize>(self) -> DiffReceiver<T, Codec, NEW_MAX_ITEM_SIZE> {
+        DiffReceiver { inner: self.inner.set_max_item_size(), snapshot_interval: self.snapshot_interval }
+    }
+
+    /// Converts this into a watch [Receiver].
+    ///
+    /// The returned receiver transmits full values when sent to a remote endpoint.
+    pub fn into_inner(self) -> Receiver<T, Codec, MAX_ITEM_SIZE> {
+        self.inner
+    }
+}
+
+impl<T, Codec, const MAX_ITEM_SIZE: usize> Deref for DiffReceiver<T, Codec, MAX_ITEM_SIZE> {
+    type Target = Receiver<T, Codec, MAX_ITEM_SIZE>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T, Codec, const MAX_ITEM_SIZE: usize> DerefMut for DiffReceiver<T, Codec, MAX_ITEM_SIZE> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+/// Delta-encoded watch receiver in transport.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize"))]
+#[serde(bound(deserialize = "T: DeserializeOwned"))]
+struct TransportedDiffReceiver<T, Codec> {
+    receiver: TransportedReceiver<T, Codec>,
+    snapshot_interval: u32,
+}
+
+impl<T, Codec, const MAX_ITEM_SIZE: usize> Serialize for DiffReceiver<T, Codec, MAX_ITEM_SIZE>
+where
+    T: Diff + RemoteSend + Sync + Clone,
+    Codec: codec::Codec,
+{
+    /// Serializes this receiver for sending over a chmux channel.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let snapshot_interval = self.snapshot_interval;
+        let transported = TransportedDiffReceiver {
+            receiver: self.inner.transport(|data| Patched::new(data, snapshot_interval))?,
+            snapshot_interval,
+        };
+        transported.serialize(serializer)
+    }
+}
+
+impl<'de, T, Codec, const MAX_ITEM_SIZE: usize> Deserialize<'de> for DiffReceiver<T, Codec, MAX_ITEM_SIZE>
+where
+    T: Diff + RemoteSend + Sync + Clone,
+    Codec: codec::Codec,
+{
+    /// Deserializes the receiver after it has been received over a chmux channel.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let TransportedDiffReceiver { receiver, snapshot_interval } =
+            TransportedDiffReceiver::<T, Codec>::deserialize(deserializer)?;
+        let inner = Receiver::from_transported::<Patched<T>, _>(receiver)?;
+        Ok(Self { inner, snapshot_interval })
+    }
+}
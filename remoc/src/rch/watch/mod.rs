@@ -13,6 +13,48 @@
 //! with other endpoints, consider using an [read/write lock](crate::robj::rw_lock)
 //! instead.
 //!
+//! # Delta-encoded updates
+//!
+//! For large values of which only small parts change at a time, use a
+//! [delta-encoded watch channel](diff_channel).
+//! Its value type implements the [`Diff`] trait, usually by deriving it, and
+//! only patches describing the changes are transmitted to remote receivers.
+//! A full snapshot of the value is transmitted when a receiver connects and periodically
+//! after a configurable number of patches.
+//!
+//! ```
+//! use remoc::prelude::*;
+//! use std::collections::HashMap;
+//!
+//! #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rch::watch::Diff)]
+//! struct State {
+//!     version: u32,
+//!     #[diff(nested)]
+//!     entries: HashMap<String, String>,
+//! }
+//!
+//! // This would be run on the client.
+//! async fn client(mut tx: rch::base::Sender<rch::watch::DiffReceiver<State>>) {
+//!     let (watch_tx, watch_rx) = rch::watch::diff_channel(State { version: 0, entries: HashMap::new() });
+//!     tx.send(watch_rx).await.unwrap();
+//!
+//!     // Only the changed version and the new entry are transmitted.
+//!     watch_tx.send_modify(|state| {
+//!         state.version += 1;
+//!         state.entries.insert("key".to_string(), "value".to_string());
+//!     });
+//!     watch_tx.closed().await;
+//! }
+//!
+//! // This would be run on the server.
+//! async fn server(mut rx: rch::base::Receiver<rch::watch::DiffReceiver<State>>) {
+//!     let mut watch_rx = rx.recv().await.unwrap().unwrap();
+//!     let state = watch_rx.wait_for(|state| state.version == 1).await.unwrap();
+//!     assert_eq!(state.entries["key"], "value");
+//! }
+//! # tokio_test::block_on(remoc::doctest::client_server(client, server));
+//! ```
+//!
 //! # Example
 //!
 //! In the following example the client sends a number and a watch channel sender to the server.
@@ -62,12 +104,27 @@ use std::{
 };
 
 use super::{DEFAULT_MAX_ITEM_SIZE, RemoteSendError, base};
-use crate::{RemoteSend, chmux, codec, exec, rch::BACKCHANNEL_MSG_ERROR};
+use crate::{
+    RemoteSend, chmux, codec, exec,
+    rch::{BACKCHANNEL_MSG_ERROR, BACKCHANNEL_MSG_RESYNC},
+};
 
+mod diff;
 mod receiver;
 mod sender;
 
+pub use diff::{DEFAULT_SNAPSHOT_INTERVAL, Diff, DiffReceiver, DiffSender, MapPatch, diff_channel};
 pub use receiver::{ChangedError, Receiver, ReceiverStream, RecvError, WaitForError};
+/// Derives [`Diff`] for a struct.
+///
+/// A patch type named after the struct with the suffix `Patch` is generated.
+/// It contains the new value of each field that has changed.
+/// Fields marked with `#[diff(nested)]` must implement [`Diff`] themselves
+/// and only their patch is transmitted.
+/// All other fields must implement [`PartialEq`] and [`Clone`].
+#[cfg(feature = "diff-derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "diff-derive")))]
+pub use remoc_macro::Diff;
 pub use sender::{SendError, Sender};
 
 /// Returns a reference to the inner value.
@@ -202,32 +259,85 @@ where
     }
 }
 
+/// Encoding of values sent over a remote connection.
+pub(crate) trait Encoding<T>: Send + 'static {
+    /// Message sent over the remote connection.
+    type Msg: RemoteSend;
+
+    /// Encodes a value for sending.
+    ///
+    /// Returns `None` if the remote endpoint already has the value.
+    fn encode(&mut self, value: Result<T, RecvError>) -> Option<Self::Msg>;
+
+    /// Decodes a received message and updates the local channel.
+    ///
+    /// Fails if all local receivers have been dropped or the message cannot be
+    /// applied to the local value.
+    fn decode(msg: Self::Msg, tx: &tokio::sync::watch::Sender<Result<T, RecvError>>) -> Result<(), DecodeError>;
+
+    /// Called when the remote endpoint is out of sync and requests the full value.
+    fn resync(&mut self) {}
+}
+
+/// Error decoding a received message.
+pub(crate) enum DecodeError {
+    /// All local receivers have been dropped.
+    Closed,
+    /// The message cannot be applied because the local value is out of sync.
+    Desync,
+}
+
+/// Encoding that always transmits the full value.
+pub(crate) struct Full;
+
+impl<T> Encoding<T> for Full
+where
+    T: RemoteSend,
+{
+    type Msg = Result<T, RecvError>;
+
+    fn encode(&mut self, value: Result<T, RecvError>) -> Option<Self::Msg> {
+        Some(value)
+    }
+
+    fn decode(msg: Self::Msg, tx: &tokio::sync::watch::Sender<Result<T, RecvError>>) -> Result<(), DecodeError> {
+        tx.send(msg).map_err(|_| DecodeError::Closed)
+    }
+}
+
 /// Send implementation for deserializer of Sender and serializer of Receiver.
-async fn send_impl<T, Codec>(
+async fn send_impl<T, Codec, E>(
     mut rx: tokio::sync::watch::Receiver<Result<T, RecvError>>, raw_tx: chmux::Sender,
     mut raw_rx: chmux::Receiver, remote_send_err_tx: tokio::sync::mpsc::UnboundedSender<RemoteSendError>,
-    max_item_size: usize,
+    max_item_size: usize, mut encoding: E,
 ) where
     T: Serialize + Send + Clone + 'static,
     Codec: codec::Codec,
+    E: Encoding<T>,
 {
     // Encode data using remote sender for sending.
-    let mut remote_tx = base::Sender::<Result<T, RecvError>, Codec>::new(raw_tx);
+    let mut remote_tx = base::Sender::<E::Msg, Codec>::new(raw_tx);
     remote_tx.set_max_item_size(max_item_size);
 
     // Process events.
     loop {
-        tokio::select! {
+        let value = tokio::select! {
             biased;
 
             // Back channel message from remote endpoint.
             backchannel_msg = raw_rx.recv() => {
                 match backchannel_msg {
-                    Ok(Some(mut msg)) if msg.remaining() >= 1 => {
-                        if msg.get_u8() == BACKCHANNEL_MSG_ERROR {
+                    Ok(Some(mut msg)) if msg.remaining() >= 1 => match msg.get_u8() {
+                        BACKCHANNEL_MSG_ERROR => {
                             let _ = remote_send_err_tx.send(RemoteSendError::Forward);
+                            continue;
                         }
-                    }
+                        BACKCHANNEL_MSG_RESYNC => {
+                            encoding.resync();
+                            rx.borrow_and_update().clone()
+                        }
+                        _ => continue,
+                    },
                     _ => break,
                 }
             }
@@ -235,34 +345,35 @@ async fn send_impl<T, Codec>(
             // Data to send to remote endpoint.
             changed = rx.changed() => {
                 match changed {
-                    Ok(()) => {
-                        let value = rx.borrow_and_update().clone();
-                        if let Err(err) = remote_tx.send(value).await {
-                            let _ = remote_send_err_tx.send(RemoteSendError::Send(err.kind.clone()));
-                            if err.is_item_specific() {
-                                tracing::warn!(%err, "sending over remote channel failed");
-                                break
-                            }
-                        }
-                    }
+                    Ok(()) => rx.borrow_and_update().clone(),
                     Err(_) => break,
                 }
             }
+        };
+
+        let Some(msg) = encoding.encode(value) else { continue };
+        if let Err(err) = remote_tx.send(msg).await {
+            let _ = remote_send_err_tx.send(RemoteSendError::Send(err.kind.clone()));
+            if err.is_item_specific() {
+                tracing::warn!(%err, "sending over remote channel failed");
+                break;
+            }
         }
     }
 }
 
 /// Receive implementation for serializer of Sender and deserializer of Receiver.
-async fn recv_impl<T, Codec>(
+async fn recv_impl<T, Codec, E>(
     tx: tokio::sync::watch::Sender<Result<T, RecvError>>, mut raw_tx: chmux::Sender, raw_rx: chmux::Receiver,
     mut remote_send_err_rx: tokio::sync::mpsc::UnboundedReceiver<RemoteSendError>,
     mut current_err: Option<RemoteSendError>, max_item_size: usize,
 ) where
     T: DeserializeOwned + Send + 'static,
     Codec: codec::Codec,
+    E: Encoding<T>,
 {
     // Decode raw received data using remote receiver.
-    let mut remote_rx = base::Receiver::<Result<T, RecvError>, Codec>::new(raw_rx);
+    let mut remote_rx = base::Receiver::<E::Msg, Codec>::new(raw_rx);
     remote_rx.set_max_item_size(max_item_size);
 
     // Process events.
@@ -284,20 +395,21 @@ async fn recv_impl<T, Codec>(
 
             // Data received from remote endpoint.
             res = remote_rx.recv() => {
-                let mut is_final_err = false;
-                let value = match res {
-                    Ok(Some(value)) => value,
+                match res {
+                    Ok(Some(msg)) => match E::decode(msg, &tx) {
+                        Ok(()) => (),
+                        Err(DecodeError::Closed) => break,
+                        Err(DecodeError::Desync) => {
+                            let _ = raw_tx.send(vec![BACKCHANNEL_MSG_RESYNC].into()).await;
+                        }
+                    },
                     Ok(None) => break,
                     Err(err) => {
-                        is_final_err = err.is_final();
-                        Err(RecvError::RemoteReceive(err))
-                    },
-                };
-                if tx.send(value).is_err() {
-                    break;
-                }
-                if is_final_err {
-                    break;
+                        let is_final_err = err.is_final();
+                        if tx.send(Err(RecvError::RemoteReceive(err))).is_err() || is_final_err {
+                            break;
+                        }
+                    }
                 }
             }
         }
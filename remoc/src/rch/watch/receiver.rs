@@ -15,7 +15,7 @@ use super::{
         DEFAULT_MAX_ITEM_SIZE, RemoteSendError,
         base::{self, PortDeserializer, PortSerializer},
     },
-    Ref,
+    Encoding, Full, Ref,
 };
 use crate::{RemoteSend, chmux, codec};
 
@@ -270,19 +270,25 @@ impl<T, Codec, const MAX_ITEM_SIZE: usize> Drop for Receiver<T, Codec, MAX_ITEM_
     }
 }
 
-impl<T, Codec, const MAX_ITEM_SIZE: usize> Serialize for Receiver<T, Codec, MAX_ITEM_SIZE>
+impl<T, Codec, const MAX_ITEM_SIZE: usize> Receiver<T, Codec, MAX_ITEM_SIZE>
 where
     T: RemoteSend + Sync + Clone,
     Codec: codec::Codec,
 {
-    /// Serializes this receiver for sending over a chmux channel.
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    /// Prepares this receiver for sending over a chmux channel using the specified encoding.
+    ///
+    /// The encoding is created from the transported value.
+    pub(crate) fn transport<E, Err>(
+        &self, encoding: impl FnOnce(&Result<T, RecvError>) -> E,
+    ) -> Result<TransportedReceiver<T, Codec>, Err>
     where
-        S: serde::Serializer,
+        E: Encoding<T>,
+        Err: serde::ser::Error,
     {
         // Prepare channel for takeover.
         let mut rx = self.rx.clone();
         let data = rx.borrow_and_update().clone();
+        let encoding = encoding(&data);
         let remote_send_err_tx = self.remote_send_err_tx.clone();
 
         let port = PortSerializer::connect(|connect| {
@@ -296,35 +302,34 @@ where
                     }
                 };
 
-                super::send_impl::<T, Codec>(rx, raw_tx, raw_rx, remote_send_err_tx, MAX_ITEM_SIZE).await;
+                super::send_impl::<T, Codec, E>(rx, raw_tx, raw_rx, remote_send_err_tx, MAX_ITEM_SIZE, encoding)
+                    .await;
             }
             .boxed()
         })?;
 
-        // Encode chmux port number in transport type and serialize it.
-        let transported = TransportedReceiver::<T, Codec> {
+        // Encode chmux port number in transport type.
+        Ok(TransportedReceiver::<T, Codec> {
             port,
             data,
             max_item_size: self.max_item_size().try_into().unwrap_or(u64::MAX),
             codec: PhantomData,
-        };
-        transported.serialize(serializer)
+        })
     }
 }
 
-impl<'de, T, Codec, const MAX_ITEM_SIZE: usize> Deserialize<'de> for Receiver<T, Codec, MAX_ITEM_SIZE>
+impl<T, Codec, const MAX_ITEM_SIZE: usize> Receiver<T, Codec, MAX_ITEM_SIZE>
 where
     T: RemoteSend + Sync,
     Codec: codec::Codec,
 {
-    /// Deserializes the receiver after it has been received over a chmux channel.
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    /// Creates a receiver from its transported form using the specified encoding.
+    pub(crate) fn from_transported<E, Err>(transported: TransportedReceiver<T, Codec>) -> Result<Self, Err>
     where
-        D: serde::Deserializer<'de>,
+        E: Encoding<T>,
+        Err: serde::de::Error,
     {
-        // Get chmux port number from deserialized transport type.
-        let TransportedReceiver { port, data, max_item_size, .. } =
-            TransportedReceiver::<T, Codec>::deserialize(deserializer)?;
+        let TransportedReceiver { port, data, max_item_size, .. } = transported;
 
         let max_item_size = usize::try_from(max_item_size).unwrap_or(usize::MAX);
         if max_item_size > MAX_ITEM_SIZE {
@@ -349,7 +354,8 @@ where
                     }
                 };
 
-                super::recv_impl::<T, Codec>(tx, raw_tx, raw_rx, remote_send_err_rx, None, MAX_ITEM_SIZE).await;
+                super::recv_impl::<T, Codec, E>(tx, raw_tx, raw_rx, remote_send_err_rx, None, MAX_ITEM_SIZE)
+                    .await;
             }
             .boxed()
         })?;
@@ -358,6 +364,35 @@ where
     }
 }
 
+impl<T, Codec, const MAX_ITEM_SIZE: usize> Serialize for Receiver<T, Codec, MAX_ITEM_SIZE>
+where
+    T: RemoteSend + Sync + Clone,
+    Codec: codec::Codec,
+{
+    /// Serializes this receiver for sending over a chmux channel.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.transport::<Full, _>(|_| Full)?.serialize(serializer)
+    }
+}
+
+impl<'de, T, Codec, const MAX_ITEM_SIZE: usize> Deserialize<'de> for Receiver<T, Codec, MAX_ITEM_SIZE>
+where
+    T: RemoteSend + Sync,
+    Codec: codec::Codec,
+{
+    /// Deserializes the receiver after it has been received over a chmux channel.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let transported = TransportedReceiver::<T, Codec>::deserialize(deserializer)?;
+        Self::from_transported::<Full, _>(transported)
+    }
+}
+
 /// A wrapper around a watch [Receiver] that implements [Stream](futures::Stream).
 ///
 /// This stream will always start by yielding the current value when it is polled,
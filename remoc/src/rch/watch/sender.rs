@@ -7,7 +7,7 @@ use super::{
         RemoteSendError, SendErrorExt,
         base::{self, PortDeserializer, PortSerializer},
     },
-    Receiver, Ref,
+    Encoding, Full, Receiver, Ref,
     receiver::RecvError,
 };
 use crate::{RemoteSend, chmux, codec};
@@ -317,15 +317,16 @@ impl<T, Codec> Drop for Sender<T, Codec> {
     }
 }
 
-impl<T, Codec> Serialize for Sender<T, Codec>
+impl<T, Codec> Sender<T, Codec>
 where
     T: RemoteSend + Sync + Clone,
     Codec: codec::Codec,
 {
-    /// Serializes this sender for sending over a chmux channel.
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    /// Prepares this sender for sending over a chmux channel using the specified encoding.
+    pub(crate) fn transport<E, Err>(&self) -> Result<TransportedSender<T, Codec>, Err>
     where
-        S: serde::Serializer,
+        E: Encoding<T>,
+        Err: serde::ser::Error,
     {
         let max_item_size = self.max_item_size();
 
@@ -352,41 +353,45 @@ where
                     }
                 };
 
-                super::recv_impl::<T, Codec>(tx, raw_tx, raw_rx, remote_send_err_rx, current_err, max_item_size)
-                    .await;
+                super::recv_impl::<T, Codec, E>(
+                    tx,
+                    raw_tx,
+                    raw_rx,
+                    remote_send_err_rx,
+                    current_err,
+                    max_item_size,
+                )
+                .await;
             }
             .boxed()
         })?;
 
         // Encode chmux port number in transport type and serialize it.
         let data = self.inner.as_ref().unwrap().tx.borrow().clone();
-        let transported = TransportedSender::<T, Codec> {
+        Ok(TransportedSender::<T, Codec> {
             port,
             data,
             max_item_size: max_item_size.try_into().unwrap_or(u64::MAX),
             codec: PhantomData,
-        };
-        transported.serialize(serializer)
+        })
     }
-}
 
-impl<'de, T, Codec> Deserialize<'de> for Sender<T, Codec>
-where
-    T: RemoteSend + Sync + Clone,
-    Codec: codec::Codec,
-{
-    /// Deserializes this sender after it has been received over a chmux channel.
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    /// Creates a sender from its transported form using the specified encoding.
+    ///
+    /// The encoding is created from the initial value.
+    pub(crate) fn from_transported<E, Err>(
+        transported: TransportedSender<T, Codec>, encoding: impl FnOnce(&Result<T, RecvError>) -> E,
+    ) -> Result<Self, Err>
     where
-        D: serde::Deserializer<'de>,
+        E: Encoding<T>,
+        Err: serde::de::Error,
     {
-        // Get chmux port number from deserialized transport type.
-        let TransportedSender { port, data, max_item_size, .. } =
-            TransportedSender::<T, Codec>::deserialize(deserializer)?;
+        let TransportedSender { port, data, max_item_size, .. } = transported;
         let max_item_size = usize::try_from(max_item_size).unwrap_or(usize::MAX);
         if data.is_err() {
             return Err(serde::de::Error::custom("received watch data with error"));
         }
+        let encoding = encoding(&data);
 
         // Create internal communication channels.
         let (tx, rx) = tokio::sync::watch::channel(data);
@@ -405,7 +410,8 @@ where
                     }
                 };
 
-                super::send_impl::<T, Codec>(rx, raw_tx, raw_rx, remote_send_err_tx, max_item_size).await;
+                super::send_impl::<T, Codec, E>(rx, raw_tx, raw_rx, remote_send_err_tx, max_item_size, encoding)
+                    .await;
             }
             .boxed()
         })?;
@@ -413,3 +419,32 @@ where
         Ok(Self::new(tx, remote_send_err_tx2, remote_send_err_rx, max_item_size))
     }
 }
+
+impl<T, Codec> Serialize for Sender<T, Codec>
+where
+    T: RemoteSend + Sync + Clone,
+    Codec: codec::Codec,
+{
+    /// Serializes this sender for sending over a chmux channel.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.transport::<Full, _>()?.serialize(serializer)
+    }
+}
+
+impl<'de, T, Codec> Deserialize<'de> for Sender<T, Codec>
+where
+    T: RemoteSend + Sync + Clone,
+    Codec: codec::Codec,
+{
+    /// Deserializes this sender after it has been received over a chmux channel.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let transported = TransportedSender::<T, Codec>::deserialize(deserializer)?;
+        Self::from_transported(transported, |_| Full)
+    }
+}
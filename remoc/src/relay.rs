@@ -0,0 +1,407 @@
+//! Relay for connecting endpoints that cannot reach each other directly.
+//!
+//! A [Relay] runs on a host that is reachable by all endpoints, for example when the
+//! endpoints are located behind NAT and can only establish outgoing connections.
+//! Each endpoint connects to the relay using [RelayEndpoint::connect] and registers itself
+//! under a unique name, which is transmitted as part of the
+//! [handshake metadata](crate::chmux::Metadata).
+//!
+//! An endpoint can then open a [base channel](crate::rch::base) to another registered endpoint
+//! using [RelayEndpoint::connect_to], which the other endpoint obtains using
+//! [RelayEndpoint::accept].
+//! The relay splices the underlying [chmux] ports of both endpoints together
+//! and forwards all data.
+//! Ports opened over a spliced port, for example by sending a [remote channel](crate::rch)
+//! or an [RTC client](crate::rtc), are forwarded recursively, thus all Remoc objects work
+//! as if both endpoints were connected directly.
+//!
+//! # Example
+//!
+//! In the following example two endpoints are connected via a relay and exchange a number.
+//!
+//! ```
+//! use futures::StreamExt;
+//! use remoc::{prelude::*, relay::{Relay, RelayEndpoint}};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let relay = Relay::new();
+//!
+//!     // Connects an endpoint with the specified name to the relay.
+//!     async fn connect(relay: &Relay, name: &str) -> RelayEndpoint {
+//!         let (a_tx, b_rx) = futures::channel::mpsc::channel(16);
+//!         let (b_tx, a_rx) = futures::channel::mpsc::channel(16);
+//!         let relay = relay.clone();
+//!         tokio::spawn(async move {
+//!             let _ = relay.serve(remoc::Cfg::default(), b_tx, b_rx.map(Ok::<_, std::io::Error>)).await;
+//!         });
+//!
+//!         let (mux, endpoint) =
+//!             RelayEndpoint::connect(remoc::Cfg::default(), name, a_tx, a_rx.map(Ok::<_, std::io::Error>))
+//!                 .await
+//!                 .unwrap();
+//!         tokio::spawn(mux.run());
+//!         endpoint
+//!     }
+//!
+//!     let alice = connect(&relay, "alice").await;
+//!     let mut bob = connect(&relay, "bob").await;
+//!
+//!     let bob_task = tokio::spawn(async move {
+//!         let (peer, _tx, mut rx): (_, rch::base::Sender<()>, rch::base::Receiver<u32>) =
+//!             bob.accept().await.unwrap().unwrap();
+//!         assert_eq!(peer, "alice");
+//!         assert_eq!(rx.recv().await.unwrap(), Some(42));
+//!     });
+//!
+//!     let (mut tx, _rx): (rch::base::Sender<u32>, rch::base::Receiver<()>) =
+//!         alice.connect_to("bob").await.unwrap();
+//!     tx.send(42).await.unwrap();
+//!     bob_task.await.unwrap();
+//! }
+//! ```
+
+use bytes::Bytes;
+use futures::{Sink, Stream, future};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::watch;
+use tracing::Instrument;
+
+use crate::{
+    RemoteSend,
+    chmux::{self, AcceptMetadata, ChMux, ChMuxError},
+    codec, exec,
+    rch::base,
+};
+
+/// Metadata header containing the name an endpoint registers under.
+const NAME_HEADER: &str = "remoc-relay-name";
+
+/// Error occurred while connecting to another endpoint via a relay.
+#[derive(Debug, Clone)]
+pub enum RelayError {
+    /// Opening a port to the relay failed.
+    Connect(chmux::ConnectError),
+    /// Accepting a port from the relay failed.
+    Listen(chmux::ListenerError),
+    /// Sending to the relay failed.
+    Send(chmux::SendError),
+    /// Receiving from the relay failed.
+    Recv(chmux::RecvError),
+    /// The relay refused the connection, for example because the
+    /// requested endpoint is not registered.
+    Refused(String),
+    /// The relay did not follow the relay protocol.
+    Protocol,
+}
+
+impl fmt::Display for RelayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Connect(err) => write!(f, "relay connect failed: {err}"),
+            Self::Listen(err) => write!(f, "relay listen failed: {err}"),
+            Self::Send(err) => write!(f, "relay send failed: {err}"),
+            Self::Recv(err) => write!(f, "relay receive failed: {err}"),
+            Self::Refused(reason) => write!(f, "relay refused connection: {reason}"),
+            Self::Protocol => write!(f, "relay protocol error"),
+        }
+    }
+}
+
+impl Error for RelayError {}
+
+impl From<chmux::ConnectError> for RelayError {
+    fn from(err: chmux::ConnectError) -> Self {
+        Self::Connect(err)
+    }
+}
+
+impl From<chmux::ListenerError> for RelayError {
+    fn from(err: chmux::ListenerError) -> Self {
+        Self::Listen(err)
+    }
+}
+
+impl From<chmux::SendError> for RelayError {
+    fn from(err: chmux::SendError) -> Self {
+        Self::Send(err)
+    }
+}
+
+impl From<chmux::RecvError> for RelayError {
+    fn from(err: chmux::RecvError) -> Self {
+        Self::Recv(err)
+    }
+}
+
+/// Receives the relay control message at the start of a port.
+async fn recv_control(rx: &mut chmux::Receiver) -> Result<String, RelayError> {
+    let data = rx.recv().await?.ok_or(RelayError::Protocol)?;
+    String::from_utf8(Vec::from(data)).map_err(|_| RelayError::Protocol)
+}
+
+/// Sends a relay control message at the start of a port.
+async fn send_control(tx: &mut chmux::Sender, msg: &str) -> Result<(), RelayError> {
+    tx.send(Bytes::copy_from_slice(msg.as_bytes())).await?;
+    Ok(())
+}
+
+/// Registered endpoints by name.
+///
+/// The client is [None] while the handshake with the endpoint is in progress.
+type Endpoints = Arc<Mutex<HashMap<String, watch::Sender<Option<chmux::Client>>>>>;
+
+/// Relay splicing connections between registered endpoints.
+///
+/// Clones share the same set of registered endpoints.
+#[derive(Clone, Default)]
+pub struct Relay {
+    endpoints: Endpoints,
+}
+
+impl fmt::Debug for Relay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Relay").field("endpoints", &self.endpoints()).finish()
+    }
+}
+
+impl Relay {
+    /// Creates a new relay with no registered endpoints.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names of the currently registered endpoints.
+    pub fn endpoints(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.endpoints.lock().unwrap().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Serves a connection from an endpoint over a framed transport.
+    ///
+    /// This establishes a [chmux] connection over the transport and registers
+    /// the endpoint under the name it provided.
+    /// The connection is refused if the endpoint provided no name or the name is already
+    /// registered.
+    ///
+    /// Returns when the connection has been terminated, after the endpoint has been
+    /// unregistered.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    pub async fn serve<TransportSink, TransportSinkError, TransportStream, TransportStreamError>(
+        &self, mut cfg: crate::Cfg, transport_sink: TransportSink, transport_stream: TransportStream,
+    ) -> Result<(), ChMuxError<TransportSinkError, TransportStreamError>>
+    where
+        TransportSink: Sink<Bytes, Error = TransportSinkError> + Send + Unpin,
+        TransportSinkError: Error + Send + Sync + 'static,
+        TransportStream: Stream<Item = Result<Bytes, TransportStreamError>> + Send + Unpin,
+        TransportStreamError: Error + Send + Sync + 'static,
+    {
+        // The name is reserved during the handshake, so that the endpoint is reachable
+        // as soon as its connection has been established.
+        let endpoints = self.endpoints.clone();
+        let reserved = Arc::new(Mutex::new(None));
+        let reserved_name = reserved.clone();
+        let user_accept = cfg.accept_metadata.take();
+        cfg.accept_metadata = Some(AcceptMetadata::new(move |metadata| {
+            let Some(name) = metadata.header(NAME_HEADER) else {
+                return Err("no relay endpoint name provided".to_string());
+            };
+            if let Some(accept) = &user_accept {
+                accept.check(metadata)?;
+            }
+
+            let mut endpoints = endpoints.lock().unwrap();
+            if endpoints.contains_key(name) {
+                return Err(format!("relay endpoint {name} is already registered"));
+            }
+            endpoints.insert(name.to_string(), watch::channel(None).0);
+            *reserved_name.lock().unwrap() = Some(name.to_string());
+            Ok(())
+        }));
+
+        let (mux, client, mut listener) = match ChMux::new(cfg, transport_sink, transport_stream).await {
+            Ok(mux) => mux,
+            Err(err) => {
+                if let Some(name) = reserved.lock().unwrap().take() {
+                    self.endpoints.lock().unwrap().remove(&name);
+                }
+                return Err(err);
+            }
+        };
+        let name = reserved.lock().unwrap().take().expect("relay endpoint name not reserved");
+
+        // Register endpoint.
+        if let Some(tx) = self.endpoints.lock().unwrap().get(&name) {
+            tx.send_replace(Some(client));
+        }
+        tracing::debug!(%name, "relay endpoint registered");
+
+        // Process connection requests from endpoint.
+        let endpoints = self.endpoints.clone();
+        let accept_name = name.clone();
+        let accept_task = async move {
+            while let Ok(Some((tx, rx))) = listener.accept().await {
+                exec::spawn(Self::splice(endpoints.clone(), accept_name.clone(), tx, rx).in_current_span());
+            }
+            future::pending().await
+        };
+
+        let res = tokio::select! {
+            res = mux.run() => res,
+            () = accept_task => Ok(()),
+        };
+
+        // Unregister endpoint.
+        self.endpoints.lock().unwrap().remove(&name);
+        tracing::debug!(%name, "relay endpoint unregistered");
+
+        res
+    }
+
+    /// Splices a port opened by an endpoint with a port to the requested target endpoint.
+    async fn splice(endpoints: Endpoints, source: String, mut tx: chmux::Sender, mut rx: chmux::Receiver) {
+        let result = async {
+            let target = recv_control(&mut rx).await?;
+            let target_rx = endpoints.lock().unwrap().get(&target).map(|tx| tx.subscribe());
+            let target_client = match target_rx {
+                Some(mut rx) => rx.wait_for(Option::is_some).await.ok().and_then(|client| client.clone()),
+                None => None,
+            };
+            let Some(target_client) = target_client else {
+                return Ok(Err(format!("relay endpoint {target} is not registered")));
+            };
+
+            let (mut target_tx, target_rx) = match target_client.connect().await {
+                Ok(port) => port,
+                Err(err) => return Ok(Err(format!("connecting to relay endpoint {target} failed: {err}"))),
+            };
+            send_control(&mut target_tx, &source).await?;
+            Ok::<_, RelayError>(Ok((target, target_tx, target_rx)))
+        }
+        .await;
+
+        let (target, target_tx, target_rx) = match result {
+            Ok(Ok(target_port)) => target_port,
+            Ok(Err(reason)) => {
+                tracing::debug!(%source, %reason, "relay connection refused");
+                let _ = send_control(&mut tx, &reason).await;
+                return;
+            }
+            Err(err) => {
+                tracing::debug!(%source, %err, "relay connection failed");
+                return;
+            }
+        };
+
+        if send_control(&mut tx, "").await.is_err() {
+            return;
+        }
+        tracing::debug!(%source, %target, "relay connection established");
+
+        for (mut rx, mut tx) in [(rx, target_tx), (target_rx, tx)] {
+            exec::spawn(
+                async move {
+                    if let Err(err) = rx.forward(&mut tx).await {
+                        tracing::debug!(%err, "relay forwarding failed");
+                    }
+                }
+                .in_current_span(),
+            );
+        }
+    }
+}
+
+/// An endpoint connected to a [Relay].
+///
+/// Use [connect_to](Self::connect_to) to open a channel to another endpoint and
+/// [accept](Self::accept) to accept a channel opened by another endpoint.
+pub struct RelayEndpoint {
+    name: String,
+    client: chmux::Client,
+    listener: chmux::Listener,
+}
+
+impl fmt::Debug for RelayEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RelayEndpoint").field("name", &self.name).finish_non_exhaustive()
+    }
+}
+
+impl RelayEndpoint {
+    /// Connects to a relay over a framed transport and registers under the specified name.
+    ///
+    /// The connection is refused by the relay if the name is already registered.
+    ///
+    /// You must run the returned [multiplexer](ChMux) for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    pub async fn connect<TransportSink, TransportSinkError, TransportStream, TransportStreamError>(
+        mut cfg: crate::Cfg, name: impl Into<String>, transport_sink: TransportSink,
+        transport_stream: TransportStream,
+    ) -> Result<(ChMux<TransportSink, TransportStream>, Self), ChMuxError<TransportSinkError, TransportStreamError>>
+    where
+        TransportSink: Sink<Bytes, Error = TransportSinkError> + Send + Unpin,
+        TransportSinkError: Error + Send + Sync + 'static,
+        TransportStream: Stream<Item = Result<Bytes, TransportStreamError>> + Send + Unpin,
+        TransportStreamError: Error + Send + Sync + 'static,
+    {
+        let name = name.into();
+        cfg.metadata.headers.insert(NAME_HEADER.to_string(), name.clone());
+
+        let (mux, client, listener) = ChMux::new(cfg, transport_sink, transport_stream).await?;
+        Ok((mux, Self { name, client, listener }))
+    }
+
+    /// Name this endpoint is registered under.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Opens a channel to the endpoint registered under the specified name.
+    ///
+    /// This waits until the other endpoint [accepts](Self::accept) the channel.
+    pub async fn connect_to<Tx, Rx, Codec>(
+        &self, peer: &str,
+    ) -> Result<(base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>), RelayError>
+    where
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (mut tx, mut rx) = self.client.connect().await?;
+        send_control(&mut tx, peer).await?;
+
+        let reason = recv_control(&mut rx).await?;
+        if !reason.is_empty() {
+            return Err(RelayError::Refused(reason));
+        }
+
+        Ok((base::Sender::new(tx), base::Receiver::new(rx)))
+    }
+
+    /// Accepts a channel opened by another endpoint.
+    ///
+    /// Returns the name of the other endpoint together with the channel.
+    /// Returns [None] when the relay accepts no more connections.
+    pub async fn accept<Tx, Rx, Codec>(
+        &mut self,
+    ) -> Result<Option<(String, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>)>, RelayError>
+    where
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let Some((tx, mut rx)) = self.listener.accept().await? else { return Ok(None) };
+        let peer = recv_control(&mut rx).await?;
+        Ok(Some((peer, base::Sender::new(tx), base::Receiver::new(rx))))
+    }
+}
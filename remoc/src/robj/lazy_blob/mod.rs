@@ -59,21 +59,36 @@
 //! ```
 //!
 
-use bytes::Bytes;
+use bytes::{Buf, Bytes, BytesMut};
 use futures::{
     FutureExt, future,
     future::{BoxFuture, MaybeDone},
 };
 use serde::{Deserialize, Serialize};
-use std::{convert::TryFrom, fmt, pin::Pin, sync::Arc};
-use tokio::sync::Mutex;
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
+use std::{
+    convert::TryFrom,
+    fmt,
+    io::{self, SeekFrom},
+    pin::Pin,
+    sync::Arc,
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWrite, AsyncWriteExt},
+    sync::{Mutex, watch},
+};
 use tracing::Instrument;
 
 use crate::{
     chmux,
-    chmux::DataBuf,
+    chmux::{DataBuf, Received, RecvChunkError, RecvError},
     codec, exec,
-    rch::{ConnectError, mpsc},
+    rch::{
+        ConnectError,
+        io::{Progress, ProgressTracker},
+        mpsc,
+    },
 };
 
 mod fw_bin;
@@ -156,6 +171,177 @@ impl Drop for Provider {
     }
 }
 
+/// Size of chunks read from a reader source.
+const READ_CHUNK_SIZE: u64 = 65_536;
+
+/// A reader that can be used as the source of a [LazyBlob].
+trait Reader: AsyncRead + AsyncSeek + Send + Unpin {}
+
+impl<T> Reader for T where T: AsyncRead + AsyncSeek + Send + Unpin {}
+
+/// Source of the binary data held by the provider.
+#[derive(Clone)]
+enum Source {
+    /// Data in memory.
+    Data(Bytes),
+    /// Reader that is rewound for each request.
+    Reader(Arc<Mutex<Box<dyn Reader>>>),
+    /// File that is opened for each request.
+    #[cfg(feature = "fs")]
+    File(Arc<PathBuf>),
+}
+
+impl Source {
+    /// Sends the binary data of length `len` to the requesting [LazyBlob].
+    async fn send(self, fw_tx: fw_bin::Sender, len: u64) -> io::Result<()> {
+        match self {
+            Self::Data(data) => match fw_tx.into_inner() {
+                Some(fw_bin::InnerSender::Local(tx)) => {
+                    let _ = tx.send(data);
+                }
+                Some(fw_bin::InnerSender::Remote(bin_tx)) => {
+                    let mut tx = bin_tx.into_inner().await.map_err(io::Error::other)?;
+                    tx.send(data).await?;
+                }
+                None => (),
+            },
+            Self::Reader(reader) => {
+                let mut reader = reader.lock().await;
+                reader.seek(SeekFrom::Start(0)).await?;
+                send_from_reader(&mut *reader, fw_tx, len).await?;
+            }
+            #[cfg(feature = "fs")]
+            Self::File(path) => {
+                let mut file = tokio::fs::File::open(&*path).await?;
+                send_from_reader(&mut file, fw_tx, len).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Streams exactly `len` bytes from the reader to the requesting [LazyBlob].
+///
+/// If the reader provides less data, the transmission is cancelled.
+async fn send_from_reader<R>(reader: &mut R, fw_tx: fw_bin::Sender, len: u64) -> io::Result<()>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    let mut reader = reader.take(len);
+
+    match fw_tx.into_inner() {
+        Some(fw_bin::InnerSender::Local(tx)) => {
+            let mut data = BytesMut::with_capacity(len.try_into().unwrap_or_default());
+            while reader.read_buf(&mut data).await? > 0 {}
+            if data.len() as u64 != len {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let _ = tx.send(data.freeze());
+        }
+        Some(fw_bin::InnerSender::Remote(bin_tx)) => {
+            let mut tx = bin_tx.into_inner().await.map_err(io::Error::other)?;
+            let mut chunks = tx.send_chunks();
+            let mut remaining = len;
+            while remaining > 0 {
+                let mut chunk = BytesMut::with_capacity(remaining.min(READ_CHUNK_SIZE) as usize);
+                if reader.read_buf(&mut chunk).await? == 0 {
+                    return Err(io::ErrorKind::UnexpectedEof.into());
+                }
+                remaining -= chunk.len() as u64;
+                chunks = chunks.send(chunk.freeze()).await?;
+            }
+            chunks.finish().await?;
+        }
+        None => (),
+    }
+
+    Ok(())
+}
+
+/// Receives the binary data from the provider chunk by chunk, while updating the progress.
+struct ChunkedFetch<'a> {
+    inner: ChunkedFetchInner,
+    len: usize,
+    received: usize,
+    progress: &'a ProgressTracker,
+}
+
+enum ChunkedFetchInner {
+    Local(Option<Bytes>),
+    Remote { rx: chmux::Receiver, started: bool },
+}
+
+impl<'a> ChunkedFetch<'a> {
+    /// Requests the binary data of length `len` from the provider.
+    async fn start<Codec>(
+        req_tx: &mpsc::Sender<fw_bin::Sender, Codec, 1>, len: usize, progress: &'a ProgressTracker,
+    ) -> Result<Self, FetchError>
+    where
+        Codec: codec::Codec,
+    {
+        progress.start();
+
+        let (fw_tx, fw_rx) = fw_bin::channel();
+        let _ = req_tx.send(fw_tx).await;
+        let inner = match fw_rx.into_inner().await {
+            Some(fw_bin::InnerReceiver::Local(data)) => ChunkedFetchInner::Local(Some(data)),
+            Some(fw_bin::InnerReceiver::Remote(bin_rx)) => {
+                let mut rx = bin_rx.into_inner().await.map_err(FetchError::RemoteConnect)?;
+                // Force reception in chunks.
+                rx.set_max_data_size(0);
+                ChunkedFetchInner::Remote { rx, started: false }
+            }
+            None => return Err(FetchError::Dropped),
+        };
+
+        Ok(Self { inner, len, received: 0, progress })
+    }
+
+    /// Receives the next chunk of binary data.
+    ///
+    /// Returns `None` when all data has been received and fails with
+    /// [FetchError::Dropped] if the data ends before its announced length.
+    async fn next(&mut self) -> Result<Option<Bytes>, FetchError> {
+        let chunk = match &mut self.inner {
+            ChunkedFetchInner::Local(data) => data.take(),
+            ChunkedFetchInner::Remote { rx, started } => {
+                if !*started {
+                    *started = true;
+                    match rx.recv_any().await.map_err(FetchError::RemoteReceive)? {
+                        Some(Received::Data(data)) => Some(data.into()),
+                        Some(Received::Chunks) => rx.recv_chunk().await.map_err(Self::chunk_error)?,
+                        Some(Received::Requests(_)) | None => return Err(FetchError::Dropped),
+                    }
+                } else {
+                    rx.recv_chunk().await.map_err(Self::chunk_error)?
+                }
+            }
+        };
+
+        let Some(chunk) = chunk else {
+            // The provider stopped transmitting before all data was sent.
+            if self.received < self.len {
+                return Err(FetchError::Dropped);
+            }
+            return Ok(None);
+        };
+        if self.received + chunk.len() > self.len {
+            return Err(FetchError::RemoteReceive(RecvError::ExceedsMaxDataSize(self.len)));
+        }
+        self.received += chunk.len();
+        self.progress.update(self.received as u64);
+
+        Ok(Some(chunk))
+    }
+
+    fn chunk_error(err: RecvChunkError) -> FetchError {
+        match err {
+            RecvChunkError::Cancelled => FetchError::Dropped,
+            RecvChunkError::ChMux => FetchError::RemoteReceive(RecvError::ChMux),
+        }
+    }
+}
+
 /// Lazily transferred binary data. 🐡💤
 ///
 /// Allows efficient transmission of large binary data on-demand.
@@ -171,6 +357,9 @@ pub struct LazyBlob<Codec = codec::Default> {
     #[serde(default)]
     #[allow(clippy::type_complexity)]
     fetch_task: Arc<Mutex<Option<Pin<Box<MaybeDone<BoxFuture<'static, Result<DataBuf, FetchError>>>>>>>>,
+    #[serde(skip)]
+    #[serde(default)]
+    progress: Arc<ProgressTracker>,
 }
 
 impl<Codec> fmt::Debug for LazyBlob<Codec> {
@@ -196,11 +385,48 @@ where
     /// Create a new LazyBlob with the specified data and return it together with
     /// its provider.
     pub fn provided(data: Bytes) -> (Self, Provider) {
+        let len = data.len() as _;
+        Self::provided_source(Source::Data(data), len)
+    }
+
+    /// Create a new LazyBlob with the data read from the specified reader.
+    ///
+    /// The length of the data is determined by seeking to the end of the reader.
+    /// When the data is requested, the reader is rewound and its content is
+    /// transmitted in chunks without loading it into memory as a whole.
+    pub async fn from_reader<R>(mut reader: R) -> io::Result<Self>
+    where
+        R: AsyncRead + AsyncSeek + Send + Unpin + 'static,
+    {
+        let len = reader.seek(SeekFrom::End(0)).await?;
+        let (lazy_blob, provider) =
+            Self::provided_source(Source::Reader(Arc::new(Mutex::new(Box::new(reader)))), len);
+        provider.keep();
+        Ok(lazy_blob)
+    }
+
+    /// Create a new LazyBlob with the content of the specified file.
+    ///
+    /// The file is opened and its content is transmitted in chunks each time the data
+    /// is requested, without loading it into memory as a whole.
+    /// The file must not change its size while the LazyBlob exists.
+    #[cfg(feature = "fs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    pub async fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let len = tokio::fs::metadata(&path).await?.len();
+        let (lazy_blob, provider) = Self::provided_source(Source::File(Arc::new(path)), len);
+        provider.keep();
+        Ok(lazy_blob)
+    }
+
+    /// Create a new LazyBlob for the specified data source and return it together with
+    /// its provider.
+    fn provided_source(source: Source, len: u64) -> (Self, Provider) {
         let (keep_tx, keep_rx) = tokio::sync::oneshot::channel();
         let (req_tx, req_rx) = mpsc::channel(1);
         let req_tx = req_tx.set_buffer();
         let mut req_rx = req_rx.set_buffer::<1>();
-        let len = data.len() as _;
 
         exec::spawn(
             async move {
@@ -213,18 +439,11 @@ where
                             Err(_) => continue,
                         };
 
-                        let data = data.clone();
+                        let source = source.clone();
                         exec::spawn(
                             async move {
-                                match fw_tx.into_inner() {
-                                    Some(fw_bin::InnerSender::Local(tx)) => {
-                                        let _ = tx.send(data);
-                                    }
-                                    Some(fw_bin::InnerSender::Remote(bin_tx)) => {
-                                        let Ok(mut tx) = bin_tx.into_inner().await else { return };
-                                        let _ = tx.send(data).await;
-                                    }
-                                    None => return,
+                                if let Err(err) = source.send(fw_tx, len).await {
+                                    tracing::debug!(%err, "providing lazy blob data failed");
                                 }
                             }
                             .in_current_span(),
@@ -240,7 +459,7 @@ where
             .in_current_span(),
         );
 
-        let lazy_blob = LazyBlob { req_tx, len, fetch_task: Default::default() };
+        let lazy_blob = LazyBlob { req_tx, len, fetch_task: Default::default(), progress: Default::default() };
         let provider = Provider { keep_tx: Some(keep_tx) };
         (lazy_blob, provider)
     }
@@ -257,6 +476,16 @@ where
         usize::try_from(self.len).map_err(|_| UsizeExceeded(self.len))
     }
 
+    /// Watches the progress of fetching the binary data.
+    ///
+    /// The transfer starts when [get](Self::get) or [into_inner](Self::into_inner)
+    /// is first called.
+    /// Clones of this LazyBlob share the progress.
+    pub fn progress(&self) -> watch::Receiver<Progress> {
+        self.progress.set_total(self.len);
+        self.progress.subscribe()
+    }
+
     /// Fetches and caches the binary data from the provider.
     async fn fetch(&self) -> Result<(), FetchError> {
         let mut fetch_task = self.fetch_task.lock().await;
@@ -264,19 +493,16 @@ where
         if fetch_task.is_none() {
             let req_tx = self.req_tx.clone();
             let len = self.len()?;
+            let progress = self.progress.clone();
+            progress.set_total(self.len);
             *fetch_task = Some(Box::pin(future::maybe_done(
                 async move {
-                    let (fw_tx, fw_rx) = fw_bin::channel();
-                    let _ = req_tx.send(fw_tx).await;
-                    match fw_rx.into_inner().await {
-                        Some(fw_bin::InnerReceiver::Local(data)) => Ok(data.into()),
-                        Some(fw_bin::InnerReceiver::Remote(bin_rx)) => {
-                            let mut rx = bin_rx.into_inner().await.map_err(FetchError::RemoteConnect)?;
-                            rx.set_max_data_size(len);
-                            rx.recv().await.map_err(FetchError::RemoteReceive)?.ok_or(FetchError::Dropped)
-                        }
-                        None => Err(FetchError::Dropped),
+                    let mut fetch = ChunkedFetch::start(&req_tx, len, &progress).await?;
+                    let mut data = DataBuf::default();
+                    while let Some(chunk) = fetch.next().await? {
+                        data.push(chunk);
                     }
+                    Ok(data)
                 }
                 .boxed(),
             )));
@@ -298,6 +524,54 @@ where
         res.as_mut().unwrap().as_mut().output_mut().unwrap().clone()
     }
 
+    /// Fetches the binary data and writes it to the specified writer.
+    ///
+    /// The data is written chunk by chunk as it is received, without collecting
+    /// it in memory, and is not cached.
+    /// If the data has already been cached by a previous call to [get](Self::get),
+    /// the cached data is written.
+    ///
+    /// Returns the number of bytes written.
+    /// Errors fetching the data are returned as an [io::Error] of
+    /// kind [Other](io::ErrorKind::Other) containing the [FetchError].
+    pub async fn write_to<W>(&self, writer: &mut W) -> io::Result<u64>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let cached = {
+            let mut fetch_task = self.fetch_task.lock().await;
+            match fetch_task.as_mut().and_then(|task| task.as_mut().output_mut()) {
+                Some(Ok(data)) => Some(data.clone()),
+                _ => None,
+            }
+        };
+
+        let mut written = 0;
+        match cached {
+            Some(mut data) => {
+                while data.has_remaining() {
+                    let chunk = data.chunk();
+                    writer.write_all(chunk).await?;
+                    written += chunk.len() as u64;
+                    data.advance(chunk.len());
+                }
+            }
+            None => {
+                let len = self.len().map_err(io::Error::other)?;
+                self.progress.set_total(self.len);
+                let mut fetch =
+                    ChunkedFetch::start(&self.req_tx, len, &self.progress).await.map_err(io::Error::other)?;
+                while let Some(chunk) = fetch.next().await.map_err(io::Error::other)? {
+                    writer.write_all(&chunk).await?;
+                    written += chunk.len() as u64;
+                }
+            }
+        }
+
+        writer.flush().await?;
+        Ok(written)
+    }
+
     /// Returns the binary data.
     ///
     /// The binary data is fetched when not already cached by a previous
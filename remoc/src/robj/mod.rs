@@ -9,4 +9,5 @@
 pub mod handle;
 pub mod lazy;
 pub mod lazy_blob;
+pub mod remote_reader;
 pub mod rw_lock;
@@ -0,0 +1,409 @@
+//! Random-access reading of remote data.
+//!
+//! A [RemoteReader] wraps a local data source implementing [AsyncRead] and [AsyncSeek],
+//! for example a file, and can be sent to a remote endpoint.
+//! There it implements [AsyncRead] and [AsyncSeek] itself and fetches the requested
+//! ranges of data on demand from the source.
+//! Unlike a [lazy blob](super::lazy_blob), this does not transfer the whole data but
+//! only the parts that are actually read.
+//!
+//! Data is fetched in blocks of the [read-ahead size](RemoteReader::set_read_ahead).
+//! When reading sequentially, the following block is requested in advance to hide
+//! the round-trip time to the source.
+//! Seeking is performed locally and does not require communication with the source.
+//!
+//! Data is transmitted over [chmux](crate::chmux) binary channels without the overhead of a [codec].
+//! A [RemoteReader] can be forwarded over multiple remote endpoints.
+//!
+//! # Example
+//!
+//! In the following example the client sends a reader to the server, which
+//! reads only the last part of the data.
+//!
+//! ```
+//! use remoc::prelude::*;
+//! use remoc::robj::remote_reader::RemoteReader;
+//! use std::io::{Cursor, SeekFrom};
+//! use tokio::io::{AsyncReadExt, AsyncSeekExt};
+//!
+//! // This would be run on the client.
+//! async fn client(mut tx: rch::base::Sender<RemoteReader>) {
+//!     let data = Cursor::new(b"hello remote world".to_vec());
+//!     let reader = RemoteReader::new(data).await.unwrap();
+//!     tx.send(reader).await.unwrap();
+//! }
+//!
+//! // This would be run on the server.
+//! async fn server(mut rx: rch::base::Receiver<RemoteReader>) {
+//!     let mut reader = rx.recv().await.unwrap().unwrap();
+//!     assert_eq!(reader.len(), 18);
+//!
+//!     reader.seek(SeekFrom::End(-5)).await.unwrap();
+//!     let mut buf = String::new();
+//!     reader.read_to_string(&mut buf).await.unwrap();
+//!     assert_eq!(buf, "world");
+//! }
+//! # tokio_test::block_on(remoc::doctest::client_server(client, server));
+//! ```
+//!
+
+use bytes::{Buf, Bytes, BytesMut};
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    io::{self, ErrorKind, SeekFrom},
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, ReadBuf};
+use tracing::Instrument;
+
+use crate::{
+    codec, exec,
+    rch::{bin, mpsc},
+};
+
+/// Maximum size of a range that is served by the provider in one response.
+const MAX_RANGE: u64 = 16_777_216;
+
+/// Response header indicating that data follows.
+const RESPONSE_DATA: u8 = 0;
+
+/// Response header indicating that an error message follows.
+const RESPONSE_ERROR: u8 = 1;
+
+/// Request for a range of data.
+#[derive(Debug, Serialize, Deserialize)]
+struct ReadRequest {
+    /// Offset of the range.
+    offset: u64,
+    /// Length of the range.
+    len: u64,
+    /// Channel for sending the response.
+    reply: bin::Sender,
+}
+
+/// Holds the data source for a [RemoteReader].
+///
+/// Dropping the provider will stop making the data available for remote reading.
+pub struct Provider {
+    keep_tx: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+impl fmt::Debug for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Provider").finish()
+    }
+}
+
+impl Provider {
+    /// Keeps the provider alive until it is not required anymore.
+    pub fn keep(mut self) {
+        let _ = self.keep_tx.take().unwrap().send(());
+    }
+
+    /// Waits until the provider can be safely dropped.
+    ///
+    /// This is the case when all associated [RemoteReader]s have been dropped.
+    pub async fn done(&mut self) {
+        self.keep_tx.as_mut().unwrap().closed().await
+    }
+}
+
+impl Drop for Provider {
+    fn drop(&mut self) {
+        // empty
+    }
+}
+
+/// Range of data that is being fetched.
+///
+/// The fetch runs as a separate task, so that read-ahead requests are sent
+/// immediately and not only once the data is needed.
+struct Fetch {
+    offset: u64,
+    task: exec::task::JoinHandle<io::Result<Bytes>>,
+}
+
+impl Drop for Fetch {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Remote reader with random access. 📖🔍
+///
+/// Implements [AsyncRead] and [AsyncSeek] by fetching ranges of data on demand from
+/// the data source at the remote endpoint.
+///
+/// See [module-level documentation](self) for details.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "Codec: codec::Codec"))]
+#[serde(bound(deserialize = "Codec: codec::Codec"))]
+pub struct RemoteReader<Codec = codec::Default> {
+    req_tx: mpsc::Sender<ReadRequest, Codec>,
+    len: u64,
+    pos: u64,
+    read_ahead: u64,
+    /// Buffered data starting at `buf_offset`.
+    #[serde(skip)]
+    buf: Bytes,
+    #[serde(skip)]
+    buf_offset: u64,
+    /// Pending fetch of data.
+    #[serde(skip)]
+    fetch: Option<Fetch>,
+}
+
+impl<Codec> fmt::Debug for RemoteReader<Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RemoteReader").field("len", &self.len).field("pos", &self.pos).finish()
+    }
+}
+
+impl<Codec> RemoteReader<Codec>
+where
+    Codec: codec::Codec,
+{
+    /// Default read-ahead size.
+    pub const DEFAULT_READ_AHEAD: u64 = 262_144;
+
+    /// Creates a new remote reader for the specified data source.
+    ///
+    /// The length of the data is determined by seeking to the end of the source.
+    pub async fn new<R>(source: R) -> io::Result<Self>
+    where
+        R: AsyncRead + AsyncSeek + Send + Unpin + 'static,
+    {
+        let (reader, provider) = Self::provided(source).await?;
+        provider.keep();
+        Ok(reader)
+    }
+
+    /// Creates a new remote reader for the specified data source and returns it
+    /// together with its provider.
+    ///
+    /// The length of the data is determined by seeking to the end of the source.
+    pub async fn provided<R>(mut source: R) -> io::Result<(Self, Provider)>
+    where
+        R: AsyncRead + AsyncSeek + Send + Unpin + 'static,
+    {
+        let len = source.seek(SeekFrom::End(0)).await?;
+
+        let (keep_tx, keep_rx) = tokio::sync::oneshot::channel();
+        let (req_tx, mut req_rx) = mpsc::channel(1);
+
+        exec::spawn(
+            async move {
+                let serve = async move {
+                    loop {
+                        let req: ReadRequest = match req_rx.recv().await {
+                            Ok(Some(req)) => req,
+                            Ok(None) => break,
+                            Err(err) if err.is_final() => break,
+                            Err(_) => continue,
+                        };
+
+                        let response = match read_range(&mut source, req.offset, req.len.min(MAX_RANGE)).await {
+                            Ok(data) => data,
+                            Err(err) => {
+                                tracing::debug!(%err, offset = req.offset, "reading from source failed");
+                                let mut response = BytesMut::new();
+                                response.extend_from_slice(&[RESPONSE_ERROR]);
+                                response.extend_from_slice(err.to_string().as_bytes());
+                                response.freeze()
+                            }
+                        };
+
+                        exec::spawn(
+                            async move {
+                                let Ok(mut tx) = req.reply.into_inner().await else { return };
+                                let _ = tx.send(response).await;
+                            }
+                            .in_current_span(),
+                        );
+                    }
+                };
+
+                tokio::select! {
+                    () = serve => (),
+                    Err(_) = keep_rx => (),
+                }
+            }
+            .in_current_span(),
+        );
+
+        let reader = Self {
+            req_tx,
+            len,
+            pos: 0,
+            read_ahead: Self::DEFAULT_READ_AHEAD,
+            buf: Bytes::new(),
+            buf_offset: 0,
+            fetch: None,
+        };
+        let provider = Provider { keep_tx: Some(keep_tx) };
+        Ok((reader, provider))
+    }
+
+    /// Length of the data in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns true if the data has zero length.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Current read position.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Size of the blocks that are fetched from the source.
+    pub fn read_ahead(&self) -> u64 {
+        self.read_ahead
+    }
+
+    /// Sets the size of the blocks that are fetched from the source.
+    ///
+    /// Larger blocks reduce the number of round trips for sequential reading, while
+    /// smaller blocks avoid transferring unneeded data for scattered reads.
+    /// The size is limited to 16 MiB.
+    ///
+    /// # Panics
+    /// Panics if the size is zero.
+    pub fn set_read_ahead(&mut self, read_ahead: u64) {
+        assert!(read_ahead > 0, "read-ahead size must not be zero");
+        self.read_ahead = read_ahead.min(MAX_RANGE);
+    }
+
+    /// Starts fetching a block of data at the specified offset.
+    fn start_fetch(&mut self, offset: u64) {
+        let len = self.read_ahead.min(self.len - offset);
+        let task = exec::spawn(fetch(self.req_tx.clone(), offset, len).in_current_span());
+        self.fetch = Some(Fetch { offset, task });
+    }
+
+    /// Starts fetching the following block, if the buffer is running low.
+    fn maybe_read_ahead(&mut self) {
+        let buf_end = self.buf_offset + self.buf.len() as u64;
+        if self.fetch.is_none() && buf_end < self.len && buf_end - self.pos <= self.read_ahead / 2 {
+            self.start_fetch(buf_end);
+        }
+    }
+}
+
+/// Reads the specified range from the source and returns it as a data response.
+async fn read_range<R>(source: &mut R, offset: u64, len: u64) -> io::Result<Bytes>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    source.seek(SeekFrom::Start(offset)).await?;
+
+    let mut data = BytesMut::with_capacity(len as usize + 1);
+    data.extend_from_slice(&[RESPONSE_DATA]);
+    let mut reader = source.take(len);
+    while reader.read_buf(&mut data).await? > 0 {}
+
+    Ok(data.freeze())
+}
+
+/// Fetches the specified range from the provider.
+async fn fetch<Codec>(req_tx: mpsc::Sender<ReadRequest, Codec>, offset: u64, len: u64) -> io::Result<Bytes>
+where
+    Codec: codec::Codec,
+{
+    let (reply, reply_rx) = bin::channel();
+    req_tx
+        .send(ReadRequest { offset, len, reply })
+        .await
+        .map_err(|err| io::Error::new(ErrorKind::BrokenPipe, format!("provider unavailable: {err}")))?;
+
+    let mut rx = reply_rx.into_inner().await.map_err(|err| io::Error::new(ErrorKind::ConnectionRefused, err))?;
+    rx.set_max_data_size(len as usize + 1);
+    let mut response = match rx.recv().await {
+        Ok(Some(response)) => response,
+        Ok(None) => return Err(io::Error::new(ErrorKind::BrokenPipe, "provider dropped")),
+        Err(err) => return Err(io::Error::new(ErrorKind::ConnectionReset, err)),
+    };
+
+    if !response.has_remaining() {
+        return Err(io::Error::new(ErrorKind::InvalidData, "empty response"));
+    }
+    match response.get_u8() {
+        RESPONSE_DATA => Ok(Bytes::from(response)),
+        _ => Err(io::Error::other(format!(
+            "reading from remote source failed: {}",
+            String::from_utf8_lossy(&Vec::from(response))
+        ))),
+    }
+}
+
+impl<Codec> AsyncRead for RemoteReader<Codec>
+where
+    Codec: codec::Codec,
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.pos >= this.len || buf.remaining() == 0 {
+                return Poll::Ready(Ok(()));
+            }
+
+            // Serve from buffer.
+            if this.pos >= this.buf_offset && this.pos < this.buf_offset + this.buf.len() as u64 {
+                let start = (this.pos - this.buf_offset) as usize;
+                let n = (this.buf.len() - start).min(buf.remaining());
+                buf.put_slice(&this.buf[start..start + n]);
+                this.pos += n as u64;
+                this.maybe_read_ahead();
+                return Poll::Ready(Ok(()));
+            }
+
+            // Fetch block containing the current position.
+            match &this.fetch {
+                Some(fetch) if fetch.offset == this.pos => (),
+                _ => this.start_fetch(this.pos),
+            }
+            let fetch = this.fetch.as_mut().unwrap();
+            let res = ready!(fetch.task.poll_unpin(cx));
+            this.fetch = None;
+
+            let data = res.map_err(|err| io::Error::other(format!("fetch task failed: {err}")))??;
+            if data.is_empty() {
+                // Source is shorter than announced.
+                return Poll::Ready(Ok(()));
+            }
+            this.buf_offset = this.pos;
+            this.buf = data;
+        }
+    }
+}
+
+impl<Codec> AsyncSeek for RemoteReader<Codec>
+where
+    Codec: codec::Codec,
+{
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        let this = self.get_mut();
+
+        let pos = match position {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => this.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => this.pos.checked_add_signed(offset),
+        };
+        this.pos = pos.ok_or_else(|| {
+            io::Error::new(ErrorKind::InvalidInput, "invalid seek to a negative or overflowing position")
+        })?;
+
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Poll::Ready(Ok(self.pos))
+    }
+}
@@ -306,8 +306,12 @@ use crate::{
 ///
 pub use remoc_macro::remote;
 
+mod reconnect;
+pub use reconnect::{ReconnectCfg, ReconnectingClient};
+
 /// Call a method on a remotable trait failed.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum CallError {
     /// Processing request failed.
     ///
@@ -324,6 +328,11 @@ pub enum CallError {
     RemoteListen(chmux::ListenerError),
     /// Forwarding at a remote endpoint to another remote endpoint failed.
     RemoteForward,
+    /// The connection was lost while the call was in progress.
+    ///
+    /// The server may or may not have executed the call.
+    /// This is returned by [ReconnectingClient::call].
+    Interrupted,
 }
 
 impl fmt::Display for CallError {
@@ -335,6 +344,7 @@ impl fmt::Display for CallError {
             Self::RemoteConnect(err) => write!(f, "connect error: {err}"),
             Self::RemoteListen(err) => write!(f, "listen error: {err}"),
             Self::RemoteForward => write!(f, "forwarding error"),
+            Self::Interrupted => write!(f, "connection lost during call"),
         }
     }
 }
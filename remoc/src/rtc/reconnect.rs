@@ -0,0 +1,253 @@
+//! Automatically reconnecting client.
+
+use futures::Future;
+use std::{fmt, time::Duration};
+use tokio::sync::watch;
+
+use super::{CallError, Client};
+use crate::exec;
+
+/// Configuration of a [reconnecting client](ReconnectingClient).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReconnectCfg {
+    /// Delay before the first reconnection attempt after the connection has been lost
+    /// or establishing it has failed.
+    ///
+    /// By default this is 100 milliseconds.
+    pub initial_backoff: Duration,
+    /// Maximum delay between two reconnection attempts.
+    ///
+    /// By default this is 30 seconds.
+    pub max_backoff: Duration,
+    /// Factor by which the delay is multiplied after each failed attempt.
+    ///
+    /// By default this is 2.
+    pub backoff_factor: u32,
+    /// Maximum number of consecutive failed connection attempts before giving up.
+    ///
+    /// Once the reconnecting client has given up, all calls fail with [CallError::Dropped].
+    /// If `None`, reconnection is attempted indefinitely.
+    ///
+    /// By default this is `None`.
+    pub max_attempts: Option<u32>,
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl Default for ReconnectCfg {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+            backoff_factor: 2,
+            max_attempts: None,
+            _non_exhaustive: (),
+        }
+    }
+}
+
+/// Connection state shared between the reconnection task and the handles.
+enum State<C> {
+    /// A connection is being established.
+    Connecting,
+    /// Connected using the contained client.
+    Connected(C),
+    /// Reconnecting has been given up.
+    Failed,
+}
+
+/// A client of a remotable trait that automatically reconnects when the connection is lost.
+///
+/// A fresh client is obtained from the connect factory passed to [new](Self::new)
+/// whenever the current client [has been closed](Client::closed).
+/// Failed connection attempts are retried using exponential backoff as specified
+/// in the [configuration](ReconnectCfg).
+///
+/// Calls are issued using [call](Self::call) or [call_idempotent](Self::call_idempotent).
+/// A call that is made while the client is disconnected waits until the connection has
+/// been reestablished and is then sent.
+/// A call whose request could not be transmitted because the connection was lost
+/// is reissued after reconnecting.
+/// Once the request has been transmitted, the call is in progress.
+/// If the connection is lost while a call is in progress, it is unknown whether the
+/// server has executed it.
+/// Thus [call](Self::call) fails with [CallError::Interrupted] in this case, while
+/// [call_idempotent](Self::call_idempotent) reissues the call after reconnecting.
+///
+/// The handle can be cloned and all clones share the same connection.
+/// Reconnecting stops when all handles have been dropped.
+pub struct ReconnectingClient<C> {
+    state: watch::Receiver<State<C>>,
+}
+
+impl<C> fmt::Debug for ReconnectingClient<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReconnectingClient").finish_non_exhaustive()
+    }
+}
+
+impl<C> Clone for ReconnectingClient<C> {
+    fn clone(&self) -> Self {
+        Self { state: self.state.clone() }
+    }
+}
+
+impl<C> ReconnectingClient<C>
+where
+    C: Client + Clone + Send + Sync + 'static,
+{
+    /// Creates a new reconnecting client.
+    ///
+    /// The `connect` factory is called to establish the initial connection and
+    /// every time the connection has been lost.
+    /// It must return a fresh client connected to the server.
+    ///
+    /// A task for establishing the connection is spawned.
+    pub fn new<F, Fut, E>(cfg: ReconnectCfg, mut connect: F) -> Self
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<C, E>> + Send,
+        E: fmt::Display + Send,
+    {
+        let (state_tx, state_rx) = watch::channel(State::Connecting);
+
+        exec::spawn(async move {
+            let mut backoff = cfg.initial_backoff;
+            let mut attempts = 0;
+
+            loop {
+                tracing::debug!(attempt = attempts + 1, "connecting client");
+                let res = tokio::select! {
+                    res = connect() => res,
+                    () = state_tx.closed() => break,
+                };
+
+                match res {
+                    Ok(client) => {
+                        tracing::debug!("client connected");
+                        backoff = cfg.initial_backoff;
+                        attempts = 0;
+
+                        let closed = client.closed();
+                        state_tx.send_replace(State::Connected(client));
+                        tokio::select! {
+                            () = closed => (),
+                            () = state_tx.closed() => break,
+                        }
+
+                        tracing::debug!("client connection lost");
+                        state_tx.send_replace(State::Connecting);
+                    }
+                    Err(err) => {
+                        tracing::debug!(%err, "connecting client failed");
+                        attempts += 1;
+                        if cfg.max_attempts.is_some_and(|max_attempts| attempts >= max_attempts) {
+                            tracing::warn!(%attempts, "giving up reconnecting client");
+                            state_tx.send_replace(State::Failed);
+                            break;
+                        }
+                    }
+                }
+
+                tokio::select! {
+                    () = exec::time::sleep(backoff) => (),
+                    () = state_tx.closed() => break,
+                }
+                backoff = backoff.saturating_mul(cfg.backoff_factor).min(cfg.max_backoff);
+            }
+        });
+
+        Self { state: state_rx }
+    }
+
+    /// Returns whether a connection is currently established.
+    pub fn is_connected(&self) -> bool {
+        matches!(&*self.state.borrow(), State::Connected(client) if !client.is_closed())
+    }
+
+    /// Waits until a connection is established and returns the client for it.
+    ///
+    /// The returned client is not reconnected when the connection is lost.
+    /// Fails with [CallError::Dropped] if reconnecting has been given up.
+    pub async fn client(&self) -> Result<C, CallError> {
+        let mut state = self.state.clone();
+        loop {
+            match &*state.borrow_and_update() {
+                State::Connected(client) if !client.is_closed() => return Ok(client.clone()),
+                State::Failed => return Err(CallError::Dropped),
+                _ => (),
+            }
+
+            if state.changed().await.is_err() {
+                return Err(CallError::Dropped);
+            }
+        }
+    }
+
+    /// Calls a method on the server.
+    ///
+    /// The closure `f` is invoked with a connected client and must perform the call.
+    /// If no connection is currently established, the call is delayed until it has
+    /// been reestablished.
+    ///
+    /// If the connection is lost before the request has been transmitted, that is the
+    /// client was already closed or the call failed with [CallError::RemoteConnect] or
+    /// [CallError::RemoteSend], `f` is invoked again once the connection has been reestablished.
+    /// If the connection is lost while the call is in progress, the call fails with
+    /// [CallError::Interrupted], since it is unknown whether the server has executed it.
+    /// Use [call_idempotent](Self::call_idempotent) for calls that can safely be repeated.
+    pub async fn call<F, Fut, T, E>(&self, mut f: F) -> Result<T, E>
+    where
+        F: FnMut(C) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<CallError> + TryInto<CallError>,
+    {
+        loop {
+            let client = self.client().await?;
+            let closed = client.clone();
+            if closed.is_closed() {
+                continue;
+            }
+
+            match f(client).await {
+                Ok(value) => return Ok(value),
+                Err(err) if closed.is_closed() => match err.try_into() {
+                    Ok(CallError::RemoteConnect(_) | CallError::RemoteSend(_)) => {
+                        tracing::debug!("connection lost before call was sent, reissuing");
+                    }
+                    _ => return Err(CallError::Interrupted.into()),
+                },
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Calls an idempotent method on the server.
+    ///
+    /// The closure `f` is invoked with a connected client and must perform the call.
+    /// If no connection is currently established, the call is delayed until it has
+    /// been reestablished.
+    ///
+    /// If the connection is lost while the call is in progress, `f` is invoked again
+    /// once the connection has been reestablished.
+    /// Thus the call may be executed more than once by the server.
+    pub async fn call_idempotent<F, Fut, T, E>(&self, mut f: F) -> Result<T, E>
+    where
+        F: FnMut(C) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<CallError>,
+    {
+        loop {
+            let client = self.client().await?;
+            let closed = client.clone();
+
+            match f(client).await {
+                Ok(value) => return Ok(value),
+                Err(_) if closed.is_closed() => {
+                    tracing::debug!("connection lost during idempotent call, reissuing");
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
@@ -0,0 +1,552 @@
+//! Server accepting multiple connections.
+//!
+//! A [Server] accepts connections from a [listener](Accept), such as a TCP or UNIX domain
+//! socket listener, establishes a Remoc connection over each of them using [Connect::io_buffered]
+//! and calls a handler function with the established [base channel](crate::rch::base).
+//! It limits the number of concurrent connections, aborts connections that do not complete the
+//! handshake in time and keeps track of all live connections together with their peer addresses
+//! and transfer statistics.
+//!
+//! Using the [ServerHandle] the server can be shut down gracefully.
+//! This stops accepting new connections, notifies all connection handlers via
+//! [Connection::shutdown_requested] and waits for the connections to terminate.
+//! Connections that are still open after the configured
+//! [shutdown timeout](ServerCfg::shutdown_timeout) are closed forcibly.
+//!
+//! # Example
+//!
+//! In the following example the server listens on TCP port 9872 and replies to each received
+//! number with its string representation.
+//!
+//! ```
+//! use std::net::Ipv4Addr;
+//! use tokio::net::{TcpListener, TcpStream};
+//! use remoc::{prelude::*, serve::{Server, ServerCfg}};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 9872)).await.unwrap();
+//!     let server = Server::new(remoc::Cfg::default(), ServerCfg::default(), listener);
+//!     let handle = server.handle();
+//!
+//!     let server_task = tokio::spawn(server.serve(
+//!         |mut tx: rch::base::Sender<String>, mut rx: rch::base::Receiver<u32>, conn| async move {
+//!             println!("connection from {:?}", conn.peer_addr());
+//!             while let Ok(Some(number)) = rx.recv().await {
+//!                 if tx.send(number.to_string()).await.is_err() {
+//!                     break;
+//!                 }
+//!             }
+//!         },
+//!     ));
+//!
+//!     // Connect a client.
+//!     let socket = TcpStream::connect((Ipv4Addr::LOCALHOST, 9872)).await.unwrap();
+//!     let (socket_rx, socket_tx) = socket.into_split();
+//!     let (conn, mut tx, mut rx): (_, rch::base::Sender<u32>, rch::base::Receiver<String>) =
+//!         remoc::Connect::io(remoc::Cfg::default(), socket_rx, socket_tx).await.unwrap();
+//!     tokio::spawn(conn);
+//!
+//!     tx.send(1).await.unwrap();
+//!     assert_eq!(rx.recv().await.unwrap(), Some("1".to_string()));
+//!     assert_eq!(handle.connections().len(), 1);
+//!
+//!     // Shut down server.
+//!     drop(tx);
+//!     drop(rx);
+//!     handle.shutdown();
+//!     server_task.await.unwrap();
+//! }
+//! ```
+
+use futures::{Future, future};
+use std::{
+    collections::HashMap,
+    fmt, io,
+    pin::Pin,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    task::{Context, Poll, ready},
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    sync::{Semaphore, mpsc, watch},
+};
+
+use crate::{
+    RemoteSend, codec,
+    connect::Connect,
+    exec,
+    exec::time::{sleep, timeout},
+    rch::base,
+};
+
+/// A listener that accepts incoming connections.
+///
+/// This is implemented for [TCP](tokio::net::TcpListener) and
+/// [UNIX domain socket](tokio::net::UnixListener) listeners.
+pub trait Accept: Send + 'static {
+    /// Type of an accepted connection.
+    type Io: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static;
+    /// Address of the remote peer.
+    type Addr: fmt::Debug + Clone + Send + Sync + 'static;
+
+    /// Accepts a new incoming connection.
+    fn accept(&mut self) -> impl Future<Output = io::Result<(Self::Io, Self::Addr)>> + Send;
+}
+
+impl Accept for tokio::net::TcpListener {
+    type Io = tokio::net::TcpStream;
+    type Addr = std::net::SocketAddr;
+
+    fn accept(&mut self) -> impl Future<Output = io::Result<(Self::Io, Self::Addr)>> + Send {
+        tokio::net::TcpListener::accept(self)
+    }
+}
+
+#[cfg(unix)]
+impl Accept for tokio::net::UnixListener {
+    type Io = tokio::net::UnixStream;
+    type Addr = tokio::net::unix::SocketAddr;
+
+    fn accept(&mut self) -> impl Future<Output = io::Result<(Self::Io, Self::Addr)>> + Send {
+        tokio::net::UnixListener::accept(self)
+    }
+}
+
+/// Server configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServerCfg {
+    /// Maximum number of concurrent connections.
+    ///
+    /// When this limit is reached, no further connections are accepted until
+    /// a connection terminates.
+    /// By default this is 1024.
+    pub max_connections: Option<usize>,
+    /// Maximum time for establishing the Remoc connection after a connection has been accepted.
+    ///
+    /// If the remote endpoint does not complete the handshake in time, the connection is closed.
+    /// By default this is 30 seconds.
+    pub handshake_timeout: Option<Duration>,
+    /// Time connections are given to terminate after a shutdown has been requested,
+    /// before they are closed forcibly.
+    ///
+    /// If [None], the server waits indefinitely for all connections to terminate.
+    /// By default this is 30 seconds.
+    pub shutdown_timeout: Option<Duration>,
+    /// Size of read and write buffers of each connection in bytes.
+    ///
+    /// By default this is 8 kB.
+    pub buffer: usize,
+    /// Time to wait before accepting again after the listener has failed to
+    /// accept a connection.
+    ///
+    /// By default this is 100 milliseconds.
+    pub accept_error_delay: Duration,
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl Default for ServerCfg {
+    fn default() -> Self {
+        Self {
+            max_connections: Some(1024),
+            handshake_timeout: Some(Duration::from_secs(30)),
+            shutdown_timeout: Some(Duration::from_secs(30)),
+            buffer: 8192,
+            accept_error_delay: Duration::from_millis(100),
+            _non_exhaustive: (),
+        }
+    }
+}
+
+/// Transfer statistics of a connection.
+#[derive(Debug, Default)]
+struct Stats {
+    received: AtomicU64,
+    sent: AtomicU64,
+}
+
+/// Information about a live connection.
+#[derive(Debug, Clone)]
+pub struct ConnectionInfo<Addr> {
+    /// Connection id assigned by the server.
+    pub id: u64,
+    /// Address of the remote peer.
+    pub peer_addr: Addr,
+    /// Time when the connection has been accepted.
+    pub accepted: Instant,
+    /// Whether the Remoc connection has been established.
+    pub established: bool,
+    /// Number of bytes received from the transport.
+    pub bytes_received: u64,
+    /// Number of bytes sent over the transport.
+    pub bytes_sent: u64,
+}
+
+/// Registry entry of a live connection.
+struct Entry<Addr> {
+    peer_addr: Addr,
+    accepted: Instant,
+    established: bool,
+    stats: Arc<Stats>,
+}
+
+impl<Addr: Clone> Entry<Addr> {
+    fn info(&self, id: u64) -> ConnectionInfo<Addr> {
+        ConnectionInfo {
+            id,
+            peer_addr: self.peer_addr.clone(),
+            accepted: self.accepted,
+            established: self.established,
+            bytes_received: self.stats.received.load(Ordering::Relaxed),
+            bytes_sent: self.stats.sent.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// State shared between server, handles and connections.
+struct Shared<Addr> {
+    connections: Mutex<HashMap<u64, Entry<Addr>>>,
+    shutdown_tx: watch::Sender<bool>,
+}
+
+/// Handle to a [Server].
+///
+/// It can be used to query the live connections and shut down the server.
+/// It can be cloned.
+pub struct ServerHandle<Addr> {
+    shared: Arc<Shared<Addr>>,
+}
+
+impl<Addr> Clone for ServerHandle<Addr> {
+    fn clone(&self) -> Self {
+        Self { shared: self.shared.clone() }
+    }
+}
+
+impl<Addr> fmt::Debug for ServerHandle<Addr> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ServerHandle").finish_non_exhaustive()
+    }
+}
+
+impl<Addr: Clone> ServerHandle<Addr> {
+    /// Returns information about all live connections.
+    pub fn connections(&self) -> Vec<ConnectionInfo<Addr>> {
+        let connections = self.shared.connections.lock().unwrap();
+        let mut infos: Vec<_> = connections.iter().map(|(id, entry)| entry.info(*id)).collect();
+        infos.sort_by_key(|info| info.id);
+        infos
+    }
+
+    /// Returns information about the live connection with the specified id.
+    pub fn connection(&self, id: u64) -> Option<ConnectionInfo<Addr>> {
+        self.shared.connections.lock().unwrap().get(&id).map(|entry| entry.info(id))
+    }
+
+    /// Returns the number of live connections.
+    pub fn connection_count(&self) -> usize {
+        self.shared.connections.lock().unwrap().len()
+    }
+
+    /// Requests graceful shutdown of the server.
+    ///
+    /// No more connections are accepted and all connection handlers are notified.
+    /// The [serve](Server::serve) future returns when all connections have terminated.
+    pub fn shutdown(&self) {
+        self.shared.shutdown_tx.send_replace(true);
+    }
+
+    /// Returns whether shutdown of the server has been requested.
+    pub fn is_shutdown(&self) -> bool {
+        *self.shared.shutdown_tx.borrow()
+    }
+}
+
+/// A connection accepted by the [Server], passed to the connection handler.
+pub struct Connection<Addr> {
+    id: u64,
+    peer_addr: Addr,
+    shared: Arc<Shared<Addr>>,
+}
+
+impl<Addr: fmt::Debug> fmt::Debug for Connection<Addr> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Connection").field("id", &self.id).field("peer_addr", &self.peer_addr).finish()
+    }
+}
+
+impl<Addr: Clone> Connection<Addr> {
+    /// Connection id assigned by the server.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Address of the remote peer.
+    pub fn peer_addr(&self) -> &Addr {
+        &self.peer_addr
+    }
+
+    /// Returns current information about this connection.
+    ///
+    /// Returns `None` if the connection has already been unregistered from the server.
+    pub fn info(&self) -> Option<ConnectionInfo<Addr>> {
+        self.shared.connections.lock().unwrap().get(&self.id).map(|conn| conn.info(self.id))
+    }
+
+    /// Returns a handle to the server.
+    pub fn server(&self) -> ServerHandle<Addr> {
+        ServerHandle { shared: self.shared.clone() }
+    }
+
+    /// Waits until shutdown of the server has been requested.
+    ///
+    /// The connection handler should then finish its work and return.
+    pub async fn shutdown_requested(&self) {
+        let mut shutdown_rx = self.shared.shutdown_tx.subscribe();
+        let _ = shutdown_rx.wait_for(|shutdown| *shutdown).await;
+    }
+}
+
+/// Accepts multiple connections and runs a handler for each of them.
+///
+/// See the [module-level documentation](self) for details.
+pub struct Server<L: Accept> {
+    cfg: crate::Cfg,
+    server_cfg: ServerCfg,
+    listener: L,
+    shared: Arc<Shared<L::Addr>>,
+}
+
+impl<L: Accept> fmt::Debug for Server<L> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Server").field("cfg", &self.cfg).field("server_cfg", &self.server_cfg).finish()
+    }
+}
+
+impl<L: Accept> Server<L> {
+    /// Creates a new server accepting connections from the specified listener.
+    ///
+    /// The chmux configuration `cfg` is used for all connections.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid or the maximum number of connections is zero.
+    pub fn new(cfg: crate::Cfg, server_cfg: ServerCfg, listener: L) -> Self {
+        if server_cfg.max_connections == Some(0) {
+            panic!("maximum connections must not be zero");
+        }
+
+        let (shutdown_tx, _) = watch::channel(false);
+        Self {
+            cfg,
+            server_cfg,
+            listener,
+            shared: Arc::new(Shared { connections: Mutex::new(HashMap::new()), shutdown_tx }),
+        }
+    }
+
+    /// Returns a handle to the server.
+    pub fn handle(&self) -> ServerHandle<L::Addr> {
+        ServerHandle { shared: self.shared.clone() }
+    }
+
+    /// Accepts connections and runs the `handler` for each of them.
+    ///
+    /// For each accepted connection a new task is spawned.
+    /// It establishes a Remoc connection and calls the handler with the base channel
+    /// and the [connection](Connection) information.
+    /// The connection is closed when the handler has returned and all channels have been
+    /// dropped by both endpoints.
+    ///
+    /// Errors from the listener are logged and accepting is retried after the
+    /// configured [delay](ServerCfg::accept_error_delay).
+    ///
+    /// This returns after [shutdown](ServerHandle::shutdown) has been requested and all connections
+    /// have terminated.
+    pub async fn serve<Tx, Rx, Codec, H, Fut>(mut self, handler: H)
+    where
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+        H: Fn(base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>, Connection<L::Addr>) -> Fut
+            + Send
+            + Sync
+            + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let limit = self.server_cfg.max_connections.map(|max| Arc::new(Semaphore::new(max)));
+        let mut shutdown_rx = self.shared.shutdown_tx.subscribe();
+        let (alive_tx, mut alive_rx) = mpsc::channel::<()>(1);
+        let mut next_id = 0;
+
+        while !*shutdown_rx.borrow_and_update() {
+            // Wait for connection slot.
+            let permit = match &limit {
+                Some(limit) => tokio::select! {
+                    permit = limit.clone().acquire_owned() => Some(permit.unwrap()),
+                    _ = shutdown_rx.changed() => continue,
+                },
+                None => None,
+            };
+
+            // Accept connection.
+            let (io, peer_addr) = tokio::select! {
+                res = self.listener.accept() => match res {
+                    Ok(accepted) => accepted,
+                    Err(err) => {
+                        tracing::warn!(%err, "accepting connection failed");
+                        tokio::select! {
+                            () = sleep(self.server_cfg.accept_error_delay) => (),
+                            _ = shutdown_rx.changed() => (),
+                        }
+                        continue;
+                    }
+                },
+                _ = shutdown_rx.changed() => continue,
+            };
+
+            let id = next_id;
+            next_id += 1;
+            tracing::debug!(id, ?peer_addr, "accepted connection");
+
+            let stats = Arc::new(Stats::default());
+            self.shared.connections.lock().unwrap().insert(
+                id,
+                Entry {
+                    peer_addr: peer_addr.clone(),
+                    accepted: Instant::now(),
+                    established: false,
+                    stats: stats.clone(),
+                },
+            );
+
+            let conn = Connection { id, peer_addr, shared: self.shared.clone() };
+            let task = run_connection(
+                self.cfg.clone(),
+                self.server_cfg.clone(),
+                io,
+                stats,
+                conn,
+                handler.clone(),
+                alive_tx.clone(),
+                permit,
+            );
+            exec::spawn(task);
+        }
+
+        // Wait for all connections to terminate.
+        tracing::debug!("server shutting down");
+        drop(alive_tx);
+        let _ = alive_rx.recv().await;
+    }
+}
+
+/// Establishes the Remoc connection and runs the handler.
+#[allow(clippy::too_many_arguments)]
+async fn run_connection<Io, Addr, Tx, Rx, Codec, H, Fut>(
+    cfg: crate::Cfg, server_cfg: ServerCfg, io: Io, stats: Arc<Stats>, conn: Connection<Addr>, handler: Arc<H>,
+    _alive_tx: mpsc::Sender<()>, _permit: Option<tokio::sync::OwnedSemaphorePermit>,
+) where
+    Io: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static,
+    Addr: fmt::Debug + Clone + Send + Sync + 'static,
+    Tx: RemoteSend,
+    Rx: RemoteSend,
+    Codec: codec::Codec,
+    H: Fn(base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>, Connection<Addr>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let id = conn.id;
+    let shared = conn.shared.clone();
+    let mut shutdown_rx = shared.shutdown_tx.subscribe();
+
+    let (read, write) = tokio::io::split(io);
+    let read = CountingIo { inner: read, stats: stats.clone() };
+    let write = CountingIo { inner: write, stats };
+
+    let work = async {
+        // Establish connection.
+        let connect = Connect::io_buffered(cfg, read, write, server_cfg.buffer);
+        let res = match server_cfg.handshake_timeout {
+            Some(dur) => match timeout(dur, connect).await {
+                Ok(res) => res,
+                Err(_) => {
+                    tracing::warn!(id, "connection handshake timed out");
+                    return;
+                }
+            },
+            None => connect.await,
+        };
+        let (connection, tx, rx) = match res {
+            Ok(established) => established,
+            Err(err) => {
+                tracing::warn!(id, %err, "establishing connection failed");
+                return;
+            }
+        };
+
+        if let Some(entry) = shared.connections.lock().unwrap().get_mut(&id) {
+            entry.established = true;
+        }
+
+        // Run handler and connection.
+        let connection = async {
+            if let Err(err) = connection.await {
+                tracing::debug!(id, %err, "connection failed");
+            }
+        };
+        future::join(connection, handler(tx, rx, conn)).await;
+    };
+
+    let forced_shutdown = async {
+        let _ = shutdown_rx.wait_for(|shutdown| *shutdown).await;
+        match server_cfg.shutdown_timeout {
+            Some(dur) => sleep(dur).await,
+            None => future::pending().await,
+        }
+    };
+
+    tokio::select! {
+        () = work => tracing::debug!(id, "connection terminated"),
+        () = forced_shutdown => tracing::debug!(id, "connection closed forcibly due to shutdown"),
+    }
+
+    shared.connections.lock().unwrap().remove(&id);
+}
+
+/// Counts bytes transferred through an IO object.
+struct CountingIo<T> {
+    inner: T,
+    stats: Arc<Stats>,
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for CountingIo<T> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context, buf: &mut ReadBuf) -> Poll<io::Result<()>> {
+        let this = Pin::into_inner(self);
+        let before = buf.filled().len();
+        ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        this.stats.received.fetch_add((buf.filled().len() - before) as u64, Ordering::Relaxed);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for CountingIo<T> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = Pin::into_inner(self);
+        let n = ready!(Pin::new(&mut this.inner).poll_write(cx, buf))?;
+        this.stats.sent.fetch_add(n as u64, Ordering::Relaxed);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut Pin::into_inner(self).inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut Pin::into_inner(self).inner).poll_shutdown(cx)
+    }
+}
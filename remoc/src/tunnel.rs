@@ -0,0 +1,516 @@
+//! TCP port forwarding over a Remoc connection.
+//!
+//! This provides tunnelling similar to `ssh -L`.
+//! A [LocalForward] listens on a local TCP port and, for each accepted socket, opens a
+//! bidirectional byte tunnel over a Remoc connection.
+//! On the remote endpoint a [Dialer] connects to the target address and forwards data
+//! between the tunnel and the target.
+//! The remote endpoint only dials targets contained in its [Allowlist].
+//!
+//! The [Connector] obtained from [dialer] is sent to the endpoint that should be able to
+//! open tunnels.
+//! Each tunnel is made of two [I/O channels](crate::rch::io), one for each direction.
+//! Per-tunnel byte counts are available from [ForwardHandle::tunnels] while the tunnel is
+//! open and are reported to the [close handler](LocalForward::on_close) when the tunnel
+//! has been closed.
+//!
+//! # Example
+//!
+//! In the following example the server provides access to an echo service listening on
+//! TCP port 9880, which the client makes available on local TCP port 9881.
+//!
+//! ```
+//! use remoc::{prelude::*, tunnel::{self, Allowlist, Connector, LocalForward}};
+//! use std::net::Ipv4Addr;
+//! use tokio::{
+//!     io::{AsyncReadExt, AsyncWriteExt},
+//!     net::{TcpListener, TcpStream},
+//! };
+//!
+//! // This would be run on the client.
+//! async fn client(mut rx: rch::base::Receiver<Connector>) {
+//!     let connector = rx.recv().await.unwrap().unwrap();
+//!     let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 9881)).await.unwrap();
+//!     let forward = LocalForward::new(listener, "127.0.0.1:9880", connector);
+//!     tokio::spawn(forward.run());
+//!
+//!     let mut socket = TcpStream::connect((Ipv4Addr::LOCALHOST, 9881)).await.unwrap();
+//!     socket.write_all(b"hello").await.unwrap();
+//!     let mut buf = [0; 5];
+//!     socket.read_exact(&mut buf).await.unwrap();
+//!     assert_eq!(&buf, b"hello");
+//! }
+//!
+//! // This would be run on the server.
+//! async fn server(mut tx: rch::base::Sender<Connector>) {
+//!     let echo = TcpListener::bind((Ipv4Addr::LOCALHOST, 9880)).await.unwrap();
+//!     tokio::spawn(async move {
+//!         let (mut socket, _) = echo.accept().await.unwrap();
+//!         let (mut rx, mut tx) = socket.split();
+//!         tokio::io::copy(&mut rx, &mut tx).await.unwrap();
+//!     });
+//!
+//!     let (dialer, connector) = tunnel::dialer(Allowlist::new().allow("127.0.0.1:9880"));
+//!     tokio::spawn(dialer.serve());
+//!     tx.send(connector).await.unwrap();
+//! }
+//! # tokio_test::block_on(remoc::doctest::client_server(server, client));
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt, io,
+    net::SocketAddr,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use tracing::Instrument;
+
+use crate::{
+    codec, exec,
+    rch::{self, mpsc, oneshot},
+};
+
+/// Size of the buffer used for copying data of a tunnel.
+const BUFFER: usize = 8192;
+
+/// Default time to wait before accepting again after accepting a connection has failed.
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(100);
+
+/// Targets a [Dialer] is allowed to connect to.
+///
+/// Targets are specified as `host:port` and must match the target requested by the
+/// remote endpoint exactly.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Allowlist {
+    targets: HashSet<String>,
+}
+
+impl Allowlist {
+    /// Creates an empty allowlist, denying all targets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows connecting to the specified target.
+    pub fn allow(mut self, target: impl Into<String>) -> Self {
+        self.targets.insert(target.into());
+        self
+    }
+
+    /// Returns whether connecting to the specified target is allowed.
+    pub fn is_allowed(&self, target: &str) -> bool {
+        self.targets.contains(target)
+    }
+}
+
+impl<T: Into<String>> FromIterator<T> for Allowlist {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self { targets: iter.into_iter().map(Into::into).collect() }
+    }
+}
+
+/// Opening a tunnel failed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TunnelError {
+    /// The target is not contained in the allowlist of the remote endpoint.
+    NotAllowed(String),
+    /// The remote endpoint failed to connect to the target.
+    Dial(String),
+    /// The remote endpoint has been dropped or the connection failed.
+    Closed,
+}
+
+impl fmt::Display for TunnelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotAllowed(target) => write!(f, "target {target} is not allowed"),
+            Self::Dial(err) => write!(f, "connecting to target failed: {err}"),
+            Self::Closed => write!(f, "remote endpoint closed"),
+        }
+    }
+}
+
+impl Error for TunnelError {}
+
+impl From<TunnelError> for io::Error {
+    fn from(err: TunnelError) -> Self {
+        let kind = match &err {
+            TunnelError::NotAllowed(_) => io::ErrorKind::PermissionDenied,
+            TunnelError::Dial(_) => io::ErrorKind::ConnectionRefused,
+            TunnelError::Closed => io::ErrorKind::BrokenPipe,
+        };
+        io::Error::new(kind, err)
+    }
+}
+
+/// Request to open a tunnel, sent from a [Connector] to a [Dialer].
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "Codec: codec::Codec"))]
+#[serde(bound(deserialize = "Codec: codec::Codec"))]
+struct TunnelRequest<Codec> {
+    /// Target address.
+    target: String,
+    /// Data from the target.
+    tx: rch::io::Sender<Codec>,
+    /// Data to the target.
+    rx: rch::io::Receiver<Codec>,
+    /// Result of connecting to the target.
+    result_tx: oneshot::Sender<Result<(), TunnelError>, Codec>,
+}
+
+/// Opens tunnels via a remote [Dialer].
+///
+/// This can be sent to a remote endpoint and cloned.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "Codec: codec::Codec"))]
+#[serde(bound(deserialize = "Codec: codec::Codec"))]
+pub struct Connector<Codec = codec::Default> {
+    req_tx: mpsc::Sender<TunnelRequest<Codec>, Codec>,
+}
+
+impl<Codec> Clone for Connector<Codec> {
+    fn clone(&self) -> Self {
+        Self { req_tx: self.req_tx.clone() }
+    }
+}
+
+impl<Codec> fmt::Debug for Connector<Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Connector").finish()
+    }
+}
+
+impl<Codec> Connector<Codec>
+where
+    Codec: codec::Codec,
+{
+    /// Opens a tunnel to the specified target, which is connected to by the remote endpoint.
+    ///
+    /// Returns a sender for data to the target and a receiver for data from the target.
+    /// Call [shutdown](tokio::io::AsyncWriteExt::shutdown) on the sender to close the
+    /// sending direction of the connection to the target.
+    pub async fn open(
+        &self, target: impl Into<String>,
+    ) -> Result<(rch::io::Sender<Codec>, rch::io::Receiver<Codec>), TunnelError> {
+        let (tx, remote_rx) = rch::io::channel();
+        let (remote_tx, rx) = rch::io::channel();
+        let (result_tx, result_rx) = oneshot::channel();
+
+        let req = TunnelRequest { target: target.into(), tx: remote_tx, rx: remote_rx, result_tx };
+        self.req_tx.send(req).await.map_err(|_| TunnelError::Closed)?;
+        result_rx.await.map_err(|_| TunnelError::Closed)??;
+
+        Ok((tx, rx))
+    }
+}
+
+/// Connects to targets on behalf of remote endpoints.
+///
+/// Obtain it from [dialer].
+pub struct Dialer<Codec = codec::Default> {
+    allowlist: Allowlist,
+    req_rx: mpsc::Receiver<TunnelRequest<Codec>, Codec>,
+}
+
+impl<Codec> fmt::Debug for Dialer<Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Dialer").field("allowlist", &self.allowlist).finish_non_exhaustive()
+    }
+}
+
+/// Creates a new dialer that connects to the targets in the allowlist.
+///
+/// The returned [Connector] is sent to the remote endpoint to allow it to open tunnels.
+pub fn dialer<Codec>(allowlist: Allowlist) -> (Dialer<Codec>, Connector<Codec>)
+where
+    Codec: codec::Codec,
+{
+    let (req_tx, req_rx) = mpsc::channel(1);
+    (Dialer { allowlist, req_rx }, Connector { req_tx })
+}
+
+impl<Codec> Dialer<Codec>
+where
+    Codec: codec::Codec,
+{
+    /// Processes tunnel requests until all connectors have been dropped.
+    ///
+    /// A task is spawned for each tunnel.
+    pub async fn serve(mut self) {
+        loop {
+            let TunnelRequest { target, tx, rx, result_tx } = match self.req_rx.recv().await {
+                Ok(Some(req)) => req,
+                Ok(None) => break,
+                Err(err) if err.is_final() => break,
+                Err(_) => continue,
+            };
+
+            if !self.allowlist.is_allowed(&target) {
+                tracing::warn!(%target, "tunnel target not allowed");
+                let _ = result_tx.send(Err(TunnelError::NotAllowed(target)));
+                continue;
+            }
+
+            exec::spawn(
+                async move {
+                    let socket = match TcpStream::connect(&target).await {
+                        Ok(socket) => socket,
+                        Err(err) => {
+                            tracing::debug!(%target, %err, "connecting to tunnel target failed");
+                            let _ = result_tx.send(Err(TunnelError::Dial(err.to_string())));
+                            return;
+                        }
+                    };
+                    if result_tx.send(Ok(())).is_err() {
+                        return;
+                    }
+
+                    let (socket_rx, socket_tx) = socket.into_split();
+                    let stats = Stats::default();
+                    if let Err(err) = pump(socket_rx, socket_tx, tx, rx, &stats).await {
+                        tracing::debug!(%target, %err, "tunnel failed");
+                    }
+                }
+                .in_current_span(),
+            );
+        }
+    }
+}
+
+/// Byte counts of a tunnel.
+#[derive(Debug, Default)]
+struct Stats {
+    sent: AtomicU64,
+    received: AtomicU64,
+}
+
+/// Copies data from reader to writer and counts the copied bytes.
+///
+/// The writer is shut down when the reader reaches end of file.
+async fn copy_counted(
+    mut reader: impl AsyncRead + Unpin, mut writer: impl AsyncWrite + Unpin, counter: &AtomicU64,
+) -> io::Result<()> {
+    let mut buf = vec![0; BUFFER];
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        writer.write_all(&buf[..n]).await?;
+        writer.flush().await?;
+        counter.fetch_add(n as u64, Ordering::Relaxed);
+    }
+    writer.shutdown().await
+}
+
+/// Forwards data between a socket and a tunnel in both directions.
+async fn pump<Codec: codec::Codec>(
+    socket_rx: impl AsyncRead + Unpin, socket_tx: impl AsyncWrite + Unpin, tunnel_tx: rch::io::Sender<Codec>,
+    tunnel_rx: rch::io::Receiver<Codec>, stats: &Stats,
+) -> io::Result<()> {
+    tokio::try_join!(
+        copy_counted(socket_rx, tunnel_tx, &stats.sent),
+        copy_counted(tunnel_rx, socket_tx, &stats.received),
+    )?;
+    Ok(())
+}
+
+/// Information about an open tunnel of a [LocalForward].
+#[derive(Debug, Clone)]
+pub struct TunnelInfo {
+    /// Tunnel id assigned by the forwarder.
+    pub id: u64,
+    /// Address of the local peer.
+    pub peer_addr: SocketAddr,
+    /// Target address on the remote endpoint.
+    pub target: String,
+    /// Time when the tunnel has been opened.
+    pub opened: Instant,
+    /// Number of bytes sent to the target.
+    pub bytes_sent: u64,
+    /// Number of bytes received from the target.
+    pub bytes_received: u64,
+}
+
+/// Registry entry of an open tunnel.
+struct Entry {
+    peer_addr: SocketAddr,
+    opened: Instant,
+    stats: Arc<Stats>,
+}
+
+impl Entry {
+    fn info(&self, id: u64, target: &str) -> TunnelInfo {
+        TunnelInfo {
+            id,
+            peer_addr: self.peer_addr,
+            target: target.to_string(),
+            opened: self.opened,
+            bytes_sent: self.stats.sent.load(Ordering::Relaxed),
+            bytes_received: self.stats.received.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// State shared between forwarder and handles.
+struct Shared {
+    target: String,
+    tunnels: Mutex<HashMap<u64, Entry>>,
+}
+
+/// Handle to a [LocalForward].
+///
+/// It can be used to query the open tunnels.
+/// It can be cloned.
+#[derive(Clone)]
+pub struct ForwardHandle {
+    shared: Arc<Shared>,
+}
+
+impl fmt::Debug for ForwardHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ForwardHandle").field("target", &self.shared.target).finish_non_exhaustive()
+    }
+}
+
+impl ForwardHandle {
+    /// Returns information about all open tunnels.
+    pub fn tunnels(&self) -> Vec<TunnelInfo> {
+        let tunnels = self.shared.tunnels.lock().unwrap();
+        let mut infos: Vec<_> = tunnels.iter().map(|(id, entry)| entry.info(*id, &self.shared.target)).collect();
+        infos.sort_by_key(|info| info.id);
+        infos
+    }
+}
+
+/// Handler called with the final information of a closed tunnel.
+type CloseFn = dyn Fn(&TunnelInfo) + Send + Sync;
+
+/// Forwards connections accepted on a local TCP port to a target dialed by the
+/// remote endpoint.
+///
+/// See the [module-level documentation](self) for details.
+pub struct LocalForward<Codec = codec::Default> {
+    listener: TcpListener,
+    connector: Connector<Codec>,
+    shared: Arc<Shared>,
+    on_close: Option<Arc<CloseFn>>,
+    accept_error_delay: Duration,
+}
+
+impl<Codec> fmt::Debug for LocalForward<Codec> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LocalForward").field("target", &self.shared.target).finish_non_exhaustive()
+    }
+}
+
+impl<Codec> LocalForward<Codec>
+where
+    Codec: codec::Codec,
+{
+    /// Creates a new forwarder accepting connections from the specified listener and
+    /// tunnelling them to the target using the connector.
+    pub fn new(listener: TcpListener, target: impl Into<String>, connector: Connector<Codec>) -> Self {
+        Self {
+            listener,
+            connector,
+            shared: Arc::new(Shared { target: target.into(), tunnels: Mutex::new(HashMap::new()) }),
+            on_close: None,
+            accept_error_delay: ACCEPT_ERROR_DELAY,
+        }
+    }
+
+    /// Sets the time to wait before accepting again after the listener has failed to
+    /// accept a connection.
+    ///
+    /// By default this is 100 milliseconds.
+    pub fn accept_error_delay(mut self, delay: Duration) -> Self {
+        self.accept_error_delay = delay;
+        self
+    }
+
+    /// Sets a handler that is called with the final byte counts when a tunnel has been closed.
+    pub fn on_close(mut self, handler: impl Fn(&TunnelInfo) + Send + Sync + 'static) -> Self {
+        self.on_close = Some(Arc::new(handler));
+        self
+    }
+
+    /// Returns a handle to the forwarder.
+    pub fn handle(&self) -> ForwardHandle {
+        ForwardHandle { shared: self.shared.clone() }
+    }
+
+    /// Accepts connections and opens a tunnel for each of them.
+    ///
+    /// A task is spawned for each accepted connection.
+    /// If opening the tunnel fails, the accepted connection is closed.
+    ///
+    /// Errors from the listener are logged and accepting is retried after the
+    /// [accept error delay](Self::accept_error_delay).
+    /// This runs until the returned future is dropped.
+    pub async fn run(self) {
+        let mut next_id = 0;
+
+        loop {
+            let (socket, peer_addr) = match self.listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    tracing::warn!(%err, "accepting connection failed");
+                    exec::time::sleep(self.accept_error_delay).await;
+                    continue;
+                }
+            };
+            let id = next_id;
+            next_id += 1;
+
+            let connector = self.connector.clone();
+            let shared = self.shared.clone();
+            let on_close = self.on_close.clone();
+            exec::spawn(
+                async move {
+                    let (tunnel_tx, tunnel_rx) = match connector.open(shared.target.clone()).await {
+                        Ok(tunnel) => tunnel,
+                        Err(err) => {
+                            tracing::warn!(id, ?peer_addr, %err, "opening tunnel failed");
+                            return;
+                        }
+                    };
+
+                    let stats = Arc::new(Stats::default());
+                    let entry = Entry { peer_addr, opened: Instant::now(), stats: stats.clone() };
+                    shared.tunnels.lock().unwrap().insert(id, entry);
+                    tracing::debug!(id, ?peer_addr, target = %shared.target, "tunnel opened");
+
+                    let (socket_rx, socket_tx) = socket.into_split();
+                    if let Err(err) = pump(socket_rx, socket_tx, tunnel_tx, tunnel_rx, &stats).await {
+                        tracing::debug!(id, %err, "tunnel failed");
+                    }
+
+                    let entry = shared.tunnels.lock().unwrap().remove(&id).unwrap();
+                    let info = entry.info(id, &shared.target);
+                    tracing::info!(
+                        id,
+                        ?peer_addr,
+                        bytes_sent = info.bytes_sent,
+                        bytes_received = info.bytes_received,
+                        "tunnel closed"
+                    );
+                    if let Some(on_close) = on_close {
+                        on_close(&info);
+                    }
+                }
+                .in_current_span(),
+            );
+        }
+    }
+}
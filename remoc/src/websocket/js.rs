@@ -0,0 +1,241 @@
+//! WebSocket transport using the JavaScript WebSocket API.
+
+use bytes::Bytes;
+use futures::{
+    Sink, SinkExt, Stream, StreamExt,
+    channel::{mpsc, oneshot},
+};
+use js_sys::{ArrayBuffer, Uint8Array};
+use std::{
+    fmt, io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use wasm_bindgen::{JsCast, JsValue, prelude::Closure};
+use web_sys::{BinaryType, CloseEvent, Event, MessageEvent, WebSocket};
+
+use crate::{
+    RemoteSend, codec,
+    connect::{Connect, ConnectError},
+    rch::base,
+};
+
+/// Converts a JavaScript error into an IO error.
+fn js_error(value: JsValue) -> io::Error {
+    io::Error::other(format!("WebSocket error: {value:?}"))
+}
+
+/// Event from the JavaScript WebSocket.
+enum WsEvent {
+    /// Connection has been opened.
+    Open,
+    /// Binary message has been received.
+    Binary(Bytes),
+    /// Text message has been received.
+    Text,
+    /// An error occurred.
+    Error,
+    /// Connection has been closed.
+    Close,
+}
+
+/// Connects to a WebSocket server using the JavaScript WebSocket API and
+/// returns a sink and stream of chmux frames.
+///
+/// The returned halves can be passed to [Connect::framed].
+/// The WebSocket is owned by a task that is spawned onto the JavaScript event loop
+/// of the current thread.
+pub async fn connect(url: &str) -> Result<(WsSink, WsStream), io::Error> {
+    let (open_tx, open_rx) = oneshot::channel();
+    let (in_tx, in_rx) = mpsc::unbounded();
+    let (out_tx, out_rx) = mpsc::channel(1);
+
+    wasm_bindgen_futures::spawn_local(run(url.to_string(), open_tx, in_tx, out_rx));
+
+    match open_rx.await {
+        Ok(Ok(())) => Ok((WsSink(out_tx), WsStream(in_rx))),
+        Ok(Err(err)) => Err(err),
+        Err(_) => Err(io::Error::new(io::ErrorKind::ConnectionAborted, "WebSocket task terminated")),
+    }
+}
+
+/// Runs the JavaScript WebSocket, forwarding messages between it and the channels.
+async fn run(
+    url: String, open_tx: oneshot::Sender<Result<(), io::Error>>,
+    in_tx: mpsc::UnboundedSender<Result<Bytes, io::Error>>, mut out_rx: mpsc::Receiver<Bytes>,
+) {
+    let ws = match WebSocket::new(&url) {
+        Ok(ws) => ws,
+        Err(err) => {
+            let _ = open_tx.send(Err(js_error(err)));
+            return;
+        }
+    };
+    ws.set_binary_type(BinaryType::Arraybuffer);
+
+    let (evt_tx, mut evt_rx) = mpsc::unbounded();
+    let on_open = {
+        let evt_tx = evt_tx.clone();
+        Closure::<dyn FnMut(Event)>::new(move |_| {
+            let _ = evt_tx.unbounded_send(WsEvent::Open);
+        })
+    };
+    let on_message = {
+        let evt_tx = evt_tx.clone();
+        Closure::<dyn FnMut(MessageEvent)>::new(move |evt: MessageEvent| {
+            let evt = match evt.data().dyn_into::<ArrayBuffer>() {
+                Ok(buf) => WsEvent::Binary(Uint8Array::new(&buf).to_vec().into()),
+                Err(_) => WsEvent::Text,
+            };
+            let _ = evt_tx.unbounded_send(evt);
+        })
+    };
+    let on_error = {
+        let evt_tx = evt_tx.clone();
+        Closure::<dyn FnMut(Event)>::new(move |_| {
+            let _ = evt_tx.unbounded_send(WsEvent::Error);
+        })
+    };
+    let on_close = Closure::<dyn FnMut(CloseEvent)>::new(move |_| {
+        let _ = evt_tx.unbounded_send(WsEvent::Close);
+    });
+    ws.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+    ws.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    ws.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+    ws.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+    // Wait for connection to be established.
+    match evt_rx.next().await {
+        Some(WsEvent::Open) => {
+            if open_tx.send(Ok(())).is_err() {
+                evt_rx.close();
+            }
+        }
+        _ => {
+            let _ = open_tx
+                .send(Err(io::Error::new(io::ErrorKind::ConnectionRefused, "WebSocket connection failed")));
+            evt_rx.close();
+        }
+    }
+
+    // Forward messages.
+    loop {
+        tokio::select! {
+            evt = evt_rx.next() => match evt {
+                Some(WsEvent::Binary(data)) => {
+                    if in_tx.unbounded_send(Ok(data)).is_err() {
+                        break;
+                    }
+                }
+                Some(WsEvent::Text) => {
+                    let _ = in_tx.unbounded_send(Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "received text message over WebSocket transport",
+                    )));
+                    break;
+                }
+                Some(WsEvent::Error) => {
+                    let _ = in_tx.unbounded_send(Err(io::Error::new(io::ErrorKind::ConnectionReset, "WebSocket error")));
+                    break;
+                }
+                Some(WsEvent::Open) => (),
+                Some(WsEvent::Close) | None => break,
+            },
+            data = out_rx.next() => match data {
+                Some(data) => {
+                    if let Err(err) = ws.send_with_u8_array(&data) {
+                        let _ = in_tx.unbounded_send(Err(js_error(err)));
+                        break;
+                    }
+                }
+                None => break,
+            },
+        }
+    }
+
+    ws.set_onopen(None);
+    ws.set_onmessage(None);
+    ws.set_onerror(None);
+    ws.set_onclose(None);
+    let _ = ws.close();
+}
+
+/// Sending half of a WebSocket transport.
+///
+/// Each sent frame is transmitted as a binary WebSocket message.
+pub struct WsSink(mpsc::Sender<Bytes>);
+
+impl fmt::Debug for WsSink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("WsSink").finish()
+    }
+}
+
+impl Sink<Bytes> for WsSink {
+    type Error = io::Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready_unpin(cx).map_err(|_| io::ErrorKind::ConnectionReset.into())
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Bytes) -> Result<(), Self::Error> {
+        self.0.start_send_unpin(item).map_err(|_| io::ErrorKind::ConnectionReset.into())
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_flush_unpin(cx).map_err(|_| io::ErrorKind::ConnectionReset.into())
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_close_unpin(cx).map_err(|_| io::ErrorKind::ConnectionReset.into())
+    }
+}
+
+/// Receiving half of a WebSocket transport.
+///
+/// Each received binary WebSocket message is a frame.
+/// The stream ends when the WebSocket is closed.
+pub struct WsStream(mpsc::UnboundedReceiver<Result<Bytes, io::Error>>);
+
+impl fmt::Debug for WsStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("WsStream").finish()
+    }
+}
+
+impl Stream for WsStream {
+    type Item = Result<Bytes, io::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.0.poll_next_unpin(cx)
+    }
+}
+
+impl Connect<'static, io::Error, io::Error> {
+    /// Connects to a WebSocket server at the specified URL and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// This uses the JavaScript WebSocket API.
+    /// A [chmux](crate::chmux) connection is established over the WebSocket and a remote channel is opened.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "websocket", feature = "js"))))]
+    pub async fn websocket_url<Tx, Rx, Codec>(
+        cfg: crate::Cfg, url: &str,
+    ) -> Result<
+        (Connect<'static, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (sink, stream) =
+            connect(url).await.map_err(|err| ConnectError::ChMux(crate::chmux::ChMuxError::StreamError(err)))?;
+        Self::framed(cfg, sink, stream).await
+    }
+}
@@ -0,0 +1,68 @@
+//! WebSocket transport.
+//!
+//! This adapts a WebSocket connection into the [sink](futures::Sink) and [stream](futures::Stream)
+//! of binary data expected by [Connect::framed](crate::Connect::framed).
+//! Each [chmux](crate::chmux) frame is transmitted as a binary WebSocket message.
+//! A received close frame ends the stream, which is reported by the multiplexer as
+//! [ChMuxError::StreamClosed](crate::chmux::ChMuxError::StreamClosed).
+//! Ping and pong messages are handled transparently.
+//! Receiving a text message is treated as an error, since it cannot belong to a Remoc connection.
+//!
+//! On native platforms a [tokio-tungstenite](tokio_tungstenite) WebSocket stream is adapted
+//! using [split] or [Connect::websocket](crate::Connect::websocket).
+//! This works for both the client and the server side of a WebSocket connection and
+//! thus a Remoc server is reachable by browsers and through reverse proxies.
+//!
+//! When the `js` feature is enabled, the browser's WebSocket API is used to connect to
+//! a WebSocket server using `connect` or `Connect::websocket_url`.
+//!
+//! All transport errors are reported as [std::io::Error].
+//!
+//! # Example
+//!
+//! In the following example the server accepts a WebSocket connection on TCP port 9871
+//! and the client connects to it.
+//! Then both ends establish a Remoc connection over the WebSocket.
+//!
+//! ```
+//! use std::net::Ipv4Addr;
+//! use tokio::net::{TcpListener, TcpStream};
+//! use remoc::{prelude::*, websocket::tokio_tungstenite};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 9871)).await.unwrap();
+//!
+//!     let server = async move {
+//!         let (socket, _) = listener.accept().await.unwrap();
+//!         let ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+//!         let (conn, _tx, mut rx): (_, rch::base::Sender<()>, rch::base::Receiver<String>) =
+//!             remoc::Connect::websocket(remoc::Cfg::default(), ws).await.unwrap();
+//!         tokio::spawn(conn);
+//!         assert_eq!(rx.recv().await.unwrap(), Some("Hello".to_string()));
+//!     };
+//!
+//!     let client = async move {
+//!         let socket = TcpStream::connect((Ipv4Addr::LOCALHOST, 9871)).await.unwrap();
+//!         let (ws, _) = tokio_tungstenite::client_async("ws://localhost/", socket).await.unwrap();
+//!         let (conn, mut tx, _rx): (_, rch::base::Sender<String>, rch::base::Receiver<()>) =
+//!             remoc::Connect::websocket(remoc::Cfg::default(), ws).await.unwrap();
+//!         tokio::spawn(conn);
+//!         tx.send("Hello".to_string()).await.unwrap();
+//!     };
+//!
+//!     tokio::join!(server, client);
+//! }
+//! ```
+
+#[cfg(not(target_family = "wasm"))]
+mod native;
+#[cfg(not(target_family = "wasm"))]
+pub use native::{WsSink, WsStream, split};
+#[cfg(not(target_family = "wasm"))]
+pub use tokio_tungstenite;
+
+#[cfg(all(feature = "js", target_family = "wasm"))]
+mod js;
+#[cfg(all(feature = "js", target_family = "wasm"))]
+pub use js::{WsSink, WsStream, connect};
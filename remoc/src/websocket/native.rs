@@ -0,0 +1,149 @@
+//! WebSocket transport using tokio-tungstenite.
+
+use bytes::Bytes;
+use futures::{
+    Sink, SinkExt, Stream, StreamExt,
+    stream::{SplitSink, SplitStream},
+};
+use std::{
+    fmt, io,
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_tungstenite::{
+    WebSocketStream,
+    tungstenite::{Error as WsError, Message},
+};
+
+use crate::{
+    RemoteSend, codec,
+    connect::{Connect, ConnectError},
+    rch::base,
+};
+
+/// Converts a WebSocket error into an IO error.
+fn to_io_error(err: WsError) -> io::Error {
+    match err {
+        WsError::Io(err) => err,
+        WsError::ConnectionClosed | WsError::AlreadyClosed => {
+            io::Error::new(io::ErrorKind::ConnectionReset, err.to_string())
+        }
+        err => io::Error::other(err),
+    }
+}
+
+/// Splits a WebSocket stream into a sink and stream of chmux frames.
+///
+/// The returned halves can be passed to [Connect::framed].
+pub fn split<S>(ws: WebSocketStream<S>) -> (WsSink<S>, WsStream<S>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (sink, stream) = ws.split();
+    (WsSink(sink), WsStream(stream))
+}
+
+/// Sending half of a WebSocket transport.
+///
+/// Each sent frame is transmitted as a binary WebSocket message.
+pub struct WsSink<S>(SplitSink<WebSocketStream<S>, Message>);
+
+impl<S> fmt::Debug for WsSink<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("WsSink").finish()
+    }
+}
+
+impl<S> Sink<Bytes> for WsSink<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    type Error = io::Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready_unpin(cx).map_err(to_io_error)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Bytes) -> Result<(), Self::Error> {
+        self.0.start_send_unpin(Message::Binary(item)).map_err(to_io_error)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_flush_unpin(cx).map_err(to_io_error)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_close_unpin(cx).map_err(to_io_error)
+    }
+}
+
+/// Receiving half of a WebSocket transport.
+///
+/// Each received binary WebSocket message is a frame.
+/// The stream ends when a close frame is received.
+pub struct WsStream<S>(SplitStream<WebSocketStream<S>>);
+
+impl<S> fmt::Debug for WsStream<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("WsStream").finish()
+    }
+}
+
+impl<S> Stream for WsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    type Item = Result<Bytes, io::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            let msg = match ready!(self.0.poll_next_unpin(cx)) {
+                Some(Ok(msg)) => msg,
+                Some(Err(WsError::ConnectionClosed | WsError::AlreadyClosed)) | None => return Poll::Ready(None),
+                Some(Err(err)) => return Poll::Ready(Some(Err(to_io_error(err)))),
+            };
+
+            match msg {
+                Message::Binary(data) => return Poll::Ready(Some(Ok(data))),
+                Message::Close(_) => return Poll::Ready(None),
+                Message::Text(_) => {
+                    return Poll::Ready(Some(Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "received text message over WebSocket transport",
+                    ))));
+                }
+                Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => (),
+            }
+        }
+    }
+}
+
+impl<'transport> Connect<'transport, io::Error, io::Error> {
+    /// Establishes a connection over a WebSocket and
+    /// returns a remote [sender](base::Sender) and [receiver](base::Receiver).
+    ///
+    /// The WebSocket can be either the client or the server side of the connection.
+    /// A [chmux](crate::chmux) connection is established over it and a remote channel is opened.
+    ///
+    /// You must poll the returned [Connect] future or spawn it for the connection to work.
+    ///
+    /// # Panics
+    /// Panics if the chmux configuration is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+    pub async fn websocket<S, Tx, Rx, Codec>(
+        cfg: crate::Cfg, ws: WebSocketStream<S>,
+    ) -> Result<
+        (Connect<'transport, io::Error, io::Error>, base::Sender<Tx, Codec>, base::Receiver<Rx, Codec>),
+        ConnectError<io::Error, io::Error>,
+    >
+    where
+        S: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'transport,
+        Tx: RemoteSend,
+        Rx: RemoteSend,
+        Codec: codec::Codec,
+    {
+        let (sink, stream) = split(ws);
+        Self::framed(cfg, sink, stream).await
+    }
+}
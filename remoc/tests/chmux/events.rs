@@ -0,0 +1,219 @@
+use futures::{StreamExt, channel::oneshot, future::try_join};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_transport;
+use remoc::{
+    chmux::{self, ChMuxError, Endpoint, Event, PortCloseReason},
+    exec::{self, time::sleep},
+};
+
+/// Waits for an event matching the predicate, skipping all others.
+async fn wait_for(events: &mut broadcast::Receiver<Event>, f: impl Fn(&Event) -> bool) -> Event {
+    loop {
+        let event = events.recv().await.unwrap();
+        println!("Event: {event:?}");
+        if f(&event) {
+            return event;
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn port_lifecycle() {
+    crate::init();
+
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let ((a_mux, a_client, a_server), (b_mux, b_client, mut b_server)) = try_join(
+        chmux::ChMux::new(chmux::Cfg::default(), a_tx, a_rx),
+        chmux::ChMux::new(chmux::Cfg::default(), b_tx, b_rx),
+    )
+    .await
+    .unwrap();
+
+    let mut a_events = a_mux.subscribe_events();
+    let mut b_events = b_mux.subscribe_events();
+    exec::spawn(async move { a_mux.run().await.unwrap() });
+    exec::spawn(async move { b_mux.run().await.unwrap() });
+
+    let (b_done_tx, b_done_rx) = oneshot::channel();
+    exec::spawn(async move {
+        let (tx, mut rx) = b_server.accept().await.unwrap().unwrap();
+        assert!(rx.recv().await.unwrap().is_none());
+        drop(tx);
+        drop(rx);
+        let _ = b_done_tx.send(b_server);
+    });
+
+    let (tx, rx) = a_client.connect().await.unwrap();
+    let local_port = tx.local_port();
+    let remote_port = tx.remote_port();
+
+    let event = wait_for(&mut a_events, |e| matches!(e, Event::PortOpened { .. })).await;
+    assert_eq!(event, Event::PortOpened { local_port, remote_port, initiator: Endpoint::Local });
+    let event = wait_for(&mut b_events, |e| matches!(e, Event::PortOpened { .. })).await;
+    assert_eq!(
+        event,
+        Event::PortOpened { local_port: remote_port, remote_port: local_port, initiator: Endpoint::Remote }
+    );
+
+    drop(tx);
+    drop(rx);
+
+    let event = wait_for(&mut a_events, |e| matches!(e, Event::PortClosed { .. })).await;
+    assert_eq!(
+        event,
+        Event::PortClosed { local_port, remote_port, reason: PortCloseReason::Closed(Endpoint::Local) }
+    );
+    let event = wait_for(&mut b_events, |e| matches!(e, Event::PortClosed { .. })).await;
+    assert_eq!(
+        event,
+        Event::PortClosed {
+            local_port: remote_port,
+            remote_port: local_port,
+            reason: PortCloseReason::Closed(Endpoint::Remote)
+        }
+    );
+
+    let _b_server = b_done_rx.await.unwrap();
+    drop(a_client);
+    wait_for(&mut b_events, |e| *e == Event::RemoteClientFinished).await;
+    drop(a_server);
+    wait_for(&mut b_events, |e| *e == Event::RemoteListenerFinished).await;
+
+    drop(b_client);
+    wait_for(&mut a_events, |e| *e == Event::Goodbye { initiator: Endpoint::Local }).await;
+    wait_for(&mut b_events, |e| *e == Event::Goodbye { initiator: Endpoint::Remote }).await;
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn credit_starvation() {
+    crate::init();
+
+    let cfg = chmux::Cfg { chunk_size: 16, receive_buffer: 4, ..Default::default() };
+
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let ((a_mux, a_client, _a_server), (b_mux, _b_client, mut b_server)) =
+        try_join(chmux::ChMux::new(cfg.clone(), a_tx, a_rx), chmux::ChMux::new(cfg, b_tx, b_rx)).await.unwrap();
+
+    let mut a_events = a_mux.subscribe_events();
+    exec::spawn(async move { a_mux.run().await.unwrap() });
+    exec::spawn(async move { b_mux.run().await.unwrap() });
+
+    // Accept the port but never receive from it.
+    let (accepted_tx, accepted_rx) = oneshot::channel();
+    exec::spawn(async move {
+        let port = b_server.accept().await.unwrap().unwrap();
+        let _ = accepted_tx.send(port);
+    });
+
+    let (mut tx, _rx) = a_client.connect().await.unwrap();
+    let _port = accepted_rx.await.unwrap();
+    let local_port = tx.local_port();
+    let remote_port = tx.remote_port();
+
+    exec::spawn(async move {
+        loop {
+            if tx.send(vec![0; 64].into()).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let event = wait_for(&mut a_events, |e| matches!(e, Event::CreditStarvation { .. })).await;
+    assert_eq!(event, Event::CreditStarvation { local_port, remote_port });
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn terminated() {
+    crate::init();
+
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let ((a_mux, a_client, _a_server), (b_mux, _b_client, mut b_server)) = try_join(
+        chmux::ChMux::new(chmux::Cfg::default(), a_tx, a_rx),
+        chmux::ChMux::new(chmux::Cfg::default(), b_tx, b_rx),
+    )
+    .await
+    .unwrap();
+
+    let mut a_events = a_mux.subscribe_events();
+    exec::spawn(async move {
+        let res = a_mux.run().await;
+        println!("A mux result: {res:?}");
+    });
+
+    // Remote endpoint stops after the port has been opened.
+    let (stop_tx, stop_rx) = oneshot::channel::<()>();
+    exec::spawn(async move {
+        tokio::select! {
+            res = b_mux.run() => res.unwrap(),
+            _ = stop_rx => (),
+        }
+    });
+    let (accepted_tx, accepted_rx) = oneshot::channel();
+    exec::spawn(async move {
+        let port = b_server.accept().await.unwrap().unwrap();
+        let _ = accepted_tx.send(port);
+    });
+
+    let (tx, _rx) = a_client.connect().await.unwrap();
+    let _port = accepted_rx.await.unwrap();
+    let local_port = tx.local_port();
+    let remote_port = tx.remote_port();
+
+    drop(stop_tx);
+
+    let event = wait_for(&mut a_events, |e| matches!(e, Event::PortClosed { .. })).await;
+    assert_eq!(event, Event::PortClosed { local_port, remote_port, reason: PortCloseReason::Terminated });
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn ping_timeout() {
+    crate::init();
+
+    let cfg = chmux::Cfg { connection_timeout: Some(Duration::from_millis(400)), ..Default::default() };
+
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let ((a_mux, _a_client, _a_server), (_b_mux, _b_client, _b_server)) =
+        try_join(chmux::ChMux::new(cfg.clone(), a_tx, a_rx), chmux::ChMux::new(cfg, b_tx, b_rx)).await.unwrap();
+
+    // Remote multiplexer is never run, thus it sends no pings.
+    let mut a_events = a_mux.subscribe_events();
+    let a_run = exec::spawn(async move { a_mux.run().await });
+
+    let event = wait_for(&mut a_events, |e| matches!(e, Event::PingTimeoutApproaching { .. })).await;
+    assert_eq!(event, Event::PingTimeoutApproaching { remaining: Duration::from_millis(100) });
+
+    let res = a_run.await.unwrap();
+    assert!(matches!(res, Err(ChMuxError::Timeout)));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn ping_timeout_idle() {
+    crate::init();
+
+    let cfg = chmux::Cfg { connection_timeout: Some(Duration::from_millis(400)), ..Default::default() };
+
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let ((a_mux, _a_client, _a_server), (b_mux, _b_client, _b_server)) =
+        try_join(chmux::ChMux::new(cfg.clone(), a_tx, a_rx), chmux::ChMux::new(cfg, b_tx, b_rx)).await.unwrap();
+
+    // Both multiplexers run, but no data is exchanged.
+    let mut a_events = a_mux.subscribe_events();
+    exec::spawn(async move { a_mux.run().await.unwrap() });
+    exec::spawn(async move { b_mux.run().await.unwrap() });
+
+    sleep(Duration::from_millis(1200)).await;
+    while let Ok(event) = a_events.try_recv() {
+        println!("Event: {event:?}");
+        assert!(!matches!(event, Event::PingTimeoutApproaching { .. }));
+    }
+}
@@ -0,0 +1,83 @@
+use futures::{StreamExt, future::join};
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_transport;
+use remoc::chmux::{self, AcceptMetadata, ChMuxError, Metadata};
+
+fn metadata() -> Metadata {
+    Metadata::new()
+        .with_app("remoc-test", "1.2.3")
+        .with_build_id("abcdef")
+        .with_locale("de-DE")
+        .with_header("x", "y")
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn exchange() {
+    crate::init();
+
+    let a_cfg = chmux::Cfg { metadata: metadata(), ..Default::default() };
+    let b_cfg = chmux::Cfg::default();
+
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let (a, b) = join(chmux::ChMux::new(a_cfg, a_tx, a_rx), chmux::ChMux::new(b_cfg, b_tx, b_rx)).await;
+    let (a_mux, _, _) = a.unwrap();
+    let (b_mux, _, _) = b.unwrap();
+
+    println!("A remote metadata: {:?}", a_mux.remote_metadata());
+    println!("B remote metadata: {:?}", b_mux.remote_metadata());
+    assert!(a_mux.remote_metadata().is_empty());
+    assert_eq!(b_mux.remote_metadata(), &metadata());
+    assert_eq!(b_mux.remote_metadata().app_name.as_deref(), Some("remoc-test"));
+    assert_eq!(b_mux.remote_metadata().header("x"), Some("y"));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn rejected() {
+    crate::init();
+
+    let a_cfg = chmux::Cfg { metadata: metadata(), ..Default::default() };
+    let b_cfg = chmux::Cfg {
+        accept_metadata: Some(AcceptMetadata::new(|remote| match remote.app_version.as_deref() {
+            Some("2.0.0") => Ok(()),
+            other => Err(format!("unsupported version {other:?}")),
+        })),
+        ..Default::default()
+    };
+
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let (a, b) = join(chmux::ChMux::new(a_cfg, a_tx, a_rx), chmux::ChMux::new(b_cfg, b_tx, b_rx)).await;
+
+    let a_err = a.unwrap_err();
+    let b_err = b.unwrap_err();
+    println!("A error: {a_err}");
+    println!("B error: {b_err}");
+    assert!(matches!(a_err, ChMuxError::Rejected(reason) if reason == r#"unsupported version Some("1.2.3")"#));
+    assert!(matches!(b_err, ChMuxError::Refused(reason) if reason == r#"unsupported version Some("1.2.3")"#));
+}
+
+#[cfg(feature = "rch")]
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn connect() {
+    crate::init();
+
+    let a_cfg = chmux::Cfg { metadata: metadata(), ..Default::default() };
+    let b_cfg = chmux::Cfg { metadata: Metadata::new().with_app("server", "1"), ..Default::default() };
+
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+    let (a, b) = join(
+        remoc::Connect::framed::<_, _, (), (), remoc::codec::Default>(a_cfg, a_tx, a_rx),
+        remoc::Connect::framed::<_, _, (), (), remoc::codec::Default>(b_cfg, b_tx, b_rx),
+    )
+    .await;
+    let (a_conn, _, _) = a.unwrap();
+    let (b_conn, _, _) = b.unwrap();
+
+    assert_eq!(a_conn.remote_metadata().app_name.as_deref(), Some("server"));
+    assert_eq!(b_conn.remote_metadata(), &metadata());
+}
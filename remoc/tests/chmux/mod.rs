@@ -1,4 +1,6 @@
 mod channel;
+mod events;
+mod metadata;
 
 #[cfg(not(target_family = "wasm"))]
 mod tcp;
@@ -0,0 +1,80 @@
+use futures::StreamExt;
+use std::time::Duration;
+use tokio::io::split;
+
+use crate::loop_transport;
+
+use remoc::{ConnectError, ConnectExt, ConsumeError, chmux, exec, rch::base};
+
+#[tokio::test]
+async fn handshake_timeout() {
+    crate::init();
+
+    let (a, _b) = tokio::io::duplex(4096);
+    let (a_rx, a_tx) = split(a);
+
+    let cfg = remoc::Cfg { handshake_timeout: Some(Duration::from_millis(200)), ..Default::default() };
+    let res = remoc::Connect::io::<_, _, (), (), remoc::codec::Default>(cfg, a_rx, a_tx).await;
+    assert!(matches!(res, Err(ConnectError::Timeout)));
+}
+
+#[tokio::test]
+async fn base_channel_timeout() {
+    crate::init();
+
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+
+    let cfg = remoc::Cfg { base_channel_timeout: Some(Duration::from_millis(200)), ..Default::default() };
+    let connect = remoc::Connect::framed::<_, _, (), (), remoc::codec::Default>(cfg, a_tx, a_rx);
+
+    // Remote endpoint completes the handshake but never runs its multiplexer.
+    let remote = chmux::ChMux::new(chmux::Cfg::default(), b_tx, b_rx);
+
+    let (res, remote) = tokio::join!(connect, remote);
+    let _remote = remote.unwrap();
+    assert!(matches!(res, Err(ConnectError::Timeout)));
+}
+
+#[tokio::test]
+async fn consume_timeout() {
+    crate::init();
+
+    let (a, b) = tokio::io::duplex(4096);
+    let (a_rx, a_tx) = split(a);
+    let (b_rx, b_tx) = split(b);
+
+    // Remote endpoint connects but never provides a value.
+    let remote = exec::spawn(async move {
+        let (conn, tx, rx): (_, base::Sender<u32>, base::Receiver<u32>) =
+            remoc::Connect::io(remoc::Cfg::default(), b_rx, b_tx).await.unwrap();
+        exec::spawn(conn);
+        (tx, rx)
+    });
+
+    let res: Result<u32, _> =
+        remoc::Connect::io(remoc::Cfg::default(), a_rx, a_tx).consume_timeout(Duration::from_millis(200)).await;
+    assert!(matches!(res, Err(ConsumeError::Timeout)));
+    drop(remote);
+}
+
+#[tokio::test]
+async fn provide_consume_timeout() {
+    crate::init();
+
+    let (a, b) = tokio::io::duplex(4096);
+    let (a_rx, a_tx) = split(a);
+    let (b_rx, b_tx) = split(b);
+
+    let provide =
+        remoc::Connect::io(remoc::Cfg::default(), a_rx, a_tx).provide_timeout(123u32, Duration::from_secs(10));
+    let consume = async {
+        let value: u32 = remoc::Connect::io(remoc::Cfg::default(), b_rx, b_tx)
+            .consume_timeout(Duration::from_secs(10))
+            .await?;
+        Ok::<_, ConsumeError<_, _>>(value)
+    };
+
+    let (provided, consumed) = tokio::join!(provide, consume);
+    provided.unwrap();
+    assert_eq!(consumed.unwrap(), 123);
+}
@@ -0,0 +1,54 @@
+use tokio::process::Command;
+
+use remoc::{ChildCfg, ConnectError, exec, rch::base};
+
+#[tokio::test]
+async fn child_loopback() {
+    crate::init();
+
+    // cat echoes everything back, thus we talk to ourselves.
+    let (conn, mut tx, mut rx, mut child): (_, base::Sender<u32>, base::Receiver<u32>, _) =
+        remoc::Connect::child(Default::default(), ChildCfg::default(), Command::new("cat")).await.unwrap();
+    exec::spawn(conn);
+
+    for i in 0..10 {
+        println!("Sending {i}");
+        tx.send(i).await.unwrap();
+        assert_eq!(rx.recv().await.unwrap(), Some(i));
+    }
+
+    child.kill().await.unwrap();
+}
+
+#[tokio::test]
+async fn child_exits() {
+    crate::init();
+
+    let mut command = Command::new("sh");
+    command.args(["-c", "exit 3"]);
+
+    match remoc::Connect::child::<(), (), remoc::codec::Default>(Default::default(), ChildCfg::default(), command)
+        .await
+    {
+        Err(ConnectError::ProcessExited(status)) => {
+            println!("Child exited with {status}");
+            assert_eq!(status.code(), Some(3));
+        }
+        Err(other) => panic!("unexpected error: {other}"),
+        Ok(_) => panic!("connection succeeded"),
+    }
+}
+
+#[tokio::test]
+async fn child_spawn_fails() {
+    crate::init();
+
+    let command = Command::new("/nonexistent/remoc-worker");
+    match remoc::Connect::child::<(), (), remoc::codec::Default>(Default::default(), ChildCfg::default(), command)
+        .await
+    {
+        Err(ConnectError::Spawn(err)) => println!("Spawn failed: {err}"),
+        Err(other) => panic!("unexpected error: {other}"),
+        Ok(_) => panic!("connection succeeded"),
+    }
+}
@@ -0,0 +1,129 @@
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_channel;
+use remoc::{
+    exec,
+    rch::ack::{self, ConfirmError},
+};
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn simple() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<ack::Receiver<i32>>().await;
+
+    let (tx, rx) = ack::channel(4);
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+
+    let recv_task = exec::spawn(async move {
+        let mut received = Vec::new();
+        while let Some(delivery) = rx.recv().await.unwrap() {
+            assert!(!delivery.is_redelivered());
+            received.push(*delivery);
+            delivery.ack().await.unwrap();
+        }
+        received
+    });
+
+    for i in 0..10 {
+        println!("Sending {i}");
+        tx.send_confirmed(i).await.unwrap();
+    }
+
+    let mut confirmations = Vec::new();
+    for i in 10..20 {
+        confirmations.push(tx.send(i).await.unwrap());
+    }
+    for confirmation in confirmations {
+        confirmation.await.unwrap();
+    }
+    assert!(tx.unacked().is_empty());
+
+    drop(tx);
+    assert_eq!(recv_task.await.unwrap(), (0..20).collect::<Vec<_>>());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn redeliver() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<ack::Receiver<i32>>().await;
+
+    let (tx, rx) = ack::channel(4);
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+
+    let c0 = tx.send(0).await.unwrap();
+    let c1 = tx.send(1).await.unwrap();
+    let c2 = tx.send(2).await.unwrap();
+
+    println!("Acknowledging only the second value");
+    let d0 = rx.recv().await.unwrap().unwrap();
+    let d1 = rx.recv().await.unwrap().unwrap();
+    let d2 = rx.recv().await.unwrap().unwrap();
+    assert_eq!((d0.id(), d1.id(), d2.id()), (c0.id(), c1.id(), c2.id()));
+    drop(d0);
+    d1.ack().await.unwrap();
+    drop(d2);
+    c1.await.unwrap();
+
+    let unacked: Vec<_> = tx.unacked().into_iter().map(|(_, value)| value).collect();
+    assert_eq!(unacked, vec![0, 2]);
+
+    println!("Redelivering");
+    assert_eq!(tx.redeliver().await.unwrap(), 2);
+    for (id, value) in [(c0.id(), 0), (c2.id(), 2)] {
+        let delivery = rx.recv().await.unwrap().unwrap();
+        assert!(delivery.is_redelivered());
+        assert_eq!(delivery.id(), id);
+        assert_eq!(*delivery, value);
+        delivery.ack().await.unwrap();
+    }
+
+    c0.await.unwrap();
+    c2.await.unwrap();
+    assert!(tx.unacked().is_empty());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn receiver_dropped() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<ack::Receiver<String>>().await;
+
+    let (tx, rx) = ack::channel(4);
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+
+    let c0 = tx.send("first".to_string()).await.unwrap();
+    let c1 = tx.send("second".to_string()).await.unwrap();
+
+    let delivery = rx.recv().await.unwrap().unwrap();
+    delivery.ack().await.unwrap();
+    c0.await.unwrap();
+
+    println!("Dropping receiver");
+    drop(rx);
+    assert!(matches!(c1.await, Err(ConfirmError::Closed)));
+
+    assert_eq!(tx.take_unacked(), vec!["second".to_string()]);
+    assert!(tx.unacked().is_empty());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn take_unacked() {
+    crate::init();
+
+    let (tx, mut rx) = ack::channel::<i32, remoc::codec::Default>(4);
+    let confirmation = tx.send(1).await.unwrap();
+    assert_eq!(tx.take_unacked(), vec![1]);
+    assert!(matches!(confirmation.await, Err(ConfirmError::Taken)));
+
+    println!("Late acknowledgement is ignored");
+    let delivery = rx.recv().await.unwrap().unwrap();
+    delivery.ack().await.unwrap();
+    assert!(tx.unacked().is_empty());
+}
@@ -1,11 +1,13 @@
 use futures::StreamExt;
+use std::{collections::HashSet, time::Duration};
 
 #[cfg(feature = "js")]
 use wasm_bindgen_test::wasm_bindgen_test;
 
-use crate::loop_channel_with_cfg;
+use crate::{loop_channel, loop_channel_with_cfg};
 use remoc::{
     codec, exec,
+    exec::time::sleep,
     rch::{
         broadcast::{self, ReceiverStream},
         mpsc,
@@ -213,3 +215,94 @@ async fn simple_stream() {
     println!("Waiting for tasks to finish");
     tokio::try_join!(rx1_task, rx2_task, rx3_task).unwrap();
 }
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn history_last() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<broadcast::Receiver<i32>>().await;
+
+    let tx = broadcast::Sender::<i32>::with_history(broadcast::History::Last(5));
+    assert_eq!(tx.history(), broadcast::History::Last(5));
+
+    println!("Sending values without receivers");
+    for i in 0..10 {
+        assert!(tx.send(i).unwrap_err().is_closed());
+    }
+    assert_eq!(tx.retained(), vec![5, 6, 7, 8, 9]);
+
+    println!("Sending late subscriber to remote endpoint");
+    let rx = tx.subscribe(16);
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+
+    for i in 10..15 {
+        tx.send(i).unwrap();
+    }
+    drop(tx);
+
+    for i in 5..15 {
+        assert_eq!(rx.recv().await.unwrap(), i);
+    }
+    assert!(rx.recv().await.unwrap_err().is_closed());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn history_window() {
+    crate::init();
+
+    let tx = broadcast::Sender::<i32>::with_history(broadcast::History::Window(Duration::from_millis(200)));
+    for i in 0..3 {
+        let _ = tx.send(i);
+    }
+    sleep(Duration::from_millis(400)).await;
+    for i in 3..6 {
+        let _ = tx.send(i);
+    }
+
+    let mut rx = tx.subscribe::<16>(16);
+    drop(tx);
+    for i in 3..6 {
+        assert_eq!(rx.recv().await.unwrap(), i);
+    }
+    assert!(rx.recv().await.unwrap_err().is_closed());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn filtered() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<broadcast::FilteredReceiver<i32, HashSet<i32>>>().await;
+
+    let tx = broadcast::Sender::<i32>::new();
+    let mut all_rx = tx.subscribe::<16>(32);
+
+    println!("Sending filtered receiver to remote endpoint");
+    let rx = tx.subscribe_filtered(16, HashSet::from([1, 2]));
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+    assert_eq!(*rx.filter(), HashSet::from([1, 2]));
+
+    for i in 0..10 {
+        tx.send(i).unwrap();
+    }
+    assert_eq!(rx.recv().await.unwrap(), 1);
+    assert_eq!(rx.recv().await.unwrap(), 2);
+
+    println!("Changing filter from remote endpoint");
+    rx.set_filter(HashSet::from([5])).unwrap();
+    sleep(Duration::from_millis(100)).await;
+
+    for i in 0..10 {
+        tx.send(i).unwrap();
+    }
+    drop(tx);
+
+    assert_eq!(rx.recv().await.unwrap(), 5);
+    assert!(rx.recv().await.unwrap_err().is_closed());
+
+    for i in (0..10).chain(0..10) {
+        assert_eq!(all_rx.recv().await.unwrap(), i);
+    }
+}
@@ -901,3 +901,257 @@ async fn tokio_copy_read_error_no_shutdown() {
 
     write_task.await.unwrap();
 }
+
+// ============================================================================
+// Duplex channel
+// ============================================================================
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn duplex_simple() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<io::Duplex>().await;
+
+    let (mut local, remote) = io::duplex();
+    a_tx.send(remote).await.unwrap();
+    let mut remote = b_rx.recv().await.unwrap().unwrap();
+
+    let remote_task = exec::spawn(async move {
+        let mut buf = [0; 4];
+        remote.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+        remote.write_all(b"pong").await.unwrap();
+        remote.flush().await.unwrap();
+
+        // Read until local side has shut down its writing half.
+        let mut rest = Vec::new();
+        remote.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, b"bye");
+
+        // Writing is still possible after the other direction is closed.
+        remote.write_all(b"done").await.unwrap();
+        remote.shutdown().await.unwrap();
+        assert_eq!(remote.bytes_written(), 8);
+        assert_eq!(remote.bytes_received(), 7);
+    });
+
+    local.write_all(b"ping").await.unwrap();
+    local.flush().await.unwrap();
+    let mut buf = [0; 4];
+    local.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"pong");
+
+    local.write_all(b"bye").await.unwrap();
+    local.shutdown().await.unwrap();
+
+    let mut rest = Vec::new();
+    local.read_to_end(&mut rest).await.unwrap();
+    assert_eq!(rest, b"done");
+
+    remote_task.await.unwrap();
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn duplex_forward() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<io::Duplex>().await;
+    let ((mut c_tx, _), (_, mut d_rx)) = loop_channel::<io::Duplex>().await;
+
+    let (mut local, remote) = io::duplex();
+    a_tx.send(remote).await.unwrap();
+    let remote = b_rx.recv().await.unwrap().unwrap();
+    c_tx.send(remote).await.unwrap();
+    let remote = d_rx.recv().await.unwrap().unwrap();
+
+    // Echo server using tokio::io::copy on the split halves.
+    let echo_task = exec::spawn(async move {
+        let (mut tx, mut rx) = remote.into_split();
+        tokio::io::copy(&mut rx, &mut tx).await.unwrap();
+        tx.shutdown().await.unwrap();
+    });
+
+    let mut data = vec![0u8; 100_000];
+    rand::rng().fill_bytes(&mut data);
+
+    let (mut local_rx, mut local_tx) = tokio::io::split(&mut local);
+    let data_ref = &data;
+    let write = async move {
+        local_tx.write_all(data_ref).await.unwrap();
+        local_tx.shutdown().await.unwrap();
+    };
+    let read = async move {
+        let mut buf = Vec::new();
+        local_rx.read_to_end(&mut buf).await.unwrap();
+        buf
+    };
+    let ((), echoed) = tokio::join!(write, read);
+    assert_eq!(echoed, data);
+
+    echo_task.await.unwrap();
+}
+
+// ============================================================================
+// Resumable transfers
+// ============================================================================
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn resumable_interrupted() {
+    crate::init();
+
+    let mut data = vec![0u8; 100_000];
+    rand::rng().fill_bytes(&mut data);
+    let mut stored = Vec::new();
+
+    // First attempt is interrupted after part of the data has been transferred.
+    {
+        let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<io::ResumableReceiver>().await;
+
+        let (tx, rx) = io::resumable(data.len() as u64);
+        assert_eq!(tx.size(), 100_000);
+        a_tx.send(rx).await.unwrap();
+        let rx = b_rx.recv().await.unwrap().unwrap();
+        assert_eq!(rx.size(), 100_000);
+
+        let mut rx = rx.resume(stored.len() as u64).unwrap();
+        assert_eq!(rx.size(), Some(100_000));
+
+        let mut source = std::io::Cursor::new(data.clone());
+        let (point, mut tx) = tx.resume(&mut source).await.unwrap();
+        assert_eq!(point.offset(), 0);
+        assert_eq!(source.position(), 0);
+
+        let write_data = data[..40_000].to_vec();
+        let write_task = exec::spawn(async move {
+            tx.write_all(&write_data).await.unwrap();
+            tx.flush().await.unwrap();
+        });
+
+        let mut buf = vec![0; 40_000];
+        rx.read_exact(&mut buf).await.unwrap();
+        stored.extend_from_slice(&buf);
+        write_task.await.unwrap();
+    }
+
+    // Second attempt continues where the first one left off.
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<io::ResumableReceiver>().await;
+
+    let (tx, rx) = io::resumable(data.len() as u64);
+    a_tx.send(rx).await.unwrap();
+    let rx = b_rx.recv().await.unwrap().unwrap();
+    let mut rx = rx.resume(stored.len() as u64).unwrap();
+    assert_eq!(rx.size(), Some(60_000));
+
+    let mut source = std::io::Cursor::new(data.clone());
+    let (point, mut tx) = tx.resume(&mut source).await.unwrap();
+    assert_eq!(point.offset(), 40_000);
+    assert_eq!(source.position(), 40_000);
+    assert_eq!(tx.expected_size(), Some(60_000));
+
+    let write_task = exec::spawn(async move {
+        tokio::io::copy(&mut source, &mut tx).await.unwrap();
+        tx.shutdown().await.unwrap();
+    });
+
+    rx.read_to_end(&mut stored).await.unwrap();
+    write_task.await.unwrap();
+
+    assert_eq!(stored, data);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn resumable_invalid_offset() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<io::ResumableReceiver>().await;
+
+    let (tx, rx) = io::resumable::<remoc::codec::Default>(10);
+    a_tx.send(rx).await.unwrap();
+    let rx = b_rx.recv().await.unwrap().unwrap();
+
+    let err = rx.resume(11).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let err = tx.resume(&mut std::io::Cursor::new(vec![0; 10])).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+// ============================================================================
+// Progress
+// ============================================================================
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn sized_progress() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<io::Receiver>().await;
+
+    let mut data = vec![0u8; 2_000_000];
+    rand::rng().fill_bytes(&mut data);
+
+    let (mut tx, rx) = io::sized(data.len() as u64);
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+
+    let mut tx_progress = tx.progress();
+    assert_eq!(tx_progress.borrow().total, Some(2_000_000));
+    assert_eq!(tx_progress.borrow().transferred, 0);
+    let rx_progress = rx.progress();
+    assert_eq!(rx_progress.borrow().total, Some(2_000_000));
+
+    let write_data = data.clone();
+    let write_task = exec::spawn(async move {
+        tx.write_all(&write_data).await.unwrap();
+        tx.flush().await.unwrap();
+        tx
+    });
+
+    let mut buf = Vec::new();
+    rx.read_to_end(&mut buf).await.unwrap();
+    assert_eq!(buf, data);
+    assert!(rx_progress.borrow().is_complete());
+
+    // All data is acknowledged to the sender, once the receiver has consumed it.
+    let progress = *tx_progress.wait_for(|progress| progress.is_complete()).await.unwrap();
+    assert_eq!(progress.transferred, 2_000_000);
+    assert!(progress.rate > 0.0);
+
+    let tx = write_task.await.unwrap();
+    assert_eq!(tx.bytes_written(), 2_000_000);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn unsized_progress() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<io::Receiver>().await;
+
+    let mut data = vec![0u8; 1_500_000];
+    rand::rng().fill_bytes(&mut data);
+
+    let (mut tx, rx) = io::channel();
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+
+    let mut tx_progress = tx.progress();
+    assert_eq!(tx_progress.borrow().total, None);
+    assert_eq!(tx_progress.borrow().fraction(), None);
+    let rx_progress = rx.progress();
+
+    let write_data = data.clone();
+    let write_task = exec::spawn(async move {
+        tx.write_all(&write_data).await.unwrap();
+        tx.shutdown().await.unwrap();
+    });
+
+    let mut buf = Vec::new();
+    rx.read_to_end(&mut buf).await.unwrap();
+    write_task.await.unwrap();
+    assert_eq!(buf, data);
+    assert_eq!(rx_progress.borrow().total, Some(1_500_000));
+    assert!(rx_progress.borrow().is_complete());
+
+    let progress = *tx_progress.wait_for(|progress| progress.is_complete()).await.unwrap();
+    assert_eq!(progress.total, Some(1_500_000));
+}
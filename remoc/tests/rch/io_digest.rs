@@ -0,0 +1,229 @@
+//! Tests for digest verification of the io channel.
+
+use rand::Rng;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_channel;
+use remoc::{
+    exec,
+    rch::io::{self, Digest, DigestAlgorithm},
+};
+
+async fn transfer(algorithm: DigestAlgorithm, sized: bool) -> (Digest, Digest) {
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<io::Receiver>().await;
+
+    let mut data = vec![0u8; 100_000];
+    rand::rng().fill_bytes(&mut data);
+
+    let (mut tx, rx) = if sized {
+        io::sized_with_digest(data.len() as u64, algorithm)
+    } else {
+        io::channel_with_digest(algorithm)
+    };
+    assert_eq!(tx.digest_algorithm(), Some(algorithm));
+    assert_eq!(rx.digest_algorithm(), Some(algorithm));
+
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+    assert_eq!(rx.digest_algorithm(), Some(algorithm));
+
+    let write_data = data.clone();
+    let write_task = exec::spawn(async move {
+        tx.write_all(&write_data).await.unwrap();
+        tx.shutdown().await.unwrap();
+        tx.digest().unwrap()
+    });
+
+    let mut buf = Vec::new();
+    rx.read_to_end(&mut buf).await.unwrap();
+    assert_eq!(buf, data);
+
+    (write_task.await.unwrap(), rx.digest().unwrap())
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn sha256() {
+    crate::init();
+
+    for sized in [false, true] {
+        let (sent, received) = transfer(DigestAlgorithm::Sha256, sized).await;
+        assert_eq!(sent, received);
+        assert_eq!(received.algorithm(), DigestAlgorithm::Sha256);
+        assert_eq!(received.as_bytes().len(), 32);
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn blake3() {
+    crate::init();
+
+    for sized in [false, true] {
+        let (sent, received) = transfer(DigestAlgorithm::Blake3, sized).await;
+        assert_eq!(sent, received);
+        assert_eq!(received.algorithm(), DigestAlgorithm::Blake3);
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn known_value() {
+    crate::init();
+
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<io::Receiver>().await;
+
+    let (mut tx, rx) = io::channel_with_digest(DigestAlgorithm::Sha256);
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+
+    let write_task = exec::spawn(async move {
+        tx.write_all(b"abc").await.unwrap();
+        tx.shutdown().await.unwrap();
+    });
+
+    let mut buf = Vec::new();
+    rx.read_to_end(&mut buf).await.unwrap();
+    write_task.await.unwrap();
+
+    assert_eq!(
+        rx.digest().unwrap().to_string(),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn forward_sender() {
+    crate::init();
+
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<io::Sender>().await;
+
+    let (tx, mut rx) = io::channel_with_digest(DigestAlgorithm::Blake3);
+    a_tx.send(tx).await.unwrap();
+    let mut tx = b_rx.recv().await.unwrap().unwrap();
+    assert_eq!(tx.digest_algorithm(), Some(DigestAlgorithm::Blake3));
+
+    let write_task = exec::spawn(async move {
+        tx.write_all(b"forwarded data").await.unwrap();
+        tx.shutdown().await.unwrap();
+        tx.digest().unwrap()
+    });
+
+    let mut buf = Vec::new();
+    rx.read_to_end(&mut buf).await.unwrap();
+    let sent = write_task.await.unwrap();
+
+    assert_eq!(buf, b"forwarded data");
+    assert_eq!(rx.digest(), Some(sent));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn sized_no_shutdown() {
+    crate::init();
+
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<io::Receiver>().await;
+
+    let (mut tx, rx) = io::sized_with_digest(5, DigestAlgorithm::Sha256);
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+
+    let write_task = exec::spawn(async move {
+        tx.write_all(b"hello").await.unwrap();
+        tx.flush().await.unwrap();
+        // Dropped without shutdown, thus the digest is never sent.
+    });
+
+    let mut buf = Vec::new();
+    let res = rx.read_to_end(&mut buf).await;
+    write_task.await.unwrap();
+
+    assert_eq!(buf, b"hello");
+    assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+    assert!(rx.digest().is_none());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn serialize_after_write_fails() {
+    crate::init();
+
+    let ((mut a_tx, _), (_, _b_rx)) = loop_channel::<io::Sender>().await;
+    let ((mut c_tx, _), (_, mut d_rx)) = loop_channel::<io::Receiver>().await;
+
+    let (mut tx, rx) = io::channel_with_digest(DigestAlgorithm::Sha256);
+    c_tx.send(rx).await.unwrap();
+    let _rx = d_rx.recv().await.unwrap().unwrap();
+
+    tx.write_all(b"data").await.unwrap();
+    tx.flush().await.unwrap();
+
+    let res = a_tx.send(tx).await;
+    assert!(res.is_err());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn resumable_with_digest() {
+    crate::init();
+
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<io::ResumableReceiver>().await;
+
+    let data = b"hello resumable world".to_vec();
+    let stored = data[..6].to_vec();
+
+    let (tx, rx) = io::resumable(data.len() as u64);
+    a_tx.send(rx).await.unwrap();
+    let rx = b_rx.recv().await.unwrap().unwrap();
+
+    let prefix = DigestAlgorithm::Blake3.digest_reader(&stored[..]).await.unwrap();
+    let mut rx = rx.resume_with_digest(stored.len() as u64, prefix).unwrap();
+
+    let mut source = std::io::Cursor::new(data.clone());
+    let (point, mut tx) = tx.resume(&mut source).await.unwrap();
+    assert_eq!(point.offset(), 6);
+    assert_eq!(point.digest(), Some(prefix));
+    assert_eq!(source.position(), 6);
+
+    let write_task = exec::spawn(async move {
+        tokio::io::copy(&mut source, &mut tx).await.unwrap();
+        tx.shutdown().await.unwrap();
+    });
+
+    let mut buf = stored;
+    rx.read_to_end(&mut buf).await.unwrap();
+    write_task.await.unwrap();
+
+    assert_eq!(buf, data);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn resumable_digest_mismatch() {
+    crate::init();
+
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<io::ResumableReceiver>().await;
+
+    let data = b"hello resumable world".to_vec();
+    let stored = b"jello ".to_vec();
+
+    let (tx, rx) = io::resumable(data.len() as u64);
+    a_tx.send(rx).await.unwrap();
+    let rx = b_rx.recv().await.unwrap().unwrap();
+
+    let prefix = DigestAlgorithm::Sha256.digest_reader(&stored[..]).await.unwrap();
+    let mut rx = rx.resume_with_digest(stored.len() as u64, prefix).unwrap();
+
+    println!("Resuming with corrupted partial data");
+    let err = tx.resume(&mut std::io::Cursor::new(data)).await.unwrap_err();
+    println!("Sender error: {err}");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    let mut buf = stored;
+    let err = rx.read_to_end(&mut buf).await.unwrap_err();
+    println!("Receiver error: {err}");
+}
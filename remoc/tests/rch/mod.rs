@@ -1,8 +1,13 @@
+mod ack;
 mod bin;
 mod broadcast;
 mod io;
+#[cfg(feature = "io-digest")]
+mod io_digest;
 mod lr;
 mod mpsc;
 mod oneshot;
+mod pubsub;
 mod remote;
+mod stream;
 mod watch;
@@ -854,3 +854,588 @@ async fn forwarded_local() {
     println!("Closing local sender and expecting channel to end");
     assert!(rx.recv().await.unwrap().is_none());
 }
+
+#[cfg(feature = "fs")]
+#[tokio::test]
+async fn spooled() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<mpsc::SpooledReceiver<i32>>().await;
+
+    let path = std::env::temp_dir().join(format!("remoc-spool-{}", uuid::Uuid::new_v4()));
+    let tx = mpsc::SpooledSender::<i32>::open(&path).await.unwrap();
+
+    println!("Spooling values without connection");
+    for i in 0..5 {
+        assert_eq!(tx.send(i * 10).await.unwrap(), i as u64);
+    }
+    assert_eq!(tx.pending(), 5);
+
+    println!("Connecting first receiver");
+    a_tx.send(tx.connect()).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+    assert_eq!(rx.id(), tx.id());
+    for i in 0..3 {
+        let delivery = rx.recv().await.unwrap().unwrap();
+        assert_eq!(delivery.seq(), i);
+        assert_eq!(*delivery, i as i32 * 10);
+        if i == 2 {
+            delivery.ack().await.unwrap();
+        }
+    }
+    assert_eq!(rx.last_acked(), Some(2));
+    while tx.pending() != 2 {
+        sleep(Duration::from_millis(10)).await;
+    }
+    let delivery = rx.recv().await.unwrap().unwrap();
+    assert_eq!(delivery.seq(), 3);
+
+    println!("Connecting second receiver");
+    a_tx.send(tx.connect()).await.unwrap();
+    sleep(Duration::from_millis(100)).await;
+
+    // Value 3 has been processed, but its acknowledgement is lost together with the first connection.
+    let _ = delivery.ack().await;
+    assert_eq!(rx.last_acked(), Some(3));
+    let mut rx2 = b_rx.recv().await.unwrap().unwrap();
+    assert_eq!(rx2.last_acked(), Some(2));
+    rx2.resume_after(rx.last_acked().unwrap());
+    drop(rx);
+    let mut rx = rx2;
+    assert_eq!(rx.last_acked(), Some(3));
+    tx.send(50).await.unwrap();
+    for i in 4..6 {
+        let delivery = rx.recv().await.unwrap().unwrap();
+        assert_eq!(delivery.seq(), i);
+        assert_eq!(*delivery, i as i32 * 10);
+        delivery.ack().await.unwrap();
+    }
+    while tx.pending() != 0 {
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    println!("Closing channel");
+    drop(tx);
+    assert!(rx.recv().await.unwrap().is_none());
+
+    tokio::fs::remove_file(&path).await.unwrap();
+}
+
+#[cfg(feature = "fs")]
+#[tokio::test]
+async fn spooled_resume_after() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<mpsc::SpooledReceiver<i32>>().await;
+
+    let path = std::env::temp_dir().join(format!("remoc-spool-{}", uuid::Uuid::new_v4()));
+    let tx = mpsc::SpooledSender::<i32>::open(&path).await.unwrap();
+    for i in 0..4 {
+        tx.send(i * 10).await.unwrap();
+    }
+
+    // Values up to 1 have been processed by a receiver before a restart.
+    a_tx.send(tx.connect()).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+    rx.resume_after(1);
+    for i in 2..4 {
+        let delivery = rx.recv().await.unwrap().unwrap();
+        assert_eq!(delivery.seq(), i);
+        delivery.ack().await.unwrap();
+    }
+    while tx.pending() != 0 {
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    drop(tx);
+    assert!(rx.recv().await.unwrap().is_none());
+    tokio::fs::remove_file(&path).await.unwrap();
+}
+
+#[cfg(feature = "fs")]
+#[tokio::test]
+async fn spooled_reopen() {
+    crate::init();
+
+    let path = std::env::temp_dir().join(format!("remoc-spool-{}", uuid::Uuid::new_v4()));
+
+    println!("Spooling values");
+    let tx = mpsc::SpooledSender::<String>::open(&path).await.unwrap();
+    let id = tx.id();
+    for i in 0..3 {
+        tx.send(format!("value {i}")).await.unwrap();
+    }
+    drop(tx);
+
+    println!("Reopening spool");
+    let tx = mpsc::SpooledSender::<String>::open(&path).await.unwrap();
+    assert_eq!(tx.id(), id);
+    assert_eq!(tx.pending(), 3);
+
+    let mut rx = tx.connect();
+    for i in 0..3 {
+        let delivery = rx.recv().await.unwrap().unwrap();
+        assert_eq!(*delivery, format!("value {i}"));
+        if i == 1 {
+            delivery.ack().await.unwrap();
+        }
+    }
+    while tx.pending() != 1 {
+        sleep(Duration::from_millis(10)).await;
+    }
+    drop(rx);
+    drop(tx);
+
+    println!("Reopening spool with acknowledged values");
+    let tx = mpsc::SpooledSender::<String>::open(&path).await.unwrap();
+    assert_eq!(tx.pending(), 1);
+    let mut rx = tx.connect();
+    let delivery = rx.recv().await.unwrap().unwrap();
+    assert_eq!((delivery.seq(), delivery.as_str()), (2, "value 2"));
+    delivery.ack().await.unwrap();
+    while tx.pending() != 0 {
+        sleep(Duration::from_millis(10)).await;
+    }
+    drop(rx);
+    drop(tx);
+
+    println!("Reopening cleared spool");
+    let tx = mpsc::SpooledSender::<String>::open(&path).await.unwrap();
+    assert_eq!(tx.pending(), 0);
+    assert_eq!(tx.send("value 3".to_string()).await.unwrap(), 3);
+    drop(tx);
+
+    tokio::fs::remove_file(&path).await.unwrap();
+}
+
+#[cfg(feature = "fs")]
+#[tokio::test]
+async fn spooled_corruption() {
+    crate::init();
+
+    let path = std::env::temp_dir().join(format!("remoc-spool-{}", uuid::Uuid::new_v4()));
+
+    println!("Spooling values");
+    let tx = mpsc::SpooledSender::<String>::open(&path).await.unwrap();
+    for i in 0..3 {
+        tx.send(format!("value {i}")).await.unwrap();
+    }
+    drop(tx);
+
+    println!("Appending partially written record");
+    let mut data = std::fs::read(&path).unwrap();
+    let intact_len = data.len();
+    data.extend_from_slice(&100u32.to_le_bytes());
+    data.extend_from_slice(b"torn");
+    std::fs::write(&path, &data).unwrap();
+
+    let tx = mpsc::SpooledSender::<String>::open(&path).await.unwrap();
+    assert_eq!(tx.pending(), 3);
+    assert_eq!(tx.send("value 3".to_string()).await.unwrap(), 3);
+    drop(tx);
+    assert!(std::fs::metadata(&path).unwrap().len() as usize > intact_len);
+
+    let tx = mpsc::SpooledSender::<String>::open(&path).await.unwrap();
+    assert_eq!(tx.pending(), 4);
+    drop(tx);
+
+    println!("Corrupting complete record");
+    let mut data = std::fs::read(&path).unwrap();
+    let mut pos = 0;
+    for _ in 0..2 {
+        let len = u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap()) as usize;
+        pos += 4 + len;
+    }
+    let len = u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap()) as usize;
+    data[pos + 4..pos + 4 + len].fill(0xff);
+    std::fs::write(&path, &data).unwrap();
+
+    let err = mpsc::SpooledSender::<String>::open(&path).await.unwrap_err();
+    println!("Open failed: {err}");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(std::fs::read(&path).unwrap(), data);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "fs")]
+#[tokio::test]
+async fn spooled_compaction() {
+    crate::init();
+
+    let path = std::env::temp_dir().join(format!("remoc-spool-{}", uuid::Uuid::new_v4()));
+    let tx = mpsc::SpooledSender::<u32>::open(&path).await.unwrap();
+    for i in 0..1100 {
+        tx.send(i).await.unwrap();
+    }
+    let size = tokio::fs::metadata(&path).await.unwrap().len();
+
+    println!("Acknowledging most values");
+    let mut rx = tx.connect();
+    for i in 0..1050 {
+        let delivery = rx.recv().await.unwrap().unwrap();
+        if i == 1049 {
+            delivery.ack().await.unwrap();
+        }
+    }
+    while tx.pending() != 50 {
+        sleep(Duration::from_millis(10)).await;
+    }
+    assert!(tokio::fs::metadata(&path).await.unwrap().len() < size / 10);
+    drop(rx);
+    drop(tx);
+
+    println!("Reopening compacted spool");
+    let tx = mpsc::SpooledSender::<u32>::open(&path).await.unwrap();
+    assert_eq!(tx.pending(), 50);
+    let mut rx = tx.connect();
+    let delivery = rx.recv().await.unwrap().unwrap();
+    assert_eq!((delivery.seq(), *delivery), (1050, 1050));
+    assert_eq!(tx.send(1100).await.unwrap(), 1100);
+    drop(tx);
+
+    tokio::fs::remove_file(&path).await.unwrap();
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn priority() {
+    use mpsc::Priority;
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<mpsc::PriorityReceiver<i32>>().await;
+
+    let (tx, rx) = mpsc::priority_channel(2);
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+
+    for (value, priority) in [
+        (0, Priority::Bulk),
+        (10, Priority::Normal),
+        (1, Priority::Bulk),
+        (100, Priority::Urgent),
+        (11, Priority::Normal),
+        (101, Priority::Urgent),
+    ] {
+        tx.send(value, priority).await.unwrap();
+    }
+    sleep(Duration::from_millis(200)).await;
+
+    let mut received = Vec::new();
+    for _ in 0..6 {
+        received.push(rx.recv_with_priority().await.unwrap().unwrap());
+    }
+    assert_eq!(
+        received,
+        vec![
+            (Priority::Urgent, 100),
+            (Priority::Urgent, 101),
+            (Priority::Normal, 10),
+            (Priority::Normal, 11),
+            (Priority::Bulk, 0),
+            (Priority::Bulk, 1)
+        ]
+    );
+
+    drop(tx);
+    assert_eq!(rx.recv().await.unwrap(), None);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn priority_urgent_bypass() {
+    use mpsc::Priority;
+
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<mpsc::PriorityReceiver<u32>>().await;
+
+    let (tx, rx) = mpsc::priority_channel(1);
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+
+    println!("Filling bulk buffer");
+    let mut bulk = 0;
+    loop {
+        match tx.try_send(bulk, Priority::Bulk) {
+            Ok(_) => bulk += 1,
+            Err(TrySendError::Full(_)) => break,
+            Err(err) => panic!("send failed: {err}"),
+        }
+        assert!(bulk < 1000, "bulk buffer never full");
+    }
+    println!("Bulk buffer full after {bulk} values");
+
+    println!("Sending urgent value");
+    tx.try_send(1000, Priority::Urgent).unwrap();
+    drop(tx);
+
+    let mut received = Vec::new();
+    while let Some(value) = rx.recv().await.unwrap() {
+        received.push(value);
+    }
+    received.sort_unstable();
+    let mut expected: Vec<_> = (0..bulk).collect();
+    expected.push(1000);
+    assert_eq!(received, expected);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn priority_closed() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<mpsc::PriorityReceiver<u32>>().await;
+
+    let (tx, rx) = mpsc::priority_channel(1);
+    a_tx.send(rx).await.unwrap();
+    let rx = b_rx.recv().await.unwrap().unwrap();
+    assert!(!tx.is_closed());
+
+    println!("Dropping receiver");
+    drop(rx);
+    tx.closed().await;
+    assert!(tx.is_closed());
+}
+
+/// Receives all values of the distributed receivers concurrently.
+async fn collect_distributed(rxs: Vec<mpsc::Receiver<u32>>) -> Vec<Vec<u32>> {
+    let tasks: Vec<_> = rxs
+        .into_iter()
+        .map(|mut rx| {
+            exec::spawn(async move {
+                let mut values = Vec::new();
+                while let Some(value) = rx.recv().await.unwrap() {
+                    values.push(value);
+                }
+                values
+            })
+        })
+        .collect();
+
+    let mut received = Vec::new();
+    for task in tasks {
+        received.push(task.await.unwrap());
+    }
+    received
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn distribute_round_robin() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<mpsc::Receiver<u32>>().await;
+
+    let (tx, rx) = mpsc::channel(1);
+    a_tx.send(rx).await.unwrap();
+    let rx = b_rx.recv().await.unwrap().unwrap();
+
+    let distributor = rx.distribute_with(false, mpsc::RoundRobin::new());
+    let mut rxs = Vec::new();
+    let mut handles = Vec::new();
+    for id in 0..3 {
+        let (rx, handle) = distributor.subscribe().await.unwrap();
+        assert_eq!(handle.id(), id);
+        rxs.push(rx);
+        handles.push(handle);
+    }
+    let collect = exec::spawn(collect_distributed(rxs));
+
+    for i in 0..9 {
+        tx.send(i).await.unwrap();
+    }
+    drop(tx);
+
+    let received = collect.await.unwrap();
+    println!("Received: {received:?}");
+    assert_eq!(received, vec![vec![0, 3, 6], vec![1, 4, 7], vec![2, 5, 8]]);
+    sleep(Duration::from_millis(100)).await;
+
+    for handle in &handles {
+        let stats = handle.stats();
+        println!("Stats: {stats:?}");
+        assert_eq!(stats.sent, 3);
+        assert_eq!(stats.outstanding, 0);
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn distribute_key_affinity() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<mpsc::Receiver<u32>>().await;
+
+    let (tx, rx) = mpsc::channel(1);
+    a_tx.send(rx).await.unwrap();
+    let rx = b_rx.recv().await.unwrap().unwrap();
+
+    let distributor = rx.distribute_with(false, mpsc::KeyAffinity::new(|value: &u32| value % 5));
+    let mut rxs = Vec::new();
+    let mut handles = Vec::new();
+    for _ in 0..3 {
+        let (rx, handle) = distributor.subscribe().await.unwrap();
+        rxs.push(rx);
+        handles.push(handle);
+    }
+    let collect = exec::spawn(collect_distributed(rxs));
+
+    for i in 0..50 {
+        tx.send(i).await.unwrap();
+    }
+    drop(tx);
+
+    let received = collect.await.unwrap();
+    println!("Received: {received:?}");
+    for key in 0..5 {
+        let owners: Vec<_> = received.iter().filter(|values| values.iter().any(|v| v % 5 == key)).collect();
+        assert_eq!(owners.len(), 1, "key {key} split over multiple receivers");
+        let values: Vec<_> = owners[0].iter().filter(|v| *v % 5 == key).copied().collect();
+        assert_eq!(values, (key..50).step_by(5).collect::<Vec<_>>());
+    }
+
+    let sent: u64 = handles.iter().map(|handle| handle.stats().sent).sum();
+    assert_eq!(sent, 50);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn distribute_least_outstanding() {
+    crate::init();
+
+    let (tx, rx) = mpsc::channel::<u32, codec::Default>(1);
+    let distributor = rx.distribute_with(true, mpsc::LeastOutstanding);
+    let (mut rx1, handle1) = distributor.subscribe().await.unwrap();
+    let (mut rx2, handle2) = distributor.subscribe().await.unwrap();
+
+    tx.send(0).await.unwrap();
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(handle1.stats().outstanding, 1);
+    tx.send(1).await.unwrap();
+    assert_eq!(rx2.recv().await.unwrap(), Some(1));
+    assert_eq!(rx1.recv().await.unwrap(), Some(0));
+    assert_eq!(handle1.stats().sent, 1);
+    assert_eq!(handle1.stats().outstanding, 0);
+
+    println!("Removing first receiver");
+    handle1.remove();
+    for i in 2..5 {
+        tx.send(i).await.unwrap();
+        assert_eq!(rx2.recv().await.unwrap(), Some(i));
+    }
+    assert_eq!(handle2.stats().sent, 4);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn fan_in() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<mpsc::FanInSender<u32>>().await;
+    let ((mut c_tx, _), (_, mut d_rx)) = loop_channel::<mpsc::FanInSender<u32>>().await;
+
+    let (local_tx, mut rx) = mpsc::fan_in_channel(1);
+
+    println!("Sending fan-in senders");
+    a_tx.send(local_tx.clone()).await.unwrap();
+    a_tx.send(local_tx.clone()).await.unwrap();
+    c_tx.send(local_tx.clone()).await.unwrap();
+    let tx1 = b_rx.recv().await.unwrap().unwrap();
+    let tx1_clone = tx1.clone();
+    let tx2 = b_rx.recv().await.unwrap().unwrap();
+    let tx3 = d_rx.recv().await.unwrap().unwrap();
+
+    println!(
+        "Origins: local={:?} tx1={:?} tx1_clone={:?} tx2={:?} tx3={:?}",
+        local_tx.origin(),
+        tx1.origin(),
+        tx1_clone.origin(),
+        tx2.origin(),
+        tx3.origin()
+    );
+    assert_eq!(tx1.origin().peer, tx1_clone.origin().peer);
+    assert_eq!(tx1.origin().peer, tx2.origin().peer);
+    assert_ne!(tx1.origin().sender, tx1_clone.origin().sender);
+    assert_ne!(tx1.origin().sender, tx2.origin().sender);
+    assert_ne!(tx1_clone.origin().sender, tx2.origin().sender);
+    assert_ne!(tx1.origin().peer, tx3.origin().peer);
+    assert_ne!(local_tx.origin().peer, tx1.origin().peer);
+
+    for (i, tx) in [&local_tx, &tx1, &tx1_clone, &tx2, &tx3].into_iter().enumerate() {
+        tx.send(i as u32).await.unwrap();
+        let (origin, value) = rx.recv().await.unwrap().unwrap();
+        println!("Received {value} from {origin:?}");
+        assert_eq!(origin, tx.origin());
+        assert_eq!(value, i as u32);
+    }
+    assert_eq!(rx.peers().count(), 3);
+
+    println!("Dropping senders");
+    drop(local_tx);
+    drop(tx1);
+    drop(tx1_clone);
+    drop(tx2);
+    drop(tx3);
+    drop(a_tx);
+    drop(c_tx);
+    assert!(rx.recv().await.unwrap().is_none());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn fan_in_lane_closed() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<mpsc::FanInSender<u32>>().await;
+    let ((mut c_tx, _), (_, mut d_rx)) = loop_channel::<mpsc::FanInSender<u32>>().await;
+
+    let (local_tx, mut rx) = mpsc::fan_in_channel(1);
+    a_tx.send(local_tx.clone()).await.unwrap();
+    let tx1 = b_rx.recv().await.unwrap().unwrap();
+    c_tx.send(local_tx.clone()).await.unwrap();
+    let tx2 = d_rx.recv().await.unwrap().unwrap();
+
+    tx2.send(2).await.unwrap();
+    assert_eq!(rx.recv().await.unwrap(), Some((tx2.origin(), 2)));
+    tx1.send(1).await.unwrap();
+    assert_eq!(rx.recv().await.unwrap(), Some((tx1.origin(), 1)));
+    assert_eq!(rx.peers().count(), 3);
+
+    println!("Closing local peer while a value is available from another peer");
+    drop(local_tx);
+    tx1.send(3).await.unwrap();
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(rx.try_recv().unwrap(), (tx1.origin(), 3));
+    assert_eq!(rx.peers().count(), 2);
+
+    drop(tx1);
+    sleep(Duration::from_millis(100)).await;
+    tx2.send(4).await.unwrap();
+    assert_eq!(rx.recv().await.unwrap(), Some((tx2.origin(), 4)));
+    assert_eq!(rx.peers().count(), 1);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn fan_in_close_peer() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<mpsc::FanInSender<u32>>().await;
+    let ((mut c_tx, _), (_, mut d_rx)) = loop_channel::<mpsc::FanInSender<u32>>().await;
+
+    let (local_tx, mut rx) = mpsc::fan_in_channel(1);
+    a_tx.send(local_tx.clone()).await.unwrap();
+    c_tx.send(local_tx.clone()).await.unwrap();
+    let tx1 = b_rx.recv().await.unwrap().unwrap();
+    let tx2 = d_rx.recv().await.unwrap().unwrap();
+
+    tx1.send(1).await.unwrap();
+    let (origin, value) = rx.recv().await.unwrap().unwrap();
+    assert_eq!(origin, tx1.origin());
+    assert_eq!(value, 1);
+
+    println!("Closing peer {}", origin.peer);
+    assert!(rx.close_peer(origin.peer));
+    tx1.closed().await;
+    assert!(tx1.is_closed());
+    assert!(matches!(tx1.send(10).await, Err(SendError::Closed(10))));
+
+    println!("Sending from other peers");
+    tx2.send(2).await.unwrap();
+    assert_eq!(rx.recv().await.unwrap(), Some((tx2.origin(), 2)));
+    local_tx.send(3).await.unwrap();
+    assert_eq!(rx.recv().await.unwrap(), Some((local_tx.origin(), 3)));
+    assert!(!tx2.is_closed());
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_channel;
+use remoc::{
+    codec::{self, Codec},
+    exec::time::sleep,
+    rch::{
+        broadcast::History,
+        pubsub::{Hub, Pattern, PublishError, TopicError},
+    },
+};
+
+#[cfg_attr(not(feature = "js"), test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+fn pattern() {
+    crate::init();
+
+    let pattern = Pattern::new("sensors/+/temperature").unwrap();
+    assert!(pattern.matches("sensors/kitchen/temperature"));
+    assert!(!pattern.matches("sensors/kitchen/humidity"));
+    assert!(!pattern.matches("sensors/kitchen/fridge/temperature"));
+    assert!(!pattern.matches("sensors/temperature"));
+
+    let pattern: Pattern = "sensors/#".parse().unwrap();
+    assert!(pattern.matches("sensors"));
+    assert!(pattern.matches("sensors/kitchen"));
+    assert!(pattern.matches("sensors/kitchen/fridge/temperature"));
+    assert!(!pattern.matches("actuators/kitchen"));
+
+    let pattern = Pattern::new("#").unwrap();
+    assert!(pattern.matches("anything/at/all"));
+
+    assert_eq!(Pattern::new("").unwrap_err(), TopicError::Empty);
+    assert_eq!(Pattern::new("sensors/#/temperature").unwrap_err(), TopicError::MisplacedWildcard);
+    assert_eq!(Pattern::new("sensors/kit+/temperature").unwrap_err(), TopicError::MisplacedWildcard);
+    assert_eq!(Pattern::new("sensors#").unwrap_err(), TopicError::MisplacedWildcard);
+
+    let mut buf = Vec::new();
+    codec::Default::serialize(&mut buf, &Pattern::new("sensors/+").unwrap()).unwrap();
+    let pattern: Pattern = codec::Default::deserialize(&buf[..]).unwrap();
+    assert_eq!(pattern.as_str(), "sensors/+");
+
+    let mut buf = Vec::new();
+    codec::Default::serialize(&mut buf, &"sensors/#/temperature".to_string()).unwrap();
+    assert!(codec::Default::deserialize::<_, Pattern>(&buf[..]).is_err());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn simple() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<Hub<i32>>().await;
+
+    let hub = Hub::<i32>::new();
+    let mut kitchen = hub.subscribe(Pattern::new("kitchen/#").unwrap(), 16).await.unwrap();
+
+    println!("Sending hub to remote endpoint");
+    a_tx.send(hub.clone()).await.unwrap();
+    let remote_hub = b_rx.recv().await.unwrap().unwrap();
+    let mut temperature = remote_hub.subscribe(Pattern::new("+/temperature").unwrap(), 16).await.unwrap();
+    assert_eq!(temperature.pattern().as_str(), "+/temperature");
+
+    println!("Publishing from remote endpoint");
+    remote_hub.publish("kitchen/temperature", 21).await.unwrap();
+    remote_hub.publish("kitchen/humidity", 40).await.unwrap();
+    remote_hub.publish("bedroom/temperature", 18).await.unwrap();
+    remote_hub.publish("bedroom/humidity", 50).await.unwrap();
+    assert!(matches!(
+        remote_hub.publish("bedroom/+", 0).await,
+        Err(PublishError::InvalidTopic(TopicError::Wildcard))
+    ));
+
+    let msg = kitchen.recv().await.unwrap();
+    assert_eq!((msg.topic.as_str(), msg.value), ("kitchen/temperature", 21));
+    let msg = kitchen.recv().await.unwrap();
+    assert_eq!((msg.topic.as_str(), msg.value), ("kitchen/humidity", 40));
+
+    let msg = temperature.recv().await.unwrap();
+    assert_eq!((msg.topic.as_str(), msg.value), ("kitchen/temperature", 21));
+    let msg = temperature.recv().await.unwrap();
+    assert_eq!((msg.topic.as_str(), msg.value), ("bedroom/temperature", 18));
+
+    println!("Changing pattern");
+    temperature.set_pattern(Pattern::new("bedroom/humidity").unwrap()).unwrap();
+    sleep(Duration::from_millis(100)).await;
+    hub.publish("bedroom/temperature", 19).await.unwrap();
+    hub.publish("bedroom/humidity", 55).await.unwrap();
+
+    let msg = temperature.recv().await.unwrap();
+    assert_eq!((msg.topic.as_str(), msg.value), ("bedroom/humidity", 55));
+
+    println!("Shutting down hub");
+    drop(hub);
+    drop(remote_hub);
+    assert!(kitchen.recv().await.unwrap_err().is_closed());
+    assert!(temperature.recv().await.unwrap_err().is_closed());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn history() {
+    crate::init();
+
+    let hub = Hub::<i32>::with_history(History::Last(3));
+    for i in 0..5 {
+        hub.publish(format!("counter/{}", i % 2), i).await.unwrap();
+    }
+
+    let mut even = hub.subscribe(Pattern::new("counter/0").unwrap(), 16).await.unwrap();
+    assert_eq!(even.recv().await.unwrap().value, 2);
+    assert_eq!(even.recv().await.unwrap().value, 4);
+
+    hub.publish("counter/0", 6).await.unwrap();
+    assert_eq!(even.recv().await.unwrap().value, 6);
+}
@@ -0,0 +1,112 @@
+use futures::{SinkExt, StreamExt, stream};
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_channel;
+use remoc::rch::{
+    mpsc,
+    stream::{SendError, Sender, forward, forward_sink, try_forward},
+};
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn simple() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<mpsc::Receiver<u32>>().await;
+
+    let (fwd, rx) = forward(stream::iter(0..100));
+    a_tx.send(rx).await.unwrap();
+    let rx = b_rx.recv().await.unwrap().unwrap();
+
+    let values: Vec<_> = rx.map(|res| res.unwrap()).collect().await;
+    assert_eq!(values, (0..100).collect::<Vec<_>>());
+    fwd.await.unwrap();
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn cancel() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<mpsc::Receiver<u32>>().await;
+
+    let (guard_tx, guard_rx) = tokio::sync::oneshot::channel::<()>();
+    let endless = stream::unfold((0, guard_tx), |(i, guard_tx)| async move { Some((i, (i + 1, guard_tx))) });
+    let (fwd, rx) = forward(endless);
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+
+    for i in 0..10 {
+        assert_eq!(rx.recv().await.unwrap(), Some(i));
+    }
+
+    println!("Dropping remote receiver");
+    drop(rx);
+    fwd.await.unwrap();
+    assert!(guard_rx.await.is_err());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn error() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<mpsc::Receiver<Result<u32, String>>>().await;
+
+    let items = vec![Ok(1), Ok(2), Err("failed".to_string()), Ok(3)];
+    let (fwd, rx) = try_forward(stream::iter(items));
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+
+    assert_eq!(rx.recv().await.unwrap(), Some(Ok(1)));
+    assert_eq!(rx.recv().await.unwrap(), Some(Ok(2)));
+    assert_eq!(rx.recv().await.unwrap(), Some(Err("failed".to_string())));
+    assert_eq!(rx.recv().await.unwrap(), None);
+    fwd.await.unwrap();
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn sink() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<Sender<u32, String>>().await;
+
+    let (local_tx, local_rx) = futures::channel::mpsc::channel(1);
+    a_tx.send(forward_sink(local_tx.sink_map_err(|err| err.to_string()))).await.unwrap();
+    let mut tx = b_rx.recv().await.unwrap().unwrap();
+
+    let recv_task = remoc::exec::spawn(local_rx.collect::<Vec<_>>());
+    for i in 0..100 {
+        tx.send(i).await.unwrap();
+    }
+    tx.close().await.unwrap();
+
+    assert_eq!(recv_task.await.unwrap(), (0..100).collect::<Vec<_>>());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn sink_error() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<Sender<i32, String>>().await;
+
+    let sink = futures::sink::unfold(0, |sum, value: i32| async move {
+        if value < 0 { Err(format!("negative value after sum {sum}")) } else { Ok(sum + value) }
+    });
+    a_tx.send(forward_sink(Box::pin(sink))).await.unwrap();
+    let mut tx = b_rx.recv().await.unwrap().unwrap();
+
+    tx.send(1).await.unwrap();
+    tx.send(2).await.unwrap();
+    tx.send(-1).await.unwrap();
+
+    let err = loop {
+        match tx.send(3).await {
+            Ok(()) => (),
+            Err(err) => break err,
+        }
+    };
+    match err {
+        SendError::Sink(err) => assert_eq!(err, "negative value after sum 3"),
+        other => panic!("unexpected error: {other}"),
+    }
+}
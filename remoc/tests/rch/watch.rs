@@ -1,5 +1,5 @@
 use futures::StreamExt;
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 #[cfg(feature = "js")]
 use wasm_bindgen_test::wasm_bindgen_test;
@@ -7,10 +7,10 @@ use wasm_bindgen_test::wasm_bindgen_test;
 use crate::{droppable_loop_channel, loop_channel};
 use remoc::{
     exec,
-    exec::time::sleep,
+    exec::time::{sleep, timeout},
     rch::{
         base::SendErrorKind,
-        watch::{self, ChangedError, ReceiverStream, SendError},
+        watch::{self, ChangedError, Diff, ReceiverStream, SendError},
     },
 };
 
@@ -654,3 +654,170 @@ async fn wait_for_closed() {
     let result = rx.wait_for(|v| *v == 999).await;
     assert!(result.is_err(), "wait_for should fail when sender is dropped");
 }
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, watch::Diff)]
+struct DiffState {
+    counter: u32,
+    name: String,
+    #[diff(nested)]
+    entries: HashMap<u32, String>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, watch::Diff)]
+struct DiffTuple(u8, String);
+
+#[cfg_attr(not(feature = "js"), test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+fn diff_derive() {
+    let a = DiffState { counter: 1, name: "a".to_string(), entries: HashMap::from([(1, "one".to_string())]) };
+    assert!(a.diff(&a.clone()).is_none());
+
+    let b = DiffState { counter: 2, name: "a".to_string(), entries: HashMap::from([(2, "two".to_string())]) };
+    let patch = a.diff(&b).unwrap();
+    assert_eq!(patch.counter, Some(2));
+    assert_eq!(patch.name, None);
+    let entries = patch.entries.as_ref().unwrap();
+    assert_eq!(entries.inserted, vec![(2, "two".to_string())]);
+    assert_eq!(entries.removed, vec![1]);
+
+    let mut patched = a.clone();
+    patched.apply(patch);
+    assert_eq!(patched, b);
+
+    let c = DiffTuple(1, "c".to_string());
+    let d = DiffTuple(1, "d".to_string());
+    let patch = c.diff(&d).unwrap();
+    assert_eq!(patch.0, None);
+    let mut patched = c.clone();
+    patched.apply(patch);
+    assert_eq!(patched, d);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn diff_receiver() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<watch::DiffReceiver<DiffState>>().await;
+
+    let init = DiffState { counter: 0, name: "state".to_string(), entries: HashMap::new() };
+    let (mut tx, _) = watch::diff_channel(init);
+    tx.set_snapshot_interval(3);
+    let rx = tx.subscribe();
+
+    println!("Sending remote diff watch receiver");
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+    assert_eq!(rx.borrow().unwrap().name, "state");
+
+    for i in 1..=20 {
+        tx.send_modify(|state| {
+            state.counter = i;
+            state.entries.insert(i, i.to_string());
+            state.entries.remove(&(i - 1));
+        });
+        if i % 2 == 0 {
+            sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    let expected = tx.borrow().clone();
+    let received = rx.wait_for(|state| state.counter == 20).await.unwrap().clone();
+    assert_eq!(received, expected);
+    assert_eq!(received.entries, HashMap::from([(20, "20".to_string())]));
+
+    drop(rx);
+    tx.closed().await;
+    tx.check().unwrap();
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn diff_sender() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<watch::DiffSender<DiffState>>().await;
+
+    let init = DiffState { counter: 0, name: "state".to_string(), entries: HashMap::new() };
+    let (tx, mut rx) = watch::diff_channel(init);
+
+    println!("Sending remote diff watch sender");
+    a_tx.send(tx).await.unwrap();
+    let tx = b_rx.recv().await.unwrap().unwrap();
+    assert_eq!(tx.snapshot_interval(), watch::DEFAULT_SNAPSHOT_INTERVAL);
+
+    for i in 1..=10 {
+        tx.send_modify(|state| {
+            state.counter = i;
+            state.name = format!("state {i}");
+            state.entries.insert(i, i.to_string());
+        });
+    }
+
+    let expected = tx.borrow().clone();
+    let received = rx.wait_for(|state| state.counter == 10).await.unwrap().clone();
+    assert_eq!(received, expected);
+    assert_eq!(received.entries.len(), 10);
+}
+
+/// Value whose patch fails to deserialize when it sets the value 13.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Flaky(u32);
+
+#[derive(serde::Serialize)]
+struct FlakyPatch(u32);
+
+impl<'de> serde::Deserialize<'de> for FlakyPatch {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        match u32::deserialize(deserializer)? {
+            13 => Err(serde::de::Error::custom("unlucky patch")),
+            value => Ok(Self(value)),
+        }
+    }
+}
+
+impl Diff for Flaky {
+    type Patch = FlakyPatch;
+
+    fn diff(&self, new: &Self) -> Option<Self::Patch> {
+        (self != new).then_some(FlakyPatch(new.0))
+    }
+
+    fn apply(&mut self, patch: Self::Patch) {
+        self.0 = patch.0;
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn diff_resync() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<watch::DiffReceiver<Flaky>>().await;
+
+    let (mut tx, _) = watch::diff_channel(Flaky(0));
+    tx.set_snapshot_interval(1000);
+    let rx = tx.subscribe();
+    a_tx.send(rx).await.unwrap();
+    let mut rx = b_rx.recv().await.unwrap().unwrap();
+
+    for i in 1..=20 {
+        tx.send(Flaky(i)).unwrap();
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    // Receiving patch 13 fails, thus the following patches cannot be applied
+    // until a snapshot has been requested.
+    timeout(Duration::from_secs(5), async {
+        loop {
+            if let Ok(value) = rx.borrow()
+                && value.0 == 20
+            {
+                break;
+            }
+            rx.changed().await.unwrap();
+        }
+    })
+    .await
+    .unwrap();
+}
@@ -0,0 +1,147 @@
+use futures::StreamExt;
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_transport;
+use remoc::{
+    chmux::ChMuxError,
+    exec,
+    rch::{base, mpsc},
+    relay::{Relay, RelayEndpoint, RelayError},
+};
+
+/// Connects an endpoint to the relay.
+async fn connect(
+    relay: &Relay, name: &str,
+) -> Result<RelayEndpoint, ChMuxError<futures::channel::mpsc::SendError, std::io::Error>> {
+    loop_transport!(0, a_tx, a_rx, b_tx, b_rx);
+
+    let relay = relay.clone();
+    exec::spawn(async move {
+        let res = relay.serve(Default::default(), b_tx, b_rx).await;
+        println!("Relay serve result: {res:?}");
+    });
+
+    let (mux, endpoint) = RelayEndpoint::connect(Default::default(), name, a_tx, a_rx).await?;
+    exec::spawn(async move {
+        let res = mux.run().await;
+        println!("Endpoint mux result: {res:?}");
+    });
+    Ok(endpoint)
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn channels() {
+    crate::init();
+
+    let relay = Relay::new();
+    let alice = connect(&relay, "alice").await.unwrap();
+    let mut bob = connect(&relay, "bob").await.unwrap();
+    assert_eq!(relay.endpoints(), ["alice", "bob"]);
+
+    let bob_task = exec::spawn(async move {
+        let (peer, _tx, mut rx): (_, base::Sender<()>, base::Receiver<mpsc::Sender<u32>>) =
+            bob.accept().await.unwrap().unwrap();
+        assert_eq!(peer, "alice");
+
+        let tx = rx.recv().await.unwrap().unwrap();
+        for i in 0..10 {
+            tx.send(i).await.unwrap();
+        }
+    });
+
+    let (mut tx, _rx): (base::Sender<mpsc::Sender<u32>>, base::Receiver<()>) =
+        alice.connect_to("bob").await.unwrap();
+    let (value_tx, mut value_rx) = mpsc::channel(1);
+    tx.send(value_tx).await.unwrap();
+
+    for i in 0..10 {
+        assert_eq!(value_rx.recv().await.unwrap(), Some(i));
+    }
+    assert_eq!(value_rx.recv().await.unwrap(), None);
+    bob_task.await.unwrap();
+}
+
+#[cfg(feature = "rtc")]
+mod rtc {
+    use remoc::{
+        exec,
+        rch::base,
+        rtc::{CallError, ServerRefMut},
+    };
+
+    #[cfg(feature = "js")]
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::connect;
+    use remoc::relay::Relay;
+
+    #[remoc::rtc::remote]
+    pub trait Counter {
+        async fn increase(&mut self, by: u32) -> Result<u32, CallError>;
+    }
+
+    pub struct CounterObj(u32);
+
+    impl Counter for CounterObj {
+        async fn increase(&mut self, by: u32) -> Result<u32, CallError> {
+            self.0 += by;
+            Ok(self.0)
+        }
+    }
+
+    #[cfg_attr(not(feature = "js"), tokio::test)]
+    #[cfg_attr(feature = "js", wasm_bindgen_test)]
+    async fn client() {
+        crate::init();
+
+        let relay = Relay::new();
+        let server = connect(&relay, "server").await.unwrap();
+        let mut client = connect(&relay, "client").await.unwrap();
+
+        exec::spawn(async move {
+            let (_, mut tx, _rx): (_, base::Sender<CounterClient>, base::Receiver<()>) =
+                client.accept().await.unwrap().unwrap();
+            let mut obj = CounterObj(0);
+            let (server, counter) = CounterServerRefMut::new(&mut obj, 1);
+            tx.send(counter).await.unwrap();
+            server.serve().await.unwrap();
+        });
+
+        let (_tx, mut rx): (base::Sender<()>, base::Receiver<CounterClient>) =
+            server.connect_to("client").await.unwrap();
+        let mut counter = rx.recv().await.unwrap().unwrap();
+        assert_eq!(counter.increase(2).await.unwrap(), 2);
+        assert_eq!(counter.increase(3).await.unwrap(), 5);
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn unknown_peer() {
+    crate::init();
+
+    let relay = Relay::new();
+    let alice = connect(&relay, "alice").await.unwrap();
+
+    let res: Result<(base::Sender<()>, base::Receiver<()>), _> = alice.connect_to("bob").await;
+    let err = res.unwrap_err();
+    println!("Connect error: {err}");
+    assert!(matches!(err, RelayError::Refused(_)));
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn duplicate_name() {
+    crate::init();
+
+    let relay = Relay::new();
+    let _alice = connect(&relay, "alice").await.unwrap();
+
+    let err = connect(&relay, "alice").await.unwrap_err();
+    println!("Connect error: {err}");
+    assert!(matches!(err, ChMuxError::Rejected(_)));
+    assert_eq!(relay.endpoints(), ["alice"]);
+}
@@ -141,3 +141,129 @@ async fn local() {
     let fetched = lazy.into_inner().await.unwrap();
     assert_eq!(Vec::from(fetched), data);
 }
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn progress() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<LazyBlob>().await;
+
+    let size = 3_000_000;
+    let mut data = vec![0; size];
+    rand::rng().fill_bytes(&mut data);
+
+    let lazy: LazyBlob = LazyBlob::new(data.clone().into());
+    a_tx.send(lazy).await.unwrap();
+    let lazy = b_rx.recv().await.unwrap().unwrap();
+
+    let mut progress = lazy.progress();
+    assert_eq!(progress.borrow().transferred, 0);
+    assert_eq!(progress.borrow().total, Some(size as u64));
+
+    let watch_task = remoc::exec::spawn(async move {
+        let mut updates = 0;
+        while progress.changed().await.is_ok() {
+            let progress = *progress.borrow_and_update();
+            assert!(progress.transferred <= size as u64);
+            updates += 1;
+            if progress.is_complete() {
+                break;
+            }
+        }
+        updates
+    });
+
+    let fetched = lazy.get().await.unwrap();
+    assert_eq!(Vec::from(fetched), data);
+
+    let progress = *lazy.progress().borrow();
+    assert!(progress.is_complete());
+    assert_eq!(progress.fraction(), Some(1.0));
+
+    let updates = watch_task.await.unwrap();
+    println!("received {updates} progress updates");
+    assert!(updates > 1);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn from_reader() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<LazyBlob>().await;
+
+    let mut rng = rand::rng();
+    let size = rng.random_range(1_000_000..2_000_000);
+    let mut data = vec![0; size];
+    rng.fill_bytes(&mut data);
+
+    println!("Creating lazy blob of size {} bytes from reader", data.len());
+    let lazy = LazyBlob::from_reader(std::io::Cursor::new(data.clone())).await.unwrap();
+    assert_eq!(lazy.len().unwrap(), size);
+
+    println!("Sending lazy blob");
+    a_tx.send(lazy).await.unwrap();
+    let lazy = b_rx.recv().await.unwrap().unwrap();
+    assert_eq!(lazy.len().unwrap(), size);
+
+    println!("Writing to writer");
+    let mut written = Vec::new();
+    assert_eq!(lazy.write_to(&mut written).await.unwrap(), size as u64);
+    assert_eq!(written, data);
+    assert!(lazy.progress().borrow().is_complete());
+
+    println!("Fetching reference");
+    let fetched = lazy.get().await.unwrap();
+    assert_eq!(Vec::from(fetched), data);
+
+    println!("Writing cached data to writer");
+    let mut written = Vec::new();
+    assert_eq!(lazy.write_to(&mut written).await.unwrap(), size as u64);
+    assert_eq!(written, data);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn from_reader_local() {
+    crate::init();
+
+    let mut data = vec![0; 10_000];
+    rand::rng().fill_bytes(&mut data);
+
+    let lazy: LazyBlob = LazyBlob::from_reader(std::io::Cursor::new(data.clone())).await.unwrap();
+
+    let mut written = Vec::new();
+    assert_eq!(lazy.write_to(&mut written).await.unwrap(), data.len() as u64);
+    assert_eq!(written, data);
+
+    let fetched = lazy.into_inner().await.unwrap();
+    assert_eq!(Vec::from(fetched), data);
+}
+
+#[cfg(feature = "fs")]
+#[tokio::test]
+async fn from_file() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<LazyBlob>().await;
+
+    let mut data = vec![0; 3_000_000];
+    rand::rng().fill_bytes(&mut data);
+
+    let path = std::env::temp_dir().join(format!("remoc-lazy-blob-{}", uuid::Uuid::new_v4()));
+    tokio::fs::write(&path, &data).await.unwrap();
+
+    println!("Creating lazy blob from file {}", path.display());
+    let lazy = LazyBlob::from_file(&path).await.unwrap();
+    assert_eq!(lazy.len().unwrap(), data.len());
+
+    a_tx.send(lazy).await.unwrap();
+    let lazy = b_rx.recv().await.unwrap().unwrap();
+
+    let mut written = Vec::new();
+    assert_eq!(lazy.write_to(&mut written).await.unwrap(), data.len() as u64);
+    assert_eq!(written, data);
+
+    let fetched = lazy.into_inner().await.unwrap();
+    assert_eq!(Vec::from(fetched), data);
+
+    tokio::fs::remove_file(&path).await.unwrap();
+}
@@ -1,4 +1,5 @@
 mod handle;
 mod lazy;
 mod lazy_blob;
+mod remote_reader;
 mod rw_lock;
@@ -0,0 +1,188 @@
+use rand::{Rng, RngExt};
+use std::{
+    io::{self, Cursor, ErrorKind, SeekFrom},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, ReadBuf};
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::loop_channel;
+use remoc::{exec::time::sleep, robj::remote_reader::RemoteReader};
+
+fn random_data(size: usize) -> Vec<u8> {
+    let mut data = vec![0; size];
+    rand::rng().fill_bytes(&mut data);
+    data
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn sequential() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<RemoteReader>().await;
+
+    let data = random_data(3_000_000);
+    let reader = RemoteReader::new(Cursor::new(data.clone())).await.unwrap();
+    assert_eq!(reader.len(), 3_000_000);
+
+    a_tx.send(reader).await.unwrap();
+    let mut reader = b_rx.recv().await.unwrap().unwrap();
+    assert_eq!(reader.len(), 3_000_000);
+    reader.set_read_ahead(100_000);
+
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf).await.unwrap();
+    assert_eq!(buf, data);
+    assert_eq!(reader.position(), 3_000_000);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn random_access() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<RemoteReader>().await;
+
+    let data = random_data(1_000_000);
+    let reader = RemoteReader::new(Cursor::new(data.clone())).await.unwrap();
+    a_tx.send(reader).await.unwrap();
+    let mut reader = b_rx.recv().await.unwrap().unwrap();
+    reader.set_read_ahead(10_000);
+
+    let mut rng = rand::rng();
+    for _ in 0..50 {
+        let offset = rng.random_range(0..data.len());
+        let len = rng.random_range(1..50_000).min(data.len() - offset);
+        println!("reading {len} bytes at {offset}");
+
+        assert_eq!(reader.seek(SeekFrom::Start(offset as u64)).await.unwrap(), offset as u64);
+        let mut buf = vec![0; len];
+        reader.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, &data[offset..offset + len]);
+    }
+
+    reader.seek(SeekFrom::End(-10)).await.unwrap();
+    reader.seek(SeekFrom::Current(-10)).await.unwrap();
+    let mut buf = vec![0; 10];
+    reader.read_exact(&mut buf).await.unwrap();
+    assert_eq!(buf, &data[data.len() - 20..data.len() - 10]);
+
+    let err = reader.seek(SeekFrom::Current(-2_000_000)).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    // Reading beyond the end returns EOF.
+    reader.seek(SeekFrom::Start(2_000_000)).await.unwrap();
+    assert_eq!(reader.read(&mut buf).await.unwrap(), 0);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn forwarded() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<RemoteReader>().await;
+    let ((mut b_tx, _), (_, mut c_rx)) = loop_channel::<RemoteReader>().await;
+
+    let data = random_data(500_000);
+    let reader = RemoteReader::new(Cursor::new(data.clone())).await.unwrap();
+
+    a_tx.send(reader).await.unwrap();
+    let mut reader = b_rx.recv().await.unwrap().unwrap();
+    reader.seek(SeekFrom::Start(1000)).await.unwrap();
+    b_tx.send(reader).await.unwrap();
+    let mut reader = c_rx.recv().await.unwrap().unwrap();
+
+    // Position is preserved when forwarding.
+    assert_eq!(reader.position(), 1000);
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf).await.unwrap();
+    assert_eq!(buf, &data[1000..]);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn provider_dropped() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<RemoteReader>().await;
+
+    let (reader, provider) = RemoteReader::provided(Cursor::new(random_data(1000))).await.unwrap();
+    a_tx.send(reader).await.unwrap();
+    let mut reader = b_rx.recv().await.unwrap().unwrap();
+
+    drop(provider);
+
+    let mut buf = Vec::new();
+    assert!(reader.read_to_end(&mut buf).await.is_err());
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn local() {
+    crate::init();
+
+    let data = random_data(100_000);
+    let mut reader: RemoteReader = RemoteReader::new(Cursor::new(data.clone())).await.unwrap();
+
+    reader.seek(SeekFrom::Start(50_000)).await.unwrap();
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf).await.unwrap();
+    assert_eq!(buf, &data[50_000..]);
+}
+
+/// Data source recording the offsets it is read from.
+struct RecordingSource {
+    inner: Cursor<Vec<u8>>,
+    seeks: Arc<Mutex<Vec<u64>>>,
+}
+
+impl AsyncRead for RecordingSource {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncSeek for RecordingSource {
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        if let SeekFrom::Start(offset) = position {
+            self.seeks.lock().unwrap().push(offset);
+        }
+        Pin::new(&mut self.inner).start_seek(position)
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Pin::new(&mut self.inner).poll_complete(cx)
+    }
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn read_ahead() {
+    crate::init();
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<RemoteReader>().await;
+
+    let data = random_data(10_000);
+    let seeks = Arc::new(Mutex::new(Vec::new()));
+    let source = RecordingSource { inner: Cursor::new(data.clone()), seeks: seeks.clone() };
+    let reader = RemoteReader::new(source).await.unwrap();
+    a_tx.send(reader).await.unwrap();
+    let mut reader = b_rx.recv().await.unwrap().unwrap();
+    reader.set_read_ahead(1000);
+
+    println!("Reading into second half of first block");
+    let mut buf = vec![0; 600];
+    reader.read_exact(&mut buf).await.unwrap();
+    assert_eq!(buf, &data[..600]);
+
+    println!("Waiting without reading");
+    sleep(Duration::from_millis(200)).await;
+    let requested = seeks.lock().unwrap().clone();
+    println!("Requested offsets: {requested:?}");
+    assert!(requested.contains(&1000), "following block was not requested in advance");
+
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf).await.unwrap();
+    assert_eq!(buf, &data[600..]);
+}
@@ -4,6 +4,7 @@ mod errors;
 mod generics;
 mod generics_non_clone;
 mod readonly;
+mod reconnect;
 mod simple;
 mod simple_clone;
 mod simple_req;
@@ -0,0 +1,224 @@
+use futures::future;
+use remoc::{
+    chmux, exec,
+    rtc::{CallError, Client, ReconnectCfg, ReconnectingClient, ServerShared},
+};
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use tokio::sync::mpsc;
+
+#[cfg(feature = "js")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::droppable_loop_channel;
+
+#[remoc::rtc::remote]
+pub trait Value {
+    async fn value(&self) -> Result<u32, CallError>;
+    async fn hang(&self) -> Result<(), CallError>;
+}
+
+pub struct ValueObj;
+
+impl Value for ValueObj {
+    async fn value(&self) -> Result<u32, CallError> {
+        Ok(42)
+    }
+
+    async fn hang(&self) -> Result<(), CallError> {
+        future::pending().await
+    }
+}
+
+/// Connections established by the connect factory.
+#[derive(Clone, Default)]
+struct Connections {
+    count: Arc<AtomicUsize>,
+    current: Arc<Mutex<Option<mpsc::Receiver<()>>>>,
+}
+
+impl Connections {
+    async fn connect(self) -> Result<ValueClient, CallError> {
+        let ((mut a_tx, _), (_, mut b_rx), conn) = droppable_loop_channel::<ValueClient>().await;
+
+        let (server, client) = ValueServerShared::new(Arc::new(ValueObj), 1);
+        exec::spawn(server.serve(true));
+        a_tx.send(client).await.unwrap();
+        let client = b_rx.recv().await.unwrap().unwrap();
+
+        self.count.fetch_add(1, Ordering::SeqCst);
+        *self.current.lock().unwrap() = Some(conn);
+        Ok(client)
+    }
+
+    fn disconnect(&self) {
+        println!("Dropping connection");
+        self.current.lock().unwrap().take().expect("not connected");
+    }
+
+    fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+}
+
+fn reconnecting(conns: &Connections) -> ReconnectingClient<ValueClient> {
+    let cfg = ReconnectCfg { initial_backoff: Duration::from_millis(10), ..Default::default() };
+    let conns = conns.clone();
+    ReconnectingClient::new(cfg, move || conns.clone().connect())
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn reconnect() {
+    crate::init();
+    let conns = Connections::default();
+    let client = reconnecting(&conns);
+
+    let value = client.call(|c| async move { c.value().await }).await.unwrap();
+    assert_eq!(value, 42);
+    assert!(client.is_connected());
+    assert_eq!(conns.count(), 1);
+
+    conns.disconnect();
+    while conns.count() < 2 {
+        exec::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    println!("Calling after connection loss");
+    let value = client.call(|c| async move { c.value().await }).await.unwrap();
+    assert_eq!(value, 42);
+    assert_eq!(conns.count(), 2);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn backoff_after_loss() {
+    crate::init();
+    let conns = Connections::default();
+    let cfg = ReconnectCfg { initial_backoff: Duration::from_millis(500), ..Default::default() };
+    let factory_conns = conns.clone();
+    let client = ReconnectingClient::new(cfg, move || factory_conns.clone().connect());
+
+    client.client().await.unwrap();
+    assert_eq!(conns.count(), 1);
+
+    conns.disconnect();
+    exec::time::sleep(Duration::from_millis(100)).await;
+    println!("Connections after connection loss: {}", conns.count());
+    assert_eq!(conns.count(), 1);
+
+    let value = client.call(|c| async move { c.value().await }).await.unwrap();
+    assert_eq!(value, 42);
+    assert_eq!(conns.count(), 2);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn interrupted() {
+    crate::init();
+    let conns = Connections::default();
+    let client = reconnecting(&conns);
+    client.client().await.unwrap();
+
+    let call_client = client.clone();
+    let call = exec::spawn(async move { call_client.call(|c| async move { c.hang().await }).await });
+    exec::time::sleep(Duration::from_millis(100)).await;
+    conns.disconnect();
+
+    let res = call.await.unwrap();
+    println!("Interrupted call result: {res:?}");
+    assert!(matches!(res, Err(CallError::Interrupted)));
+
+    let value = client.call(|c| async move { c.value().await }).await.unwrap();
+    assert_eq!(value, 42);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn not_sent() {
+    crate::init();
+    let conns = Connections::default();
+    let client = reconnecting(&conns);
+    client.client().await.unwrap();
+
+    let attempts = AtomicUsize::new(0);
+    let value = client
+        .call(|c| {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            let conns = conns.clone();
+            async move {
+                if attempt == 0 {
+                    // Connection is lost before the request could be transmitted.
+                    conns.disconnect();
+                    c.closed().await;
+                    return Err(CallError::RemoteConnect(chmux::ConnectError::ChMux));
+                }
+                c.value().await
+            }
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(value, 42);
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    assert_eq!(conns.count(), 2);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn idempotent() {
+    crate::init();
+    let conns = Connections::default();
+    let client = reconnecting(&conns);
+    client.client().await.unwrap();
+
+    let disconnect_conns = conns.clone();
+    let disconnect = exec::spawn(async move {
+        exec::time::sleep(Duration::from_millis(100)).await;
+        disconnect_conns.disconnect();
+    });
+
+    let attempts = AtomicUsize::new(0);
+    let value = client
+        .call_idempotent(|c| {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt == 0 {
+                    c.hang().await?;
+                }
+                c.value().await
+            }
+        })
+        .await
+        .unwrap();
+    disconnect.await.unwrap();
+
+    assert_eq!(value, 42);
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    assert_eq!(conns.count(), 2);
+}
+
+#[cfg_attr(not(feature = "js"), tokio::test)]
+#[cfg_attr(feature = "js", wasm_bindgen_test)]
+async fn give_up() {
+    crate::init();
+    let attempts = Arc::new(AtomicUsize::new(0));
+
+    let cfg =
+        ReconnectCfg { initial_backoff: Duration::from_millis(10), max_attempts: Some(3), ..Default::default() };
+    let factory_attempts = attempts.clone();
+    let client: ReconnectingClient<ValueClient> = ReconnectingClient::new(cfg, move || {
+        factory_attempts.fetch_add(1, Ordering::SeqCst);
+        future::ready(Err("connection refused"))
+    });
+
+    let res = client.call(|c| async move { c.value().await }).await;
+    assert!(matches!(res, Err(CallError::Dropped)));
+    assert!(!client.is_connected());
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+}
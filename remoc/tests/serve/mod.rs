@@ -0,0 +1,161 @@
+use std::{net::Ipv4Addr, time::Duration};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    time::sleep,
+};
+
+use remoc::{
+    exec,
+    rch::base,
+    serve::{Server, ServerCfg},
+};
+
+async fn connect(addr: std::net::SocketAddr) -> (base::Sender<u32>, base::Receiver<String>) {
+    let socket = TcpStream::connect(addr).await.unwrap();
+    let (socket_rx, socket_tx) = socket.into_split();
+    let (conn, tx, rx) = remoc::Connect::io(Default::default(), socket_rx, socket_tx).await.unwrap();
+    exec::spawn(conn);
+    (tx, rx)
+}
+
+async fn echo(mut tx: base::Sender<String>, mut rx: base::Receiver<u32>) {
+    while let Ok(Some(value)) = rx.recv().await {
+        if tx.send(value.to_string()).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[tokio::test]
+async fn multiple_connections() {
+    crate::init();
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = Server::new(Default::default(), ServerCfg::default(), listener);
+    let handle = server.handle();
+    let server_task = exec::spawn(server.serve(|tx, rx, conn| async move {
+        println!("Serving connection {} from {:?}", conn.id(), conn.peer_addr());
+        let info = conn.info().unwrap();
+        assert_eq!(info.id, conn.id());
+        assert!(info.established);
+        echo(tx, rx).await
+    }));
+
+    let mut clients = Vec::new();
+    for i in 0..5 {
+        let (mut tx, mut rx) = connect(addr).await;
+        tx.send(i).await.unwrap();
+        assert_eq!(rx.recv().await.unwrap(), Some(i.to_string()));
+        clients.push((tx, rx));
+    }
+
+    let connections = handle.connections();
+    println!("Connections: {connections:?}");
+    assert_eq!(connections.len(), 5);
+    for info in &connections {
+        assert!(info.established);
+        assert!(info.bytes_received > 0);
+        assert!(info.bytes_sent > 0);
+        assert_eq!(info.peer_addr.ip(), Ipv4Addr::LOCALHOST);
+    }
+
+    println!("Closing two clients");
+    clients.truncate(3);
+    while handle.connection_count() != 3 {
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    println!("Shutting down");
+    handle.shutdown();
+    drop(clients);
+    server_task.await.unwrap();
+    assert_eq!(handle.connection_count(), 0);
+}
+
+#[tokio::test]
+async fn max_connections() {
+    crate::init();
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server_cfg = ServerCfg { max_connections: Some(1), ..Default::default() };
+    let server = Server::new(Default::default(), server_cfg, listener);
+    let handle = server.handle();
+    exec::spawn(server.serve(|tx, rx, _conn| echo(tx, rx)));
+
+    let (mut tx1, mut rx1) = connect(addr).await;
+    tx1.send(1).await.unwrap();
+    assert_eq!(rx1.recv().await.unwrap(), Some("1".to_string()));
+
+    println!("Connecting second client");
+    let second = exec::spawn(async move {
+        let (mut tx2, mut rx2) = connect(addr).await;
+        tx2.send(2).await.unwrap();
+        rx2.recv().await.unwrap()
+    });
+
+    sleep(Duration::from_millis(300)).await;
+    assert!(!second.is_finished());
+    assert_eq!(handle.connection_count(), 1);
+
+    println!("Closing first client");
+    drop(tx1);
+    drop(rx1);
+    assert_eq!(second.await.unwrap(), Some("2".to_string()));
+
+    handle.shutdown();
+}
+
+#[tokio::test]
+async fn handshake_timeout() {
+    crate::init();
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server_cfg = ServerCfg { handshake_timeout: Some(Duration::from_millis(200)), ..Default::default() };
+    let server = Server::new(Default::default(), server_cfg, listener);
+    let handle = server.handle();
+    exec::spawn(server.serve(|tx, rx, _conn| echo(tx, rx)));
+
+    // Connect but never perform handshake.
+    let _socket = TcpStream::connect(addr).await.unwrap();
+    sleep(Duration::from_millis(100)).await;
+    let connections = handle.connections();
+    assert_eq!(connections.len(), 1);
+    assert!(!connections[0].established);
+
+    sleep(Duration::from_millis(300)).await;
+    assert_eq!(handle.connection_count(), 0);
+
+    handle.shutdown();
+}
+
+#[tokio::test]
+async fn shutdown() {
+    crate::init();
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server_cfg = ServerCfg { shutdown_timeout: Some(Duration::from_millis(200)), ..Default::default() };
+    let server = Server::new(Default::default(), server_cfg, listener);
+    let handle = server.handle();
+    let server_task = exec::spawn(server.serve(|tx, rx, conn| async move {
+        tokio::select! {
+            () = echo(tx, rx) => (),
+            () = conn.shutdown_requested() => println!("Handler notified of shutdown"),
+        }
+    }));
+
+    let (_tx1, _rx1) = connect(addr).await;
+    let (_tx2, _rx2) = connect(addr).await;
+    while handle.connection_count() != 2 {
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    println!("Shutting down while clients are connected");
+    handle.shutdown();
+    assert!(handle.is_shutdown());
+    server_task.await.unwrap();
+    assert_eq!(handle.connection_count(), 0);
+}
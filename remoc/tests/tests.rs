@@ -18,9 +18,16 @@ mod chmux;
 #[cfg(feature = "serde")]
 mod codec;
 
+#[cfg(feature = "rch")]
+#[cfg(not(target_family = "wasm"))]
+mod connect;
+
 #[cfg(feature = "rch")]
 mod rch;
 
+#[cfg(feature = "rch")]
+mod relay;
+
 #[cfg(feature = "rfn")]
 mod rfn;
 
@@ -33,6 +40,18 @@ mod robs;
 #[cfg(feature = "rtc")]
 mod rtc;
 
+#[cfg(all(feature = "process", unix))]
+mod process;
+
+#[cfg(feature = "serve")]
+mod serve;
+
+#[cfg(all(feature = "tunnel", not(target_family = "wasm")))]
+mod tunnel;
+
+#[cfg(all(feature = "websocket", not(target_family = "wasm")))]
+mod websocket;
+
 static INIT: Once = Once::new();
 
 pub fn init() {
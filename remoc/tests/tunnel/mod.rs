@@ -0,0 +1,156 @@
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+
+use crate::loop_channel;
+use remoc::{
+    exec,
+    tunnel::{self, Allowlist, Connector, LocalForward, TunnelError, TunnelInfo},
+};
+
+/// Starts an echo server and returns its address.
+async fn echo_server() -> SocketAddr {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    exec::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            exec::spawn(async move {
+                let (mut rx, mut tx) = socket.split();
+                tokio::io::copy(&mut rx, &mut tx).await.unwrap();
+                tx.shutdown().await.unwrap();
+            });
+        }
+    });
+    addr
+}
+
+/// Provides a connector over a remote connection to a dialer with the specified allowlist.
+async fn remote_connector(allowlist: Allowlist) -> Connector {
+    let ((mut a_tx, _), (_, mut b_rx)) = loop_channel::<Connector>().await;
+    let (dialer, connector) = tunnel::dialer(allowlist);
+    exec::spawn(dialer.serve());
+    a_tx.send(connector).await.unwrap();
+    b_rx.recv().await.unwrap().unwrap()
+}
+
+#[tokio::test]
+async fn forward() {
+    crate::init();
+
+    let target = echo_server().await.to_string();
+    let connector = remote_connector(Allowlist::new().allow(&target)).await;
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let local_addr = listener.local_addr().unwrap();
+    let (closed_tx, mut closed_rx) = mpsc::unbounded_channel();
+    let forward = LocalForward::new(listener, &target, connector).on_close(move |info: &TunnelInfo| {
+        let _ = closed_tx.send(info.clone());
+    });
+    let handle = forward.handle();
+    exec::spawn(forward.run());
+
+    let mut socket = TcpStream::connect(local_addr).await.unwrap();
+    socket.write_all(b"hello tunnel").await.unwrap();
+    let mut buf = [0; 12];
+    socket.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"hello tunnel");
+
+    let tunnels = handle.tunnels();
+    println!("Open tunnels: {tunnels:?}");
+    assert_eq!(tunnels.len(), 1);
+    assert_eq!(tunnels[0].target, target);
+    assert_eq!(tunnels[0].peer_addr, socket.local_addr().unwrap());
+    assert_eq!(tunnels[0].bytes_sent, 12);
+    assert_eq!(tunnels[0].bytes_received, 12);
+
+    // Half-close: the echo server closes its side after our side has been shut down.
+    socket.write_all(b"bye").await.unwrap();
+    socket.shutdown().await.unwrap();
+    let mut rest = Vec::new();
+    socket.read_to_end(&mut rest).await.unwrap();
+    assert_eq!(rest, b"bye");
+
+    let info = closed_rx.recv().await.unwrap();
+    println!("Closed tunnel: {info:?}");
+    assert_eq!(info.bytes_sent, 15);
+    assert_eq!(info.bytes_received, 15);
+    assert!(handle.tunnels().is_empty());
+}
+
+#[tokio::test]
+async fn not_allowed() {
+    crate::init();
+
+    let target = echo_server().await.to_string();
+    let connector = remote_connector(Allowlist::new().allow("127.0.0.1:1")).await;
+
+    let err = connector.open(&target).await.unwrap_err();
+    println!("Open error: {err}");
+    assert!(matches!(err, TunnelError::NotAllowed(t) if t == target));
+}
+
+#[tokio::test]
+async fn dial_failed() {
+    crate::init();
+
+    // Obtain an address with no listener.
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let target = listener.local_addr().unwrap().to_string();
+    drop(listener);
+
+    let connector = remote_connector(Allowlist::new().allow(&target)).await;
+
+    let err = connector.open(&target).await.unwrap_err();
+    println!("Open error: {err}");
+    assert!(matches!(err, TunnelError::Dial(_)));
+}
+
+#[tokio::test]
+async fn concurrent() {
+    crate::init();
+
+    let target = echo_server().await.to_string();
+    let connector = remote_connector(Allowlist::new().allow(&target)).await;
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let local_addr = listener.local_addr().unwrap();
+    let closed = Arc::new(Mutex::new(Vec::new()));
+    let closed_cb = closed.clone();
+    let forward = LocalForward::new(listener, &target, connector)
+        .on_close(move |info: &TunnelInfo| closed_cb.lock().unwrap().push(info.clone()));
+    exec::spawn(forward.run());
+
+    let mut tasks = Vec::new();
+    for i in 0..10u8 {
+        tasks.push(exec::spawn(async move {
+            let mut socket = TcpStream::connect(local_addr).await.unwrap();
+            let data = vec![i; 100_000];
+            let (mut rx, mut tx) = socket.split();
+            let write = async {
+                tx.write_all(&data).await.unwrap();
+                tx.shutdown().await.unwrap();
+            };
+            let mut received = Vec::new();
+            let read = rx.read_to_end(&mut received);
+            let (_, res) = tokio::join!(write, read);
+            res.unwrap();
+            assert_eq!(received, data);
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    while closed.lock().unwrap().len() < 10 {
+        exec::time::sleep(Duration::from_millis(10)).await;
+    }
+    let closed = closed.lock().unwrap();
+    assert!(closed.iter().all(|info| info.bytes_sent == 100_000 && info.bytes_received == 100_000));
+}
@@ -0,0 +1,102 @@
+use futures::{SinkExt, StreamExt};
+use std::net::Ipv4Addr;
+use tokio::net::{TcpListener, TcpStream};
+
+use remoc::{
+    exec,
+    rch::base,
+    websocket::{
+        self,
+        tokio_tungstenite::{accept_async, client_async, tungstenite::Message},
+    },
+};
+
+#[tokio::test]
+async fn loopback() {
+    crate::init();
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        let ws = accept_async(socket).await.unwrap();
+        let (conn, mut tx, mut rx): (_, base::Sender<String>, base::Receiver<u32>) =
+            remoc::Connect::websocket(Default::default(), ws).await.unwrap();
+        exec::spawn(conn);
+
+        while let Some(value) = rx.recv().await.unwrap() {
+            println!("Server received {value}");
+            tx.send(value.to_string()).await.unwrap();
+        }
+    };
+
+    let client = async move {
+        let socket = TcpStream::connect(addr).await.unwrap();
+        let (ws, _) = client_async(format!("ws://{addr}/"), socket).await.unwrap();
+        let (conn, mut tx, mut rx): (_, base::Sender<u32>, base::Receiver<String>) =
+            remoc::Connect::websocket(Default::default(), ws).await.unwrap();
+        exec::spawn(conn);
+
+        for i in 0..100 {
+            tx.send(i).await.unwrap();
+            assert_eq!(rx.recv().await.unwrap(), Some(i.to_string()));
+        }
+    };
+
+    tokio::join!(server, client);
+}
+
+#[tokio::test]
+async fn close_frame() {
+    crate::init();
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(socket).await.unwrap();
+        ws.send(Message::Binary(vec![1, 2, 3].into())).await.unwrap();
+        ws.send(Message::Ping(vec![].into())).await.unwrap();
+        ws.close(None).await.unwrap();
+    };
+
+    let client = async move {
+        let socket = TcpStream::connect(addr).await.unwrap();
+        let (ws, _) = client_async(format!("ws://{addr}/"), socket).await.unwrap();
+        let (_sink, mut stream) = websocket::split(ws);
+
+        assert_eq!(stream.next().await.unwrap().unwrap().as_ref(), &[1, 2, 3]);
+        assert!(stream.next().await.is_none());
+    };
+
+    tokio::join!(server, client);
+}
+
+#[tokio::test]
+async fn text_message() {
+    crate::init();
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(socket).await.unwrap();
+        ws.send(Message::text("hello")).await.unwrap();
+        let _ = ws.next().await;
+    };
+
+    let client = async move {
+        let socket = TcpStream::connect(addr).await.unwrap();
+        let (ws, _) = client_async(format!("ws://{addr}/"), socket).await.unwrap();
+        let (_sink, mut stream) = websocket::split(ws);
+
+        let err = stream.next().await.unwrap().unwrap_err();
+        println!("Received error: {err}");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    };
+
+    tokio::join!(server, client);
+}
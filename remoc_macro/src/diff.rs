@@ -0,0 +1,168 @@
+//! Derive macro for watch channel diffs.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Field, Fields, Index, Member, WherePredicate, parse_quote, spanned::Spanned};
+
+/// Field of the struct deriving `Diff`.
+struct DiffField<'a> {
+    field: &'a Field,
+    member: Member,
+    nested: bool,
+}
+
+impl<'a> DiffField<'a> {
+    fn parse(idx: usize, field: &'a Field) -> syn::Result<Self> {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(idx)),
+        };
+
+        let mut nested = false;
+        for attr in &field.attrs {
+            if attr.path().is_ident("diff") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("nested") {
+                        nested = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("unknown diff attribute"))
+                    }
+                })?;
+            }
+        }
+
+        Ok(Self { field, member, nested })
+    }
+
+    /// Type of the field within the patch.
+    fn patch_ty(&self) -> TokenStream {
+        let ty = &self.field.ty;
+        if self.nested {
+            quote! { ::std::option::Option<<#ty as ::remoc::rch::watch::Diff>::Patch> }
+        } else {
+            quote! { ::std::option::Option<#ty> }
+        }
+    }
+
+    /// Bound required on the field type for the generated implementation.
+    fn bound(&self) -> WherePredicate {
+        let ty = &self.field.ty;
+        if self.nested {
+            parse_quote! { #ty: ::remoc::rch::watch::Diff }
+        } else {
+            parse_quote! { #ty: ::std::cmp::PartialEq + ::std::clone::Clone + ::remoc::RemoteSend }
+        }
+    }
+
+    /// Expression computing the field patch.
+    fn diff_expr(&self) -> TokenStream {
+        let member = &self.member;
+        if self.nested {
+            quote! { ::remoc::rch::watch::Diff::diff(&self.#member, &new.#member) }
+        } else {
+            quote! {
+                if self.#member != new.#member {
+                    ::std::option::Option::Some(::std::clone::Clone::clone(&new.#member))
+                } else {
+                    ::std::option::Option::None
+                }
+            }
+        }
+    }
+
+    /// Statement applying the field patch.
+    fn apply_stmt(&self) -> TokenStream {
+        let member = &self.member;
+        if self.nested {
+            quote! {
+                if let ::std::option::Option::Some(field) = patch.#member {
+                    ::remoc::rch::watch::Diff::apply(&mut self.#member, field);
+                }
+            }
+        } else {
+            quote! {
+                if let ::std::option::Option::Some(field) = patch.#member {
+                    self.#member = field;
+                }
+            }
+        }
+    }
+}
+
+/// Generates the patch type and `Diff` implementation for a struct.
+pub fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let DeriveInput { vis, ident, generics, data, .. } = &input;
+
+    let Data::Struct(data) = data else {
+        return Err(syn::Error::new(input.span(), "Diff can only be derived for structs"));
+    };
+
+    let fields = data
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| DiffField::parse(idx, field))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let patch_ident = format_ident!("{}Patch", ident);
+    let patch_doc = format!("Patch for [`{ident}`] generated by `#[derive(Diff)]`.");
+
+    // Field types must be bounded if they may depend on generic parameters.
+    let mut impl_generics = generics.clone();
+    if !generics.params.is_empty() {
+        let where_clause = impl_generics.make_where_clause();
+        for field in &fields {
+            where_clause.predicates.push(field.bound());
+        }
+    }
+    let (impl_generics_impl, ty_generics, where_clause) = impl_generics.split_for_impl();
+    let patch_where = &impl_generics.where_clause;
+
+    let patch_fields: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            let vis = &field.field.vis;
+            let ty = field.patch_ty();
+            match &field.field.ident {
+                Some(ident) => quote! { #vis #ident: #ty },
+                None => quote! { #vis #ty },
+            }
+        })
+        .collect();
+    let patch_def = match &data.fields {
+        Fields::Named(_) => quote! { #vis struct #patch_ident #generics #patch_where { #(#patch_fields),* } },
+        Fields::Unnamed(_) => quote! { #vis struct #patch_ident #generics ( #(#patch_fields),* ) #patch_where; },
+        Fields::Unit => quote! { #vis struct #patch_ident #generics #patch_where; },
+    };
+
+    let members: Vec<_> = fields.iter().map(|field| &field.member).collect();
+    let diff_exprs: Vec<_> = fields.iter().map(|field| field.diff_expr()).collect();
+    let apply_stmts: Vec<_> = fields.iter().map(|field| field.apply_stmt()).collect();
+
+    Ok(quote! {
+        #[doc = #patch_doc]
+        #[derive(::remoc::_serde::Serialize, ::remoc::_serde::Deserialize)]
+        #[serde(crate = "::remoc::_serde")]
+        #patch_def
+
+        impl #impl_generics_impl ::remoc::rch::watch::Diff for #ident #ty_generics #where_clause {
+            type Patch = #patch_ident #ty_generics;
+
+            #[allow(unused_variables)]
+            fn diff(&self, new: &Self) -> ::std::option::Option<Self::Patch> {
+                let patch = #patch_ident { #(#members: #diff_exprs),* };
+                if true #(&& patch.#members.is_none())* {
+                    ::std::option::Option::None
+                } else {
+                    ::std::option::Option::Some(patch)
+                }
+            }
+
+            #[allow(unused_variables)]
+            fn apply(&mut self, patch: Self::Patch) {
+                #(#apply_stmts)*
+            }
+        }
+    })
+}
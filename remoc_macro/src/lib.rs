@@ -3,8 +3,9 @@
 //! Procedural macros for Remoc.
 
 use quote::quote;
-use syn::{meta, parse_macro_input};
+use syn::{DeriveInput, meta, parse_macro_input};
 
+mod diff;
 mod method;
 mod trait_def;
 mod util;
@@ -37,3 +38,12 @@ pub fn remote(args: proc_macro::TokenStream, input: proc_macro::TokenStream) ->
 
     output
 }
+
+#[proc_macro_derive(Diff, attributes(diff))]
+pub fn derive_diff(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match diff::derive(input) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}